@@ -1,3 +1,5 @@
+use crate::token::token::Span;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Identifier(pub String);
 
@@ -7,6 +9,15 @@ pub enum Prefix {
     Not,
 }
 
+impl std::fmt::Display for Prefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Prefix::Minus => write!(f, "-"),
+            Prefix::Not => write!(f, "!"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Infix {
     Plus,
@@ -37,10 +48,94 @@ impl std::fmt::Display for Infix {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     Let(Identifier, Expression),
+    /// `const x = e;` binds `x` so it can't be rebound in the same scope.
+    Const(Identifier, Expression),
+    /// `let [a, b] = arr;` binds each name to the matching array element.
+    DestructureArray(Vec<Identifier>, Expression),
+    /// `x = e;` rebinds an existing, non-constant `x` in the nearest scope
+    /// that has it.
+    Assign(Identifier, Expression),
     Return(Expression),
+    /// Leaves the innermost enclosing loop.
+    Break,
+    /// Skips to the next condition check of the innermost enclosing loop.
+    Continue,
     Expression(Expression),
 }
 
+impl std::fmt::Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Statement::Let(Identifier(ref name), ref value) => write!(f, "let {name} = {value};"),
+            Statement::Const(Identifier(ref name), ref value) => {
+                write!(f, "const {name} = {value};")
+            }
+            Statement::DestructureArray(ref names, ref value) => {
+                write!(f, "let [")?;
+                fmt_list(f, names)?;
+                write!(f, "] = {value};")
+            }
+            Statement::Assign(Identifier(ref name), ref value) => write!(f, "{name} = {value};"),
+            Statement::Return(ref value) => write!(f, "return {value};"),
+            Statement::Break => write!(f, "break;"),
+            Statement::Continue => write!(f, "continue;"),
+            Statement::Expression(ref expression) => write!(f, "{expression};"),
+        }
+    }
+}
+
+/// Writes `statements` as a braced block, e.g. `{ let x = 1; x; }`.
+pub fn fmt_block(f: &mut std::fmt::Formatter, statements: &[Statement]) -> std::fmt::Result {
+    write!(f, "{{")?;
+
+    for statement in statements {
+        write!(f, " {statement}")?;
+    }
+
+    write!(f, " }}")
+}
+
+/// Writes a float so that it reads back as a float rather than an integer,
+/// e.g. `4.0` instead of `4`.
+pub fn fmt_float(f: &mut std::fmt::Formatter, value: f64) -> std::fmt::Result {
+    if value.is_finite() && value.fract() == 0.0 {
+        write!(f, "{value:.1}")
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+/// Writes a string as a Monkey string literal, escaping what the lexer
+/// would otherwise read differently, e.g. `"say \"hi\"\n"`.
+pub fn fmt_str(f: &mut std::fmt::Formatter, value: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            ch if ch.is_control() => write!(f, "\\u{{{:x}}}", ch as u32)?,
+            ch => write!(f, "{ch}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+fn fmt_list<T: std::fmt::Display>(f: &mut std::fmt::Formatter, items: &[T]) -> std::fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{item}")?;
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     Identifier(Identifier),
@@ -52,6 +147,10 @@ pub enum Expression {
         consequence: Vec<Statement>,
         alternative: Option<Vec<Statement>>,
     },
+    While {
+        condition: Box<Expression>,
+        body: Vec<Statement>,
+    },
     Function {
         parameters: Vec<Identifier>,
         body: Vec<Statement>,
@@ -60,15 +159,247 @@ pub enum Expression {
         function: Box<Expression>,
         arguments: Vec<Expression>,
     },
+    Index(Box<Expression>, Box<Expression>),
+}
+
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Prefix, infix and index expressions are fully parenthesized so that the
+/// output parses back into the same tree.
+impl std::fmt::Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Expression::Identifier(ref identifier) => write!(f, "{identifier}"),
+            Expression::Literal(ref literal) => write!(f, "{literal}"),
+            Expression::Prefix(ref prefix, ref right) => write!(f, "({prefix}{right})"),
+            Expression::Infix(ref infix, ref left, ref right) => {
+                write!(f, "({left} {infix} {right})")
+            }
+            Expression::If {
+                ref condition,
+                ref consequence,
+                ref alternative,
+            } => {
+                write!(f, "if ({condition}) ")?;
+                fmt_block(f, consequence)?;
+
+                match alternative {
+                    Some(alternative) => {
+                        write!(f, " else ")?;
+                        fmt_block(f, alternative)
+                    }
+                    None => Ok(()),
+                }
+            }
+            Expression::While {
+                ref condition,
+                ref body,
+            } => {
+                write!(f, "while ({condition}) ")?;
+                fmt_block(f, body)
+            }
+            Expression::Function {
+                ref parameters,
+                ref body,
+            } => {
+                write!(f, "fn(")?;
+                fmt_list(f, parameters)?;
+                write!(f, ") ")?;
+                fmt_block(f, body)
+            }
+            Expression::Call {
+                ref function,
+                ref arguments,
+            } => {
+                write!(f, "{function}(")?;
+                fmt_list(f, arguments)?;
+                write!(f, ")")
+            }
+            Expression::Index(ref left, ref index) => write!(f, "({left}[{index}])"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     Int(i64),
+    Float(f64),
     Bool(bool),
+    Str(String),
+    Array(Vec<Expression>),
+    Hash(Vec<(Expression, Expression)>),
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Literal::Int(value) => write!(f, "{value}"),
+            Literal::Float(value) => fmt_float(f, value),
+            Literal::Bool(value) => write!(f, "{value}"),
+            Literal::Str(ref value) => fmt_str(f, value),
+            Literal::Array(ref elements) => {
+                write!(f, "[")?;
+                fmt_list(f, elements)?;
+                write!(f, "]")
+            }
+            Literal::Hash(ref pairs) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{key}: {value}")?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// The statements of a parsed source, in order.
+#[derive(Clone, Default, PartialEq)]
+pub struct Program(Vec<Statement>);
+
+/// Debugs as the bare list of statements, the way it did when `Program` was
+/// a `Vec`, so snapshots don't need a wrapper around every program.
+impl std::fmt::Debug for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Program {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Statement> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[Statement] {
+        &self.0
+    }
+
+    pub(crate) fn push(&mut self, statement: Statement) {
+        self.0.push(statement);
+    }
+}
+
+impl From<Vec<Statement>> for Program {
+    fn from(statements: Vec<Statement>) -> Self {
+        Self(statements)
+    }
+}
+
+impl From<Program> for Vec<Statement> {
+    fn from(program: Program) -> Self {
+        program.0
+    }
+}
+
+impl FromIterator<Statement> for Program {
+    fn from_iter<I: IntoIterator<Item = Statement>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Program {
+    type Item = Statement;
+    type IntoIter = std::vec::IntoIter<Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Program {
+    type Item = &'a Statement;
+    type IntoIter = std::slice::Iter<'a, Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl PartialEq<Vec<Statement>> for Program {
+    fn eq(&self, other: &Vec<Statement>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Program> for Vec<Statement> {
+    fn eq(&self, other: &Program) -> bool {
+        *self == other.0
+    }
+}
+
+/// A node together with the bytes of source it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+/// What `Parser::parse_program_spanned` gives back: every top-level
+/// statement with its span, plus the span of every infix expression
+/// anywhere in the program, in the order their right operand finished
+/// parsing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpannedProgram {
+    pub statements: Vec<Spanned<Statement>>,
+    pub infix_spans: Vec<Span>,
+}
+
+impl SpannedProgram {
+    /// Drops the spans, leaving what `parse_program` would have returned.
+    pub fn into_program(self) -> Program {
+        let mut program = Program::new();
+
+        for statement in self.statements {
+            program.push(statement.node);
+        }
+
+        program
+    }
 }
 
-pub type Program = Vec<Statement>;
+/// Displays a program as Monkey source, one statement per line, that parses
+/// back into the same program.
+pub struct MonkeyProgram<'a>(pub &'a [Statement]);
+
+impl std::fmt::Display for MonkeyProgram<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, statement) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{statement}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn to_monkey_source(program: &[Statement]) -> String {
+    MonkeyProgram(program).to_string()
+}
 
 #[derive(PartialEq, PartialOrd)]
 pub enum Precedence {
@@ -79,4 +410,100 @@ pub enum Precedence {
     Product,     // *
     Prefix,      // -X or !X
     Call,        // myFunction(X)
+    Index,       // array[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::ast::{
+        to_monkey_source, Expression, Identifier, MonkeyProgram, Program, Statement,
+    };
+    use crate::lexer::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_monkey_program_display() {
+        let input = "let x = 5;\nlet add = fn(a, b) { a + b }; if (add(x, 1) > 5) { x } else { [x, {1: 2}] }";
+        let program = Parser::new(Lexer::new(input)).parse_program();
+        let source = to_monkey_source(program.as_slice());
+
+        assert_eq!(
+            "let x = 5;\nlet add = fn(a, b) { (a + b); };\nif ((add(x, 1) > 5)) { x; } else { [x, {1: 2}]; };",
+            source
+        );
+        assert_eq!(source, format!("{}", MonkeyProgram(program.as_slice())));
+        assert_eq!(program, Parser::new(Lexer::new(&source)).parse_program());
+        assert_eq!("", to_monkey_source(&[]));
+    }
+
+    #[test]
+    fn test_program() {
+        let statements = vec![
+            Statement::Expression(Expression::Identifier(Identifier(String::from("x")))),
+            Statement::Break,
+        ];
+        let program = Program::from(statements.clone());
+
+        assert_eq!(2, program.len());
+        assert!(!program.is_empty());
+        assert!(Program::new().is_empty());
+        assert_eq!(program, statements);
+        assert_eq!(statements, program);
+        assert_eq!(program, statements.iter().cloned().collect::<Program>());
+        assert!(program.iter().eq(&statements));
+        assert_eq!(statements.as_slice(), program.as_slice());
+
+        let mut borrowed = Vec::new();
+        for statement in &program {
+            borrowed.push(statement.clone());
+        }
+        assert_eq!(statements, borrowed);
+        assert_eq!(statements, program.clone().into_iter().collect::<Vec<_>>());
+        assert_eq!(statements, Vec::from(program.clone()));
+        assert_eq!(format!("{statements:?}"), format!("{program:?}"));
+    }
+
+    #[test]
+    fn test_display() {
+        let tests = vec![
+            ("let x = 5;", "let x = 5;"),
+            ("return -a * b;", "return ((-a) * b);"),
+            ("a + b * c == !d", "((a + (b * c)) == (!d));"),
+            (
+                "if (x < y) { x } else { let z = y; z }",
+                "if ((x < y)) { x; } else { let z = y; z; };",
+            ),
+            (
+                "fn(x, y) { return x + y; }",
+                "fn(x, y) { return (x + y); };",
+            ),
+            ("add(1, [2, 3][0])", "add(1, ([2, 3][0]));"),
+            (r#"{"a": [1], 2: "b"}"#, r#"{"a": [1], 2: "b"};"#),
+            ("let [a, b] = pair", "let [a, b] = pair;"),
+            ("const PI = 3", "const PI = 3;"),
+            ("1.5 + 2.0", "(1.5 + 2.0);"),
+            (r#""a\"b\\c\u{7}""#, r#""a\"b\\c\u{7}";"#),
+            (r#"r"a\nb\""#, r#""a\\nb\\";"#),
+            (
+                "while (i < 3) { i = i + 1; if (i == 2) { continue; } break; }",
+                "while ((i < 3)) { i = (i + 1); if ((i == 2)) { continue; }; break; };",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let program = Parser::new(Lexer::new(input)).parse_program();
+            let rendered = program
+                .iter()
+                .map(|statement| statement.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            assert_eq!(expect, rendered);
+            assert_eq!(
+                program,
+                Parser::new(Lexer::new(&rendered)).parse_program(),
+                "{rendered} does not parse back into the same program",
+            );
+        }
+    }
 }
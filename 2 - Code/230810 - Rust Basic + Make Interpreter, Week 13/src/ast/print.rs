@@ -0,0 +1,404 @@
+use crate::ast::ast::{Expression, Identifier, Infix, Literal, Precedence, Program, Statement};
+
+/// Renders an AST as an indented tree, one node per line and two spaces per
+/// level, which is easier to read than the nested `Debug` output:
+///
+/// ```
+/// use monkey::ast::print::pretty_print;
+/// use monkey::lexer::lexer::Lexer;
+/// use monkey::parser::parser::Parser;
+///
+/// let program = Parser::new(Lexer::new("3 + 4")).parse().unwrap();
+///
+/// assert_eq!(
+///     "Infix(Plus)\n  Literal(Int(3))\n  Literal(Int(4))",
+///     pretty_print(&program)
+/// );
+/// ```
+///
+/// An operand that needed parentheses in the source, like the `1 + 2` in
+/// `(1 + 2) * 3`, is marked `(grouped)`.
+#[derive(Debug, Default)]
+pub struct Printer {
+    indent: usize,
+    output: String,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything printed so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    pub fn print_program(&mut self, program: &Program) {
+        for statement in program {
+            self.print_statement(statement);
+        }
+    }
+
+    pub fn print_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(Identifier(name), value) => {
+                self.line(&format!("Let({name})"));
+                self.nested(|printer| printer.print_expression(value, Precedence::Lowest));
+            }
+            Statement::Const(Identifier(name), value) => {
+                self.line(&format!("Const({name})"));
+                self.nested(|printer| printer.print_expression(value, Precedence::Lowest));
+            }
+            Statement::DestructureArray(names, value) => {
+                self.line(&format!("DestructureArray({})", names_list(names)));
+                self.nested(|printer| printer.print_expression(value, Precedence::Lowest));
+            }
+            Statement::Assign(Identifier(name), value) => {
+                self.line(&format!("Assign({name})"));
+                self.nested(|printer| printer.print_expression(value, Precedence::Lowest));
+            }
+            Statement::Return(value) => {
+                self.line("Return");
+                self.nested(|printer| printer.print_expression(value, Precedence::Lowest));
+            }
+            Statement::Break => self.line("Break"),
+            Statement::Continue => self.line("Continue"),
+            Statement::Expression(expression) => {
+                self.print_expression(expression, Precedence::Lowest)
+            }
+        }
+    }
+
+    /// Prints `expression` as an operand of something that binds as tightly
+    /// as `precedence`, marking it grouped if it binds more loosely.
+    pub fn print_expression(&mut self, expression: &Expression, precedence: Precedence) {
+        let grouped = if expression_precedence(expression) < precedence {
+            " (grouped)"
+        } else {
+            ""
+        };
+
+        match expression {
+            Expression::Identifier(Identifier(name)) => {
+                self.line(&format!("Identifier({name}){grouped}"))
+            }
+            Expression::Literal(literal) => self.print_literal(literal),
+            Expression::Prefix(prefix, right) => {
+                self.line(&format!("Prefix({prefix:?}){grouped}"));
+                self.nested(|printer| printer.print_expression(right, Precedence::Prefix));
+            }
+            Expression::Infix(infix, left, right) => {
+                self.line(&format!("Infix({infix:?}){grouped}"));
+                self.nested(|printer| {
+                    // Infix operators are left associative, so an operand on
+                    // the right at the same level needed parentheses too.
+                    printer.print_expression(left, infix_precedence(infix));
+                    printer.print_expression(right, tighter(infix_precedence(infix)));
+                });
+            }
+            Expression::If {
+                condition,
+                consequence,
+                alternative,
+            } => {
+                self.line("If");
+                self.nested(|printer| {
+                    printer.print_section("Condition", |printer| {
+                        printer.print_expression(condition, Precedence::Lowest)
+                    });
+                    printer.print_block("Consequence", consequence);
+
+                    if let Some(alternative) = alternative {
+                        printer.print_block("Alternative", alternative);
+                    }
+                });
+            }
+            Expression::While { condition, body } => {
+                self.line("While");
+                self.nested(|printer| {
+                    printer.print_section("Condition", |printer| {
+                        printer.print_expression(condition, Precedence::Lowest)
+                    });
+                    printer.print_block("Body", body);
+                });
+            }
+            Expression::Function { parameters, body } => {
+                self.line(&format!("Function({})", names_list(parameters)));
+                self.nested(|printer| {
+                    for statement in body {
+                        printer.print_statement(statement);
+                    }
+                });
+            }
+            Expression::Call {
+                function,
+                arguments,
+            } => {
+                self.line(&format!("Call{grouped}"));
+                self.nested(|printer| {
+                    printer.print_expression(function, Precedence::Call);
+
+                    for argument in arguments {
+                        printer.print_expression(argument, Precedence::Lowest);
+                    }
+                });
+            }
+            Expression::Index(left, index) => {
+                self.line(&format!("Index{grouped}"));
+                self.nested(|printer| {
+                    // Calls and indexes chain without parentheses.
+                    printer.print_expression(left, Precedence::Call);
+                    printer.print_expression(index, Precedence::Lowest);
+                });
+            }
+        }
+    }
+
+    fn print_literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::Int(value) => self.line(&format!("Literal(Int({value}))")),
+            Literal::Float(value) => self.line(&format!("Literal(Float({value:?}))")),
+            Literal::Bool(value) => self.line(&format!("Literal(Bool({value}))")),
+            Literal::Str(value) => self.line(&format!("Literal(Str({value:?}))")),
+            Literal::Array(elements) => {
+                self.line("Literal(Array)");
+                self.nested(|printer| {
+                    for element in elements {
+                        printer.print_expression(element, Precedence::Lowest);
+                    }
+                });
+            }
+            Literal::Hash(pairs) => {
+                self.line("Literal(Hash)");
+                self.nested(|printer| {
+                    for (key, value) in pairs {
+                        printer.print_section("Pair", |printer| {
+                            printer.print_expression(key, Precedence::Lowest);
+                            printer.print_expression(value, Precedence::Lowest);
+                        });
+                    }
+                });
+            }
+        }
+    }
+
+    fn print_block(&mut self, label: &str, statements: &[Statement]) {
+        self.print_section(label, |printer| {
+            for statement in statements {
+                printer.print_statement(statement);
+            }
+        });
+    }
+
+    fn print_section(&mut self, label: &str, body: impl FnOnce(&mut Self)) {
+        self.line(label);
+        self.nested(body);
+    }
+
+    fn nested(&mut self, body: impl FnOnce(&mut Self)) {
+        self.indent += 1;
+        body(self);
+        self.indent -= 1;
+    }
+
+    fn line(&mut self, text: &str) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        for _ in 0..self.indent {
+            self.output.push_str("  ");
+        }
+
+        self.output.push_str(text);
+    }
+}
+
+/// Renders `program` with a fresh `Printer`.
+pub fn pretty_print(program: &Program) -> String {
+    let mut printer = Printer::new();
+    printer.print_program(program);
+    printer.into_output()
+}
+
+fn names_list(names: &[Identifier]) -> String {
+    names
+        .iter()
+        .map(|Identifier(name)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How tightly `expression` binds; anything that isn't an operator is an
+/// atom and never needs grouping.
+fn expression_precedence(expression: &Expression) -> Precedence {
+    match expression {
+        Expression::Prefix(_, _) => Precedence::Prefix,
+        Expression::Infix(infix, _, _) => infix_precedence(infix),
+        Expression::Call { .. } => Precedence::Call,
+        _ => Precedence::Index,
+    }
+}
+
+fn infix_precedence(infix: &Infix) -> Precedence {
+    match infix {
+        Infix::Equal | Infix::NotEqual => Precedence::Equals,
+        Infix::LessThan | Infix::GreaterThan => Precedence::LessGreater,
+        Infix::Plus | Infix::Minus => Precedence::Sum,
+        Infix::Multiply | Infix::Divide => Precedence::Product,
+    }
+}
+
+/// The next level up from `precedence`.
+fn tighter(precedence: Precedence) -> Precedence {
+    match precedence {
+        Precedence::Lowest => Precedence::Equals,
+        Precedence::Equals => Precedence::LessGreater,
+        Precedence::LessGreater => Precedence::Sum,
+        Precedence::Sum => Precedence::Product,
+        Precedence::Product => Precedence::Prefix,
+        Precedence::Prefix => Precedence::Call,
+        Precedence::Call | Precedence::Index => Precedence::Index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::ast::{Expression, Literal, Precedence, Program};
+    use crate::ast::print::{pretty_print, Printer};
+    use crate::lexer::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    fn print(input: &str) -> String {
+        pretty_print(&Parser::new(Lexer::new(input)).parse().unwrap())
+    }
+
+    #[test]
+    fn test_nested_arithmetic() {
+        assert_eq!(
+            "Infix(Plus)\n  Literal(Int(3))\n  Literal(Int(4))",
+            print("3 + 4")
+        );
+        assert_eq!(
+            "\
+Infix(Minus)
+  Infix(Plus)
+    Literal(Int(1))
+    Infix(Multiply)
+      Infix(Plus) (grouped)
+        Identifier(a)
+        Literal(Int(2))
+      Prefix(Minus)
+        Identifier(b)
+  Infix(Minus) (grouped)
+    Literal(Int(4))
+    Infix(Divide)
+      Literal(Int(6))
+      Literal(Int(2))",
+            print("1 + (a + 2) * -b - (4 - 6 / 2)")
+        );
+    }
+
+    #[test]
+    fn test_grouping_marks() {
+        assert_eq!(
+            "Prefix(Not)\n  Infix(Equal) (grouped)\n    Identifier(a)\n    Identifier(b)",
+            print("!(a == b)")
+        );
+        assert_eq!(
+            "Index\n  Call\n    Identifier(f)\n  Literal(Int(0))",
+            print("f()[0]")
+        );
+        assert_eq!(
+            "Call\n  Prefix(Minus) (grouped)\n    Identifier(f)\n  Literal(Int(1))",
+            print("(-f)(1)")
+        );
+        // Left operands at the same level are never grouped.
+        assert_eq!(
+            "Infix(Minus)\n  Infix(Minus)\n    Identifier(a)\n    Identifier(b)\n  Identifier(c)",
+            print("(a - b) - c")
+        );
+    }
+
+    #[test]
+    fn test_statements() {
+        assert_eq!(
+            "\
+Let(add)
+  Function(a, b)
+    Return
+      Infix(Plus)
+        Identifier(a)
+        Identifier(b)
+Const(xs)
+  Literal(Array)
+    Literal(Float(1.5))
+    Literal(Str(\"two\"))
+DestructureArray(a, b)
+  Identifier(xs)
+Assign(a)
+  Call
+    Identifier(add)
+    Identifier(a)
+    Literal(Bool(true))",
+            print("let add = fn(a, b) { return a + b; }; const xs = [1.5, \"two\"]; let [a, b] = xs; a = add(a, true);")
+        );
+    }
+
+    #[test]
+    fn test_control_flow() {
+        assert_eq!(
+            "\
+While
+  Condition
+    Infix(LessThan)
+      Identifier(i)
+      Literal(Int(3))
+  Body
+    If
+      Condition
+        Identifier(done)
+      Consequence
+        Break
+      Alternative
+        Continue
+If
+  Condition
+    Literal(Bool(false))
+  Consequence",
+            print("while (i < 3) { if (done) { break; } else { continue; } } if (false) { }")
+        );
+    }
+
+    #[test]
+    fn test_hash_literal() {
+        assert_eq!(
+            "\
+Literal(Hash)
+  Pair
+    Literal(Str(\"a\"))
+    Literal(Int(1))
+  Pair
+    Literal(Int(2))
+    Index
+      Identifier(x)
+      Literal(Int(0))",
+            print("{\"a\": 1, 2: x[0]}")
+        );
+    }
+
+    #[test]
+    fn test_printer_accumulates() {
+        let mut printer = Printer::new();
+        printer.print_expression(&Expression::Literal(Literal::Int(1)), Precedence::Lowest);
+        printer.print_expression(&Expression::Literal(Literal::Int(2)), Precedence::Lowest);
+
+        assert_eq!("Literal(Int(1))\nLiteral(Int(2))", printer.output());
+        assert_eq!("", pretty_print(&Program::new()));
+    }
+}
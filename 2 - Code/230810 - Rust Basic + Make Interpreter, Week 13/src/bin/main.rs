@@ -1,43 +1,415 @@
 extern crate monkey;
 extern crate rustyline;
 
+use std::borrow::Cow;
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::{env, fs, io, process, thread};
 
+use monkey::ast::ast::{Program, SpannedProgram};
 use monkey::evaluator::environment::Environment;
 use monkey::evaluator::evaluator::Evaluator;
+use monkey::evaluator::object::Object;
+use monkey::interpreter::interpreter::MonkeyError;
+use monkey::interpreter::test_runner::{is_test_file, run_test_file, TestSummary};
 use monkey::lexer::lexer::Lexer;
-use monkey::parser::parser::Parser;
+use monkey::lints::lints::lint_spanned_program;
+use monkey::parser::parser::{ParseError, Parser};
+use monkey::repl::commands::{help_text, parse_command, Command};
+use monkey::repl::highlight::{color_enabled, highlight_line};
+use monkey::repl::session::SessionBindings;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::Editor;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+
+const PROMPT: &str = ">> ";
+const USAGE: &str = "usage: monkey [--keep-going] [--warnings] [--prelude PRELUDE] [FILE]
+       monkey --test DIR";
+/// Stack size of the thread that programs run on.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+/// Names a prelude to load when `--prelude` isn't given.
+const PRELUDE_VAR: &str = "MONKEY_PRELUDE";
+
+/// Colors the line being edited; does nothing when color is disabled.
+struct MonkeyHelper {
+    color: bool,
+}
+
+impl Completer for MonkeyHelper {
+    type Candidate = String;
+}
+
+impl Hinter for MonkeyHelper {
+    type Hint = String;
+}
+
+impl Validator for MonkeyHelper {}
+
+impl Highlighter for MonkeyHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if self.color {
+            Cow::Owned(highlight_line(line))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        self.color
+    }
+}
+
+impl Helper for MonkeyHelper {}
 
 fn main() {
-    let mut rl = Editor::<()>::new();
-    let environment = Environment::new();
-    let mut evaluator = Evaluator::new(Rc::new(RefCell::new(environment)));
+    // Calls recurse on the native stack, so give them room for
+    // `DEFAULT_MAX_CALL_DEPTH` frames even in a debug build.
+    let session = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("failed to start the interpreter thread");
+
+    if session.join().is_err() {
+        process::exit(101);
+    }
+}
+
+fn run() {
+    let mut keep_going = false;
+    let mut warnings = false;
+    let mut prelude = None;
+    let mut path = None;
+    let mut test_dir = None;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--keep-going" => keep_going = true,
+            "--warnings" => warnings = true,
+            "--prelude" if prelude.is_none() => match args.next() {
+                Some(prelude_path) => prelude = Some(prelude_path),
+                None => {
+                    eprintln!("--prelude needs a file\n{USAGE}");
+                    process::exit(2);
+                }
+            },
+            "--test" if test_dir.is_none() => match args.next() {
+                Some(dir) => test_dir = Some(dir),
+                None => {
+                    eprintln!("--test needs a directory\n{USAGE}");
+                    process::exit(2);
+                }
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("{USAGE}");
+                process::exit(2);
+            }
+        }
+    }
+
+    if let Some(dir) = test_dir {
+        if keep_going || warnings || prelude.is_some() || path.is_some() {
+            eprintln!("--test runs each file on its own\n{USAGE}");
+            process::exit(2);
+        }
+
+        process::exit(run_tests(Path::new(&dir)));
+    }
+
+    if path.is_none() && keep_going {
+        eprintln!("--keep-going only applies when running a file\n{USAGE}");
+        process::exit(2);
+    }
+
+    let prelude = prelude.or_else(|| env::var(PRELUDE_VAR).ok());
+    let mut evaluator = match new_evaluator(prelude.as_deref()) {
+        Some(evaluator) => evaluator,
+        None => process::exit(1),
+    };
+
+    match path {
+        Some(path) => process::exit(run_file(&mut evaluator, &path, keep_going, warnings)),
+        None => repl(evaluator, prelude, warnings),
+    }
+}
+
+/// A fresh evaluator, with the prelude in `path` loaded if there is one.
+fn new_evaluator(prelude: Option<&str>) -> Option<Evaluator> {
+    let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+
+    if let Some(prelude) = prelude {
+        if !load_prelude(&mut evaluator, prelude) {
+            return None;
+        }
+    }
+
+    Some(evaluator)
+}
+
+/// Loads the prelude in `path`, reporting errors on stderr, and returns
+/// whether it loaded.
+fn load_prelude(evaluator: &mut Evaluator, path: &str) -> bool {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            return false;
+        }
+    };
+
+    match evaluator.load_prelude(&source) {
+        Ok(()) => true,
+        Err(MonkeyError::Parse(errors)) => {
+            print_parse_errors(path, &errors);
+            false
+        }
+        Err(MonkeyError::Runtime(msg)) => {
+            eprintln!("{path}: {}", Object::Error(msg));
+
+            for frame in evaluator.last_trace() {
+                eprintln!("    {frame}");
+            }
+
+            false
+        }
+    }
+}
+
+/// Reads and parses `path`, reporting errors on stderr.
+fn parse_file(path: &str) -> Option<Program> {
+    parse_file_spanned(path).map(|(_, program)| program.into_program())
+}
+
+/// Like `parse_file`, but keeps the spans, and the source they index into.
+fn parse_file_spanned(path: &str) -> Option<(String, SpannedProgram)> {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            return None;
+        }
+    };
+
+    match Parser::new(Lexer::new(&source)).parse_spanned() {
+        Ok(program) => Some((source, program)),
+        Err(failure) => {
+            print_parse_errors(path, &failure.errors);
+            None
+        }
+    }
+}
+
+fn print_parse_errors(path: &str, errors: &[ParseError]) {
+    for err in errors {
+        let position = err.position();
+        eprintln!("{path}:{}:{}: {err}", position.line, position.column);
+    }
+}
+
+/// Runs the program in `path`, reporting errors on stderr, and returns the
+/// exit code. With `keep_going`, statements after a failing one still run.
+/// With `warnings`, lint warnings are reported first; they never change the
+/// exit code.
+fn run_file(evaluator: &mut Evaluator, path: &str, keep_going: bool, warnings: bool) -> i32 {
+    let program = match parse_file_spanned(path) {
+        Some((source, program)) => {
+            if warnings {
+                for warning in lint_spanned_program(&program) {
+                    let position = warning.span().unwrap_or_default().start_position(&source);
+                    eprintln!(
+                        "{path}:{}:{}: warning: {warning}",
+                        position.line, position.column
+                    );
+                }
+            }
+
+            program.into_program()
+        }
+        None => return 1,
+    };
+
+    let errors = if keep_going {
+        evaluator.eval_collect_errors(program).1
+    } else {
+        match evaluator.eval(program) {
+            Some(error @ Object::Error(_)) => vec![error],
+            _ => Vec::new(),
+        }
+    };
+
+    for error in &errors {
+        eprintln!("{path}: {error}");
+    }
+
+    if !keep_going {
+        for frame in evaluator.last_trace() {
+            eprintln!("    {frame}");
+        }
+    }
+
+    i32::from(!errors.is_empty())
+}
+
+/// Runs every test file under `dir`, in path order, printing a line for each
+/// and a tally, and returns the exit code.
+fn run_tests(dir: &Path) -> i32 {
+    let mut paths = Vec::new();
+
+    if let Err(err) = find_test_files(dir, &mut paths) {
+        eprintln!("{}: {err}", dir.display());
+        return 1;
+    }
+
+    paths.sort();
+
+    let mut summary = TestSummary::new();
+    for path in &paths {
+        summary.record(&path.display().to_string(), run_test_file(path));
+    }
+
+    println!("{summary}");
+
+    i32::from(!summary.is_success())
+}
+
+/// Adds the test files in `dir` and its subdirectories to `paths`.
+fn find_test_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_test_files(&path, paths)?;
+        } else if is_test_file(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints what a REPL input evaluated to, binding it for later inputs.
+fn print_result(evaluator: &Evaluator, session: &mut SessionBindings, evaluated: Object) {
+    println!("{evaluated:#}");
+    session.record(&evaluator.environment(), &evaluated);
+
+    if let Object::Error(_) = evaluated {
+        for frame in evaluator.last_trace() {
+            println!("    {frame}");
+        }
+    }
+}
+
+fn repl(mut evaluator: Evaluator, prelude: Option<String>, mut warnings: bool) {
+    let mut rl = Editor::<MonkeyHelper>::new();
+    rl.set_helper(Some(MonkeyHelper {
+        color: color_enabled(),
+    }));
+    let mut session = SessionBindings::new();
+    // Every input that was evaluated, for `:save`.
+    let mut inputs: Vec<String> = Vec::new();
 
     println!("Hello! This is the Monkey programming language!");
-    println!("Feel free to type in commands\n");
+    println!("Feel free to type in commands (:help lists the REPL's own)\n");
 
     loop {
-        match rl.readline(">> ") {
+        match rl.readline(PROMPT) {
             Ok(line) => {
                 rl.add_history_entry(&line);
 
-                let mut parser = Parser::new(Lexer::new(&line));
-                let program = parser.parse_program();
-                let errors = parser.get_errors();
+                if let Some(command) = parse_command(&line) {
+                    match command {
+                        Ok(Command::Help) => print!("{}", help_text()),
+                        Ok(Command::Quit) => {
+                            println!("Bye :)");
+                            break;
+                        }
+                        Ok(Command::Reset) => match new_evaluator(prelude.as_deref()) {
+                            Some(fresh) => {
+                                evaluator = fresh;
+                                session = SessionBindings::new();
+                                inputs.clear();
+                            }
+                            None => println!("the prelude failed to load; nothing was reset"),
+                        },
+                        Ok(Command::Env) => {
+                            for (name, value) in evaluator.inspect_environment() {
+                                if !matches!(value, Object::Builtin(_)) {
+                                    println!("{name} = {value}");
+                                }
+                            }
+                        }
+                        Ok(Command::Trace) => {
+                            for frame in evaluator.last_trace() {
+                                println!("    {frame}");
+                            }
+                        }
+                        Ok(Command::Type(source)) => {
+                            match Parser::new(Lexer::new(&source)).parse() {
+                                Ok(program) => match evaluator.eval_scoped(program) {
+                                    Some(error @ Object::Error(_)) => println!("{error}"),
+                                    Some(object) => println!("{}", object.type_name()),
+                                    None => println!("{}", Object::Null.type_name()),
+                                },
+                                Err(failure) => println!("{failure}"),
+                            }
+                        }
+                        Ok(Command::Load(path)) => {
+                            if let Some(program) = parse_file(&path) {
+                                if let Some(evaluated) = evaluator.eval(program) {
+                                    print_result(&evaluator, &mut session, evaluated);
+                                }
+                            }
+                        }
+                        Ok(Command::Save(path)) => {
+                            let source: String =
+                                inputs.iter().map(|input| format!("{input}\n")).collect();
 
-                if errors.len() > 0 {
-                    for err in errors {
-                        println!("{err}");
+                            if let Err(err) = fs::write(&path, source) {
+                                println!("{path}: {err}");
+                            }
+                        }
+                        Ok(Command::Color(color)) => {
+                            if let Some(helper) = rl.helper_mut() {
+                                helper.color = color;
+                            }
+                        }
+                        Ok(Command::Lint(lint)) => warnings = lint,
+                        Err(err) => println!("{err}"),
                     }
 
                     continue;
                 }
 
-                if let Some(evaluated) = evaluator.eval(program) {
-                    println!("{evaluated}\n");
+                let program = match Parser::new(Lexer::new(&line)).parse_spanned() {
+                    Ok(program) => program,
+                    Err(failure) => {
+                        for err in failure.errors {
+                            // Point at the error under the input echoed after the ">> " prompt.
+                            let column = PROMPT.chars().count() + err.position().column;
+                            println!("{:>column$} {err}", "^");
+                        }
+
+                        continue;
+                    }
+                };
+
+                if warnings {
+                    for warning in lint_spanned_program(&program) {
+                        let position = warning.span().unwrap_or_default().start_position(&line);
+                        let column = PROMPT.chars().count() + position.column;
+                        println!("{:>column$} warning: {warning}", "^");
+                    }
+                }
+
+                inputs.push(line);
+
+                if let Some(evaluated) = evaluator.eval(program.into_program()) {
+                    print_result(&evaluator, &mut session, evaluated);
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::evaluator::evaluator::Evaluator;
+use crate::evaluator::object::Object;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Builtin {
+    Len,
+    Puts,
+    Sort,
+    Format,
+    Assert,
+    Type,
+    Inspect,
+    IsError,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 8] = [
+        Builtin::Len,
+        Builtin::Puts,
+        Builtin::Sort,
+        Builtin::Format,
+        Builtin::Assert,
+        Builtin::Type,
+        Builtin::Inspect,
+        Builtin::IsError,
+    ];
+
+    pub fn lookup(name: &str) -> Option<Builtin> {
+        Self::ALL.into_iter().find(|builtin| builtin.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Builtin::Len => "len",
+            Builtin::Puts => "puts",
+            Builtin::Sort => "sort",
+            Builtin::Format => "format",
+            Builtin::Assert => "assert",
+            Builtin::Type => "type",
+            Builtin::Inspect => "inspect",
+            Builtin::IsError => "is_error",
+        }
+    }
+
+    /// Whether the builtin is called with error arguments as they are.
+    /// Every other call stops at the first argument that evaluates to an
+    /// error and gives back that error instead.
+    pub fn accepts_errors(&self) -> bool {
+        matches!(*self, Builtin::IsError)
+    }
+
+    pub fn call(&self, evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        match *self {
+            Builtin::Len => Self::len(evaluator, arguments),
+            Builtin::Puts => Self::puts(evaluator, arguments),
+            Builtin::Sort => Self::sort(evaluator, arguments),
+            Builtin::Format => Self::format(evaluator, arguments),
+            Builtin::Assert => Self::assert(evaluator, arguments),
+            Builtin::Type => Self::type_of(evaluator, arguments),
+            Builtin::Inspect => Self::inspect(evaluator, arguments),
+            Builtin::IsError => Self::is_error(evaluator, arguments),
+        }
+    }
+
+    /// Counts an array's elements or a string's chars, not its bytes.
+    fn len(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        match arguments.as_slice() {
+            [Object::Array(objects)] => Object::Int(objects.len() as i64),
+            [Object::Str(value)] => Object::Int(value.chars().count() as i64),
+            [object] => evaluator.error(format!("argument to `len` not supported, got {object}")),
+            _ => evaluator.error(format!(
+                "wrong number of arguments: 1 expected but {} given",
+                arguments.len()
+            )),
+        }
+    }
+
+    /// Returns a sorted copy of an array whose elements can all be compared
+    /// with each other.
+    fn sort(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        let mut objects = match arguments.as_slice() {
+            [Object::Array(objects)] => objects.clone(),
+            [object] => {
+                return evaluator.error(format!("argument to `sort` not supported, got {object}"))
+            }
+            _ => {
+                return evaluator.error(format!(
+                    "wrong number of arguments: 1 expected but {} given",
+                    arguments.len()
+                ))
+            }
+        };
+
+        // Only objects of the same kind compare, so checking each one against
+        // the first is enough; that also catches NaN, which equals nothing.
+        if let Some(first) = objects.first() {
+            if let Some(other) = objects
+                .iter()
+                .find(|other| first.partial_cmp(other).is_none())
+            {
+                return evaluator.error(format!(
+                    "argument to `sort` not supported, cannot compare {} with {}",
+                    first.type_name(),
+                    other.type_name()
+                ));
+            }
+        }
+
+        objects.sort_by(|left, right| left.partial_cmp(right).unwrap_or(Ordering::Equal));
+
+        Object::Array(objects)
+    }
+
+    /// Replaces each `{}` in the format string with the next argument, as
+    /// it would be printed. `{{` and `}}` stand for literal braces.
+    fn format(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        let (template, values) = match arguments.split_first() {
+            Some((Object::Str(template), values)) => (template, values),
+            Some((object, _)) => {
+                return evaluator
+                    .error(format!("argument to `format` must be STRING, got {object}"))
+            }
+            None => {
+                return evaluator.error(String::from(
+                    "wrong number of arguments: at least 1 expected but 0 given",
+                ))
+            }
+        };
+
+        let mut result = String::new();
+        let mut values = values.iter();
+        let mut placeholders = 0;
+        let mut chars = template.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match (ch, chars.peek()) {
+                ('{', Some('}')) => {
+                    chars.next();
+                    placeholders += 1;
+
+                    if let Some(value) = values.next() {
+                        result.push_str(&value.to_string());
+                    }
+                }
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    result.push(ch);
+                }
+                ('{' | '}', _) => {
+                    return evaluator.error(format!("invalid format string: unmatched `{ch}`"))
+                }
+                _ => result.push(ch),
+            }
+        }
+
+        let given = arguments.len() - 1;
+        if placeholders != given {
+            return evaluator.error(format!(
+                "format string has {placeholders} placeholders but {given} values given"
+            ));
+        }
+
+        Object::Str(result)
+    }
+
+    /// Gives `null` if the condition is truthy and an error carrying the
+    /// message, or a default one, otherwise.
+    fn assert(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        let (condition, message) = match arguments.as_slice() {
+            [condition] => (
+                condition,
+                format!("expected a truthy value, got {condition}"),
+            ),
+            [condition, message] => (condition, message.to_string()),
+            _ => {
+                return evaluator.error(format!(
+                    "wrong number of arguments: 1 or 2 expected but {} given",
+                    arguments.len()
+                ))
+            }
+        };
+
+        if Evaluator::is_truthy(condition.clone()) {
+            Object::Null
+        } else {
+            evaluator.error(format!("assertion failed: {message}"))
+        }
+    }
+
+    /// The argument's type name as a string, e.g. `"INTEGER"`.
+    fn type_of(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        match arguments.as_slice() {
+            [object] => Object::Str(String::from(object.type_name())),
+            _ => evaluator.error(format!(
+                "wrong number of arguments: 1 expected but {} given",
+                arguments.len()
+            )),
+        }
+    }
+
+    /// The argument as the REPL would show it, with strings quoted, as a
+    /// string.
+    fn inspect(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        match arguments.as_slice() {
+            [object] => Object::Str(format!("{object:#}")),
+            _ => evaluator.error(format!(
+                "wrong number of arguments: 1 expected but {} given",
+                arguments.len()
+            )),
+        }
+    }
+
+    /// Whether the argument is an error. Seeing one here handles it, so it
+    /// no longer counts as the error `last_trace` describes.
+    fn is_error(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        match arguments.as_slice() {
+            [Object::Error(_)] => {
+                evaluator.clear_trace();
+                Object::Bool(true)
+            }
+            [_] => Object::Bool(false),
+            _ => evaluator.error(format!(
+                "wrong number of arguments: 1 expected but {} given",
+                arguments.len()
+            )),
+        }
+    }
+
+    fn puts(evaluator: &mut Evaluator, arguments: Vec<Object>) -> Object {
+        let result = {
+            let output = evaluator.output();
+            let mut output = output.borrow_mut();
+
+            arguments
+                .iter()
+                .try_for_each(|argument| writeln!(output, "{argument}"))
+                .and_then(|_| output.flush())
+        };
+
+        match result {
+            Ok(()) => Object::Null,
+            Err(err) => evaluator.error(format!("puts: failed to write output: {err}")),
+        }
+    }
+}
+
+impl fmt::Display for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "builtin function {}", self.name())
+    }
+}
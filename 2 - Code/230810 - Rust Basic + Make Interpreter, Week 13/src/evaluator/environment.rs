@@ -1,10 +1,30 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::evaluator::object::Object;
 
+#[derive(Debug, PartialEq)]
+struct Binding {
+    value: Object,
+    constant: bool,
+}
+
+/// Why `Environment::assign` couldn't rebind a name.
+#[derive(Debug, PartialEq)]
+pub enum AssignError {
+    Undefined,
+    Constant,
+    Builtin,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Environment {
-    store: HashMap<String, Object>,
+    store: HashMap<String, Binding>,
+    builtins: HashSet<String>,
+    allow_builtin_shadowing: bool,
     outer: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -12,6 +32,8 @@ impl Environment {
     pub fn new() -> Self {
         Environment {
             store: HashMap::new(),
+            builtins: HashSet::new(),
+            allow_builtin_shadowing: false,
             outer: None,
         }
     }
@@ -19,21 +41,236 @@ impl Environment {
     pub fn new_with_outer(outer: Rc<RefCell<Environment>>) -> Self {
         Environment {
             store: HashMap::new(),
+            builtins: HashSet::new(),
+            allow_builtin_shadowing: false,
             outer: Some(outer),
         }
     }
 
-    pub fn get(&mut self, name: String) -> Option<Object> {
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    pub fn get(&self, name: String) -> Option<Object> {
         match self.store.get(&name) {
-            Some(value) => Some(value.clone()),
+            Some(binding) => Some(binding.value.clone()),
             None => match self.outer {
-                Some(ref outer) => outer.borrow_mut().get(name),
+                Some(ref outer) => outer.borrow().get(name),
                 None => None,
             },
         }
     }
 
-    pub fn set(&mut self, name: String, value: &Object) {
-        self.store.insert(name, value.clone());
+    /// Every name visible from this scope, including outer ones, in
+    /// alphabetical order and each only once.
+    pub fn all_keys(&self) -> Vec<String> {
+        let mut keys = BTreeSet::new();
+        self.collect_keys(&mut keys);
+
+        keys.into_iter().collect()
+    }
+
+    fn collect_keys(&self, keys: &mut BTreeSet<String>) {
+        keys.extend(self.store.keys().cloned());
+
+        if let Some(ref outer) = self.outer {
+            outer.borrow().collect_keys(keys);
+        }
+    }
+
+    pub fn define(&mut self, name: String, value: &Object) {
+        self.bind(name, value.clone(), false);
+    }
+
+    /// Binds a constant in this scope. The evaluator refuses to rebind it in
+    /// the same scope, while nested scopes may still shadow it.
+    pub fn define_const(&mut self, name: String, value: &Object) {
+        self.bind(name, value.clone(), true);
+    }
+
+    /// Whether `name` is bound to a constant in this scope, ignoring outer ones.
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.store.get(name).is_some_and(|binding| binding.constant)
+    }
+
+    /// Replaces the value of `name` in the nearest scope that binds it.
+    /// Builtins are as protected from assignment as they are from `let`.
+    pub fn assign(&mut self, name: &str, value: &Object) -> Result<(), AssignError> {
+        if self.is_protected_builtin(name) {
+            return Err(AssignError::Builtin);
+        }
+
+        self.assign_unchecked(name, value)
+    }
+
+    fn assign_unchecked(&mut self, name: &str, value: &Object) -> Result<(), AssignError> {
+        match self.store.get_mut(name) {
+            Some(binding) if binding.constant => Err(AssignError::Constant),
+            Some(binding) => {
+                binding.value = value.clone();
+                Ok(())
+            }
+            None => match self.outer {
+                Some(ref outer) => outer.borrow_mut().assign_unchecked(name, value),
+                None => Err(AssignError::Undefined),
+            },
+        }
+    }
+
+    fn bind(&mut self, name: String, value: Object, constant: bool) {
+        self.store.insert(name, Binding { value, constant });
+    }
+
+    /// Binds a builtin in this scope. Unless shadowing is allowed, a `let`
+    /// in this scope or any scope nested in it can't rebind the name.
+    pub fn define_builtin(&mut self, name: &str, object: Object) {
+        self.builtins.insert(String::from(name));
+        self.bind(String::from(name), object, false);
+    }
+
+    pub fn set_allow_builtin_shadowing(&mut self, allow: bool) {
+        self.allow_builtin_shadowing = allow;
+    }
+
+    /// Whether `let name = ...` would shadow a builtin that may not be shadowed.
+    pub fn is_protected_builtin(&self, name: &str) -> bool {
+        if self.builtins.contains(name) && !self.allow_builtin_shadowing {
+            return true;
+        }
+
+        match self.outer {
+            Some(ref outer) => outer.borrow().is_protected_builtin(name),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::evaluator::builtins::Builtin;
+    use crate::evaluator::environment::{AssignError, Environment};
+    use crate::evaluator::object::Object;
+
+    #[test]
+    fn test_new_environment_is_empty() {
+        let environment = Environment::new();
+
+        assert!(environment.is_empty());
+        assert_eq!(None, environment.get(String::from("len")));
+        assert!(!environment.is_protected_builtin("len"));
+    }
+
+    #[test]
+    fn test_define_builtin() {
+        let mut environment = Environment::new();
+        environment.define_builtin("len", Object::Builtin(Builtin::Len));
+
+        assert_eq!(
+            Some(Object::Builtin(Builtin::Len)),
+            environment.get(String::from("len"))
+        );
+        assert!(environment.is_protected_builtin("len"));
+
+        environment.set_allow_builtin_shadowing(true);
+        assert!(!environment.is_protected_builtin("len"));
+    }
+
+    #[test]
+    fn test_define_const() {
+        let mut environment = Environment::new();
+        environment.define(String::from("x"), &Object::Int(1));
+        environment.define_const(String::from("PI"), &Object::Int(3));
+
+        assert_eq!(Some(Object::Int(3)), environment.get(String::from("PI")));
+        assert!(environment.is_constant("PI"));
+        assert!(!environment.is_constant("x"));
+        assert!(!environment.is_constant("y"));
+    }
+
+    #[test]
+    fn test_constants_are_per_scope() {
+        let outer = Rc::new(RefCell::new(Environment::new()));
+        outer
+            .borrow_mut()
+            .define_const(String::from("PI"), &Object::Int(3));
+
+        let mut inner = Environment::new_with_outer(Rc::clone(&outer));
+        assert!(!inner.is_constant("PI"));
+        assert_eq!(Some(Object::Int(3)), inner.get(String::from("PI")));
+
+        inner.define(String::from("PI"), &Object::Int(4));
+        assert_eq!(Some(Object::Int(4)), inner.get(String::from("PI")));
+        assert_eq!(Some(Object::Int(3)), outer.borrow().get(String::from("PI")));
+    }
+
+    #[test]
+    fn test_assign() {
+        let outer = Rc::new(RefCell::new(Environment::new()));
+        outer
+            .borrow_mut()
+            .define(String::from("x"), &Object::Int(1));
+        outer
+            .borrow_mut()
+            .define_const(String::from("PI"), &Object::Int(3));
+
+        let mut inner = Environment::new_with_outer(Rc::clone(&outer));
+        assert_eq!(Ok(()), inner.assign("x", &Object::Int(2)));
+        assert_eq!(Some(Object::Int(2)), outer.borrow().get(String::from("x")));
+        assert!(inner.is_empty());
+
+        assert_eq!(
+            Err(AssignError::Constant),
+            inner.assign("PI", &Object::Int(4))
+        );
+        assert_eq!(
+            Err(AssignError::Undefined),
+            inner.assign("y", &Object::Int(4))
+        );
+    }
+
+    #[test]
+    fn test_assign_builtin() {
+        let outer = Rc::new(RefCell::new(Environment::new()));
+        outer
+            .borrow_mut()
+            .define_builtin("len", Object::Builtin(Builtin::Len));
+
+        let mut inner = Environment::new_with_outer(Rc::clone(&outer));
+        assert_eq!(
+            Err(AssignError::Builtin),
+            inner.assign("len", &Object::Int(1))
+        );
+        assert_eq!(
+            Some(Object::Builtin(Builtin::Len)),
+            outer.borrow().get(String::from("len"))
+        );
+
+        outer.borrow_mut().set_allow_builtin_shadowing(true);
+        assert_eq!(Ok(()), inner.assign("len", &Object::Int(1)));
+        assert_eq!(
+            Some(Object::Int(1)),
+            outer.borrow().get(String::from("len"))
+        );
+    }
+
+    #[test]
+    fn test_all_keys() {
+        let outer = Rc::new(RefCell::new(Environment::new()));
+        outer
+            .borrow_mut()
+            .define(String::from("y"), &Object::Int(1));
+        outer
+            .borrow_mut()
+            .define(String::from("x"), &Object::Int(1));
+
+        let mut inner = Environment::new_with_outer(Rc::clone(&outer));
+        inner.define(String::from("x"), &Object::Int(2));
+        inner.define(String::from("a"), &Object::Int(3));
+
+        assert_eq!(vec!["a", "x", "y"], inner.all_keys());
+        assert_eq!(vec!["x", "y"], outer.borrow().all_keys());
+        assert!(Environment::new().all_keys().is_empty());
     }
 }
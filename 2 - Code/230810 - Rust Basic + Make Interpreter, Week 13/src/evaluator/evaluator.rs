@@ -1,30 +1,178 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, Write};
 use std::rc::Rc;
 
 use crate::ast::ast::{Expression, Identifier, Infix, Literal, Prefix, Program, Statement};
-use crate::evaluator::environment::Environment;
+use crate::evaluator::builtins::Builtin;
+use crate::evaluator::environment::{AssignError, Environment};
 use crate::evaluator::object::Object;
+use crate::interpreter::interpreter::MonkeyError;
+use crate::lexer::lexer::Lexer;
+use crate::parser::parser::Parser;
+
+/// How deeply function calls may nest unless `set_max_call_depth` says
+/// otherwise. Each call recurses on the native stack, so an evaluator on a
+/// small thread stack may need a lower limit.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
 
 pub struct Evaluator {
     environment: Rc<RefCell<Environment>>,
+    output: Rc<RefCell<dyn Write>>,
+    frames: Vec<String>,
+    trace: Vec<String>,
+    max_call_depth: Option<usize>,
+    peak_call_depth: usize,
+    call_depth_exceeded: bool,
+    fuel: Option<u64>,
 }
 
 impl Evaluator {
     pub fn new(environment: Rc<RefCell<Environment>>) -> Self {
-        Evaluator { environment }
+        for builtin in Builtin::ALL {
+            environment
+                .borrow_mut()
+                .define_builtin(builtin.name(), Object::Builtin(builtin));
+        }
+
+        Evaluator {
+            environment,
+            output: Rc::new(RefCell::new(io::stdout())),
+            frames: Vec::new(),
+            trace: Vec::new(),
+            max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+            peak_call_depth: 0,
+            call_depth_exceeded: false,
+            fuel: None,
+        }
+    }
+
+    /// Creates an evaluator with a fresh environment whose builtins, such as
+    /// `puts`, write to `output` instead of stdout.
+    pub fn with_output(output: impl Write + 'static) -> Self {
+        Evaluator {
+            output: Rc::new(RefCell::new(output)),
+            ..Evaluator::new(Rc::new(RefCell::new(Environment::new())))
+        }
+    }
+
+    /// Creates an evaluator with a fresh environment and runs `prelude` in
+    /// it. Later programs see the prelude's bindings and may shadow them
+    /// with `let`. Fails with the prelude's own parse or runtime errors.
+    pub fn new_with_prelude(prelude: &str) -> Result<Self, MonkeyError> {
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        evaluator.load_prelude(prelude)?;
+
+        Ok(evaluator)
+    }
+
+    /// Runs `prelude` in the current environment, then encloses it so later
+    /// programs may shadow its bindings with `let`. On a runtime error,
+    /// `last_trace` holds the frames that were active.
+    pub fn load_prelude(&mut self, prelude: &str) -> Result<(), MonkeyError> {
+        let program = Parser::new(Lexer::new(prelude))
+            .parse()
+            .map_err(|failure| MonkeyError::Parse(failure.errors))?;
+
+        if let Some(Object::Error(msg)) = self.eval(program) {
+            return Err(MonkeyError::Runtime(msg));
+        }
+
+        self.enclose_environment();
+
+        Ok(())
+    }
+
+    pub fn output(&self) -> Rc<RefCell<dyn Write>> {
+        Rc::clone(&self.output)
+    }
+
+    /// The session environment that top-level statements are evaluated in.
+    pub fn environment(&self) -> Rc<RefCell<Environment>> {
+        Rc::clone(&self.environment)
+    }
+
+    /// Moves evaluation into a new scope nested in the current one, so later
+    /// `let` and `const` statements may shadow everything bound so far.
+    pub fn enclose_environment(&mut self) {
+        let outer = Rc::clone(&self.environment);
+        self.environment = Rc::new(RefCell::new(Environment::new_with_outer(outer)));
+    }
+
+    /// Limits how deeply function calls may nest, or lifts the limit with
+    /// `None`. The limit is `DEFAULT_MAX_CALL_DEPTH` until this is called.
+    pub fn set_max_call_depth(&mut self, max_call_depth: Option<usize>) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Limits function calls to nesting at most `limit` deep.
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.set_max_call_depth(Some(limit));
+    }
+
+    /// The deepest function calls nested during the last `eval`.
+    pub fn recursion_depth(&self) -> usize {
+        self.peak_call_depth
+    }
+
+    /// Whether the last `eval` was cut off by the recursion limit, rather
+    /// than unwinding all of its calls by itself.
+    pub fn recursion_limit_reached(&self) -> bool {
+        self.call_depth_exceeded
+    }
+
+    /// Limits how many expressions may still be evaluated, or lifts the limit
+    /// with `None`. Once it runs out, evaluation stops with an error.
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.fuel = fuel;
     }
 
-    fn is_truthy(object: Object) -> bool {
+    /// How much fuel is left, if it's limited.
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Every binding visible at the top level, builtins included, sorted by
+    /// name. A name bound in several scopes shows the value it resolves to.
+    pub fn inspect_environment(&self) -> Vec<(String, Object)> {
+        let environment = self.environment.borrow();
+
+        environment
+            .all_keys()
+            .into_iter()
+            .filter_map(|name| {
+                let value = environment.get(name.clone())?;
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    /// Call frames that were active when the last error was created,
+    /// from the outermost call to the innermost one.
+    pub fn last_trace(&self) -> &[String] {
+        &self.trace
+    }
+
+    pub(crate) fn is_truthy(object: Object) -> bool {
         match object {
             Object::Null | Object::Bool(false) => false,
             _ => true,
         }
     }
 
-    fn error(msg: String) -> Object {
+    pub(crate) fn error(&mut self, msg: String) -> Object {
+        if self.trace.is_empty() {
+            self.trace = self.frames.clone();
+        }
+
         Object::Error(msg)
     }
 
+    /// Forgets the trace of an error that was handled rather than returned.
+    pub(crate) fn clear_trace(&mut self) {
+        self.trace.clear();
+    }
+
     fn is_error(object: &Object) -> bool {
         match object {
             Object::Error(_) => true,
@@ -35,8 +183,10 @@ impl Evaluator {
     pub fn eval(&mut self, program: Program) -> Option<Object> {
         let mut result = None;
 
+        self.reset();
+
         for statement in program {
-            match self.eval_statement(statement) {
+            match self.eval_top_level_statement(statement) {
                 Some(Object::ReturnValue(value)) => return Some(*value),
                 Some(Object::Error(msg)) => return Some(Object::Error(msg)),
                 object => result = object,
@@ -46,6 +196,64 @@ impl Evaluator {
         result
     }
 
+    /// Like `eval`, but an error only stops the statement it happens in: the
+    /// rest of the program still runs. Returns the value of the last statement
+    /// that didn't fail, along with every error in the order they happened.
+    pub fn eval_collect_errors(&mut self, program: Program) -> (Option<Object>, Vec<Object>) {
+        let mut result = None;
+        let mut errors = Vec::new();
+
+        self.reset();
+
+        for statement in program {
+            match self.eval_top_level_statement(statement) {
+                Some(Object::ReturnValue(value)) => return (Some(*value), errors),
+                Some(error @ Object::Error(_)) => errors.push(error),
+                object => result = object,
+            }
+        }
+
+        (result, errors)
+    }
+
+    fn reset(&mut self) {
+        self.frames.clear();
+        self.trace.clear();
+        self.peak_call_depth = 0;
+        self.call_depth_exceeded = false;
+    }
+
+    /// Evaluates a statement that no loop encloses.
+    fn eval_top_level_statement(&mut self, statement: Statement) -> Option<Object> {
+        match self.eval_statement(statement) {
+            Some(Object::Break) => Some(self.error(String::from("break outside of loop"))),
+            Some(Object::Continue) => Some(self.error(String::from("continue outside of loop"))),
+            object => object,
+        }
+    }
+
+    /// Evaluates `program` in a child scope of the session environment:
+    /// session bindings are visible, but its own `let`s don't leak out.
+    pub fn eval_scoped(&mut self, program: Program) -> Option<Object> {
+        let scope = Environment::new_with_outer(Rc::clone(&self.environment));
+
+        self.eval_in(program, Rc::new(RefCell::new(scope)))
+    }
+
+    /// Evaluates `program` in `environment` and then switches back to the
+    /// session environment.
+    pub fn eval_in(
+        &mut self,
+        program: Program,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Option<Object> {
+        let session = std::mem::replace(&mut self.environment, environment);
+        let result = self.eval(program);
+        self.environment = session;
+
+        result
+    }
+
     fn eval_block_statement(&mut self, statements: Vec<Statement>) -> Option<Object> {
         let mut result = None;
 
@@ -53,6 +261,8 @@ impl Evaluator {
             match self.eval_statement(statement) {
                 Some(Object::ReturnValue(value)) => return Some(Object::ReturnValue(value)),
                 Some(Object::Error(msg)) => return Some(Object::Error(msg)),
+                Some(Object::Break) => return Some(Object::Break),
+                Some(Object::Continue) => return Some(Object::Continue),
                 object => result = object,
             }
         }
@@ -71,11 +281,42 @@ impl Evaluator {
                 if Self::is_error(&value) {
                     Some(value)
                 } else {
-                    let Identifier(name) = identifier;
-                    self.environment.borrow_mut().set(name, &value);
+                    self.bind(identifier, value, false)
+                }
+            }
+            Statement::Const(identifier, expression) => {
+                let value = self.eval_expression(expression)?;
+
+                if Self::is_error(&value) {
+                    Some(value)
+                } else {
+                    self.bind(identifier, value, true)
+                }
+            }
+            Statement::DestructureArray(identifiers, expression) => {
+                let value = self.eval_expression(expression)?;
+
+                let elements = match value {
+                    Object::Array(elements) => elements,
+                    Object::Error(_) => return Some(value),
+                    value => {
+                        return Some(
+                            self.error(format!("type mismatch: cannot destructure {value}")),
+                        )
+                    }
+                };
+
+                let mut elements = elements.into_iter();
+
+                for identifier in identifiers {
+                    let value = elements.next().unwrap_or(Object::Null);
 
-                    None
+                    if let Some(error) = self.bind(identifier, value, false) {
+                        return Some(error);
+                    }
                 }
+
+                None
             }
             Statement::Expression(expression) => {
                 let value = match self.eval_expression(expression) {
@@ -85,6 +326,30 @@ impl Evaluator {
 
                 Some(value)
             }
+            Statement::Assign(Identifier(name), expression) => {
+                let value = self.eval_expression(expression)?;
+
+                if Self::is_error(&value) {
+                    return Some(value);
+                }
+
+                let assigned = self.environment.borrow_mut().assign(&name, &value);
+
+                match assigned {
+                    Ok(()) => None,
+                    Err(AssignError::Constant) => {
+                        Some(self.error(format!("cannot assign to constant: {name}")))
+                    }
+                    Err(AssignError::Undefined) => {
+                        Some(self.error(format!("identifier not found: {name}")))
+                    }
+                    Err(AssignError::Builtin) => {
+                        Some(self.error(format!("cannot shadow builtin function: {name}")))
+                    }
+                }
+            }
+            Statement::Break => Some(Object::Break),
+            Statement::Continue => Some(Object::Continue),
             Statement::Return(expression) => {
                 let value = match self.eval_expression(expression) {
                     Some(value) => value,
@@ -100,25 +365,63 @@ impl Evaluator {
         }
     }
 
+    /// Binds `identifier` in the current scope. Builtins can't be shadowed,
+    /// and a constant can't be rebound by `let` or `const` in its own scope,
+    /// though nested scopes may shadow it.
+    fn bind(&mut self, identifier: Identifier, value: Object, constant: bool) -> Option<Object> {
+        let Identifier(name) = identifier;
+
+        if self.environment.borrow().is_protected_builtin(&name) {
+            return Some(self.error(format!("cannot shadow builtin function: {name}")));
+        }
+
+        if self.environment.borrow().is_constant(&name) {
+            return Some(self.error(format!("cannot assign to constant: {name}")));
+        }
+
+        if constant {
+            self.environment.borrow_mut().define_const(name, &value);
+        } else {
+            self.environment.borrow_mut().define(name, &value);
+        }
+
+        None
+    }
+
     fn eval_expression(&mut self, expression: Expression) -> Option<Object> {
+        self.debug_assert_environment_unborrowed();
+
+        match self.fuel {
+            Some(0) => return Some(self.error(String::from("out of fuel"))),
+            Some(ref mut fuel) => *fuel -= 1,
+            None => {}
+        }
+
         match expression {
             Expression::Identifier(identifier) => Some(self.eval_identifier(identifier)),
             Expression::Literal(literal) => Some(self.eval_literal(literal)),
             Expression::Prefix(prefix, right_expression) => {
-                if let Some(right) = self.eval_expression(*right_expression) {
-                    Some(self.eval_prefix_expression(prefix, right))
-                } else {
-                    None
+                match self.eval_expression(*right_expression) {
+                    Some(right) if Self::is_error(&right) => Some(right),
+                    Some(right) => Some(self.eval_prefix_expression(prefix, right)),
+                    None => None,
                 }
             }
             Expression::Infix(infix, left_expression, right_expression) => {
                 let left = self.eval_expression(*left_expression);
+
+                if left.as_ref().is_some_and(Self::is_error) {
+                    return left;
+                }
+
                 let right = self.eval_expression(*right_expression);
 
-                if left.is_some() && right.is_some() {
-                    Some(self.eval_infix_expression(infix, left.unwrap(), right.unwrap()))
-                } else {
-                    None
+                match (left, right) {
+                    (_, Some(right)) if Self::is_error(&right) => Some(right),
+                    (Some(left), Some(right)) => {
+                        Some(self.eval_infix_expression(infix, left, right))
+                    }
+                    _ => None,
                 }
             }
             Expression::If {
@@ -126,6 +429,7 @@ impl Evaluator {
                 consequence,
                 alternative,
             } => self.eval_if_expression(*condition, consequence, alternative),
+            Expression::While { condition, body } => self.eval_while_expression(*condition, body),
             Expression::Function { parameters, body } => Some(Object::Function(
                 parameters,
                 body,
@@ -135,23 +439,79 @@ impl Evaluator {
                 function,
                 arguments,
             } => Some(self.eval_call_expression(function, arguments)),
+            Expression::Index(left_expression, index_expression) => {
+                let left = self.eval_expression(*left_expression);
+                let index = self.eval_expression(*index_expression);
+
+                match (left, index) {
+                    (Some(left), _) if Self::is_error(&left) => Some(left),
+                    (_, Some(index)) if Self::is_error(&index) => Some(index),
+                    (Some(left), Some(index)) => Some(self.eval_index_expression(left, index)),
+                    _ => None,
+                }
+            }
         }
     }
 
     fn eval_identifier(&mut self, identifier: Identifier) -> Object {
         let Identifier(name) = identifier;
 
-        match self.environment.borrow_mut().get(name.clone()) {
+        let value = self.environment.borrow().get(name.clone());
+
+        match value {
             Some(value) => value,
-            None => Object::Error(String::from(format!("identifier not found: {name}"))),
+            None => self.error(format!("identifier not found: {name}")),
         }
     }
 
     fn eval_literal(&mut self, literal: Literal) -> Object {
         match literal {
             Literal::Int(value) => Object::Int(value),
+            Literal::Float(value) => Object::Float(value),
             Literal::Bool(value) => Object::Bool(value),
+            Literal::Str(value) => Object::Str(value),
+            Literal::Hash(pairs) => self.eval_hash_literal(pairs),
+            Literal::Array(expressions) => {
+                let mut objects = Vec::new();
+
+                for expression in expressions {
+                    match self.eval_expression(expression) {
+                        Some(object) if Self::is_error(&object) => return object,
+                        Some(object) => objects.push(object),
+                        None => objects.push(Object::Null),
+                    }
+                }
+
+                Object::Array(objects)
+            }
+        }
+    }
+
+    fn eval_hash_literal(&mut self, pairs: Vec<(Expression, Expression)>) -> Object {
+        let mut hash = HashMap::new();
+
+        for (key_expression, value_expression) in pairs {
+            let key = match self.eval_expression(key_expression) {
+                Some(key) if Self::is_error(&key) => return key,
+                Some(key) => key,
+                None => Object::Null,
+            };
+
+            let hash_key = match key.to_hash_key() {
+                Ok(hash_key) => hash_key,
+                Err(err) => return self.error(err.to_string()),
+            };
+
+            let value = match self.eval_expression(value_expression) {
+                Some(value) if Self::is_error(&value) => return value,
+                Some(value) => value,
+                None => Object::Null,
+            };
+
+            hash.insert(hash_key, (key, value));
         }
+
+        Object::Hash(hash)
     }
 
     fn eval_prefix_expression(&mut self, prefix: Prefix, right: Object) -> Object {
@@ -172,8 +532,12 @@ impl Evaluator {
 
     fn eval_minus_prefix_expression(&mut self, right: Object) -> Object {
         match right {
-            Object::Int(value) => Object::Int(-value),
-            _ => Self::error(format!("unknown operator: -{right}")),
+            Object::Int(value) => match value.checked_neg() {
+                Some(value) => Object::Int(value),
+                None => self.error(format!("integer overflow: -{value}")),
+            },
+            Object::Float(value) => Object::Float(-value),
+            _ => self.error(format!("unknown operator: -{right}")),
         }
     }
 
@@ -183,17 +547,36 @@ impl Evaluator {
                 if let Object::Int(right_value) = right {
                     self.eval_infix_integer_expression(infix, left_value, right_value)
                 } else {
-                    Self::error(format!("type mismatch: {left} {infix} {right}"))
+                    self.error(format!("type mismatch: {left} {infix} {right}"))
+                }
+            }
+            Object::Float(left_value) => {
+                if let Object::Float(right_value) = right {
+                    Self::eval_infix_float_expression(infix, left_value, right_value)
+                } else {
+                    self.error(format!("type mismatch: {left} {infix} {right}"))
                 }
             }
             Object::Bool(left_value) => {
                 if let Object::Bool(right_value) = right {
                     self.eval_infix_boolean_expression(infix, left_value, right_value)
                 } else {
-                    Self::error(format!("type mismatch: {left} {infix} {right}"))
+                    self.error(format!("type mismatch: {left} {infix} {right}"))
+                }
+            }
+            Object::Str(ref left_value) => {
+                if let Object::Str(ref right_value) = right {
+                    match infix {
+                        Infix::Plus => Object::Str(format!("{left_value}{right_value}")),
+                        Infix::Equal => Object::Bool(left_value == right_value),
+                        Infix::NotEqual => Object::Bool(left_value != right_value),
+                        _ => self.error(format!("unknown operator: {left} {infix} {right}")),
+                    }
+                } else {
+                    self.error(format!("type mismatch: {left} {infix} {right}"))
                 }
             }
-            _ => Self::error(format!("unknown operator: {left} {infix} {right}")),
+            _ => self.error(format!("unknown operator: {left} {infix} {right}")),
         }
     }
 
@@ -203,11 +586,36 @@ impl Evaluator {
         left_value: i64,
         right_value: i64,
     ) -> Object {
+        let checked = match infix {
+            Infix::Plus => left_value.checked_add(right_value),
+            Infix::Minus => left_value.checked_sub(right_value),
+            Infix::Multiply => left_value.checked_mul(right_value),
+            Infix::Divide if right_value == 0 => {
+                return self.error(format!("division by zero: {left_value} / 0"));
+            }
+            Infix::Divide => left_value.checked_div(right_value),
+            Infix::Equal => return Object::Bool(left_value == right_value),
+            Infix::NotEqual => return Object::Bool(left_value != right_value),
+            Infix::LessThan => return Object::Bool(left_value < right_value),
+            Infix::GreaterThan => return Object::Bool(left_value > right_value),
+        };
+
+        match checked {
+            Some(value) => Object::Int(value),
+            None => self.error(format!(
+                "integer overflow: {left_value} {infix} {right_value}"
+            )),
+        }
+    }
+
+    /// Floats follow IEEE 754, so dividing by zero gives an infinity or NaN
+    /// rather than an error.
+    fn eval_infix_float_expression(infix: Infix, left_value: f64, right_value: f64) -> Object {
         match infix {
-            Infix::Plus => Object::Int(left_value + right_value),
-            Infix::Minus => Object::Int(left_value - right_value),
-            Infix::Multiply => Object::Int(left_value * right_value),
-            Infix::Divide => Object::Int(left_value / right_value),
+            Infix::Plus => Object::Float(left_value + right_value),
+            Infix::Minus => Object::Float(left_value - right_value),
+            Infix::Multiply => Object::Float(left_value * right_value),
+            Infix::Divide => Object::Float(left_value / right_value),
             Infix::Equal => Object::Bool(left_value == right_value),
             Infix::NotEqual => Object::Bool(left_value != right_value),
             Infix::LessThan => Object::Bool(left_value < right_value),
@@ -224,12 +632,30 @@ impl Evaluator {
         match infix {
             Infix::Equal => Object::Bool(left_value == right_value),
             Infix::NotEqual => Object::Bool(left_value != right_value),
-            _ => Self::error(format!(
+            _ => self.error(format!(
                 "unknown operator: {left_value} {infix} {right_value}",
             )),
         }
     }
 
+    fn eval_index_expression(&mut self, left: Object, index: Object) -> Object {
+        match (&left, &index) {
+            (Object::Array(objects), Object::Int(index)) => usize::try_from(*index)
+                .ok()
+                .and_then(|index| objects.get(index))
+                .cloned()
+                .unwrap_or(Object::Null),
+            (Object::Hash(pairs), _) => match index.to_hash_key() {
+                Ok(hash_key) => pairs
+                    .get(&hash_key)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or(Object::Null),
+                Err(err) => self.error(err.to_string()),
+            },
+            _ => self.error(format!("index operator not supported: {left}[{index}]")),
+        }
+    }
+
     fn eval_if_expression(
         &mut self,
         condition: Expression,
@@ -237,6 +663,7 @@ impl Evaluator {
         alternative: Option<Vec<Statement>>,
     ) -> Option<Object> {
         let condition = match self.eval_expression(condition) {
+            Some(condition) if Self::is_error(&condition) => return Some(condition),
             Some(condition) => condition,
             None => return None,
         };
@@ -250,53 +677,150 @@ impl Evaluator {
         }
     }
 
+    /// Each iteration runs the body in a fresh scope enclosed by the current
+    /// one, so its `let` and `const` bindings don't outlive the iteration
+    /// while assignments still reach outer names. `break` and `continue` stop at
+    /// the innermost loop, while `return` and errors pass through it. The
+    /// loop itself evaluates to null.
+    fn eval_while_expression(
+        &mut self,
+        condition: Expression,
+        body: Vec<Statement>,
+    ) -> Option<Object> {
+        loop {
+            let condition = self.eval_expression(condition.clone())?;
+
+            if Self::is_error(&condition) {
+                return Some(condition);
+            }
+
+            if !Self::is_truthy(condition) {
+                break;
+            }
+
+            let outer = Rc::clone(&self.environment);
+            self.environment =
+                Rc::new(RefCell::new(Environment::new_with_outer(Rc::clone(&outer))));
+            let object = self.eval_block_statement(body.clone());
+            self.environment = outer;
+
+            match object {
+                Some(Object::Break) => break,
+                Some(object @ (Object::ReturnValue(_) | Object::Error(_))) => return Some(object),
+                _ => {}
+            }
+        }
+
+        Some(Object::Null)
+    }
+
     fn eval_call_expression(
         &mut self,
         function: Box<Expression>,
         arguments: Vec<Expression>,
     ) -> Object {
-        let arguments = arguments
-            .iter()
-            .map(|expression| {
-                self.eval_expression(expression.clone())
-                    .unwrap_or(Object::Null)
-            })
-            .collect::<Vec<_>>();
+        let name = match *function {
+            Expression::Identifier(Identifier(ref name)) => name.clone(),
+            _ => String::from("<anonymous>"),
+        };
+
+        let mut evaluated = Vec::new();
+        // Arguments are evaluated before the function, which is only looked
+        // at early to see whether it takes an argument that failed.
+        let mut callee = None;
+
+        for expression in arguments {
+            match self.eval_expression(expression) {
+                Some(object) if Self::is_error(&object) => {
+                    let callee =
+                        callee.get_or_insert_with(|| self.eval_expression(*function.clone()));
+
+                    match callee {
+                        Some(Object::Builtin(builtin)) if builtin.accepts_errors() => {
+                            evaluated.push(object)
+                        }
+                        _ => return object,
+                    }
+                }
+                Some(object) => evaluated.push(object),
+                None => evaluated.push(Object::Null),
+            }
+        }
+
+        let arguments = evaluated;
+        let callee = match callee {
+            Some(callee) => callee,
+            None => self.eval_expression(*function),
+        };
 
-        let (parameters, body, environment) = match self.eval_expression(*function) {
+        let (parameters, body, environment) = match callee {
             Some(Object::Function(parameters, body, environment)) => {
                 (parameters, body, environment)
             }
-            Some(object) => return Self::error(format!("{object} is not valid function")),
+            Some(Object::Builtin(builtin)) => return builtin.call(self, arguments),
+            Some(object) if Self::is_error(&object) => return object,
+            Some(object) => return self.error(format!("{object} is not valid function")),
             None => return Object::Null,
         };
 
         if parameters.len() != arguments.len() {
-            return Self::error(format!(
+            return self.error(format!(
                 "wrong number of arguments: {} expected but {} given",
                 parameters.len(),
                 arguments.len(),
             ));
         }
 
+        if let Some(max_call_depth) = self.max_call_depth {
+            if self.frames.len() >= max_call_depth {
+                self.call_depth_exceeded = true;
+                return self.error(format!("maximum call depth of {max_call_depth} exceeded"));
+            }
+        }
+
         let current_env = Rc::clone(&self.environment);
         let mut scoped_env = Environment::new_with_outer(Rc::clone(&environment));
         let list = parameters.iter().zip(arguments.iter());
 
         for (_, (identifier, object)) in list.enumerate() {
             let Identifier(name) = identifier.clone();
-            scoped_env.set(name, object);
+            scoped_env.define(name, object);
         }
 
+        self.frames.push(Self::frame(&name, arguments.len()));
+        self.peak_call_depth = self.peak_call_depth.max(self.frames.len());
         self.environment = Rc::new(RefCell::new(scoped_env));
 
         let object = self.eval_block_statement(body);
 
-        self.environment = current_env;
-
-        match object {
+        // A loop in the caller doesn't enclose the function body.
+        let object = match object {
+            Some(Object::Break) => self.error(String::from("break outside of loop")),
+            Some(Object::Continue) => self.error(String::from("continue outside of loop")),
             Some(object) => object,
             None => Object::Null,
+        };
+
+        self.environment = current_env;
+        self.frames.pop();
+
+        object
+    }
+
+    /// Fails loudly in debug builds if a borrow of the current environment is
+    /// held while evaluation recurses, which would otherwise only panic once
+    /// some nested expression happened to need a mutable borrow of it.
+    fn debug_assert_environment_unborrowed(&self) {
+        debug_assert!(
+            self.environment.try_borrow_mut().is_ok(),
+            "environment is still borrowed while evaluating an expression"
+        );
+    }
+
+    fn frame(name: &str, argument_count: usize) -> String {
+        match argument_count {
+            1 => format!("in {name} (1 argument)"),
+            _ => format!("in {name} ({argument_count} arguments)"),
         }
     }
 }
@@ -304,18 +828,50 @@ impl Evaluator {
 #[cfg(test)]
 mod tests {
     use std::cell::RefCell;
+    use std::io::{self, Write};
     use std::rc::Rc;
+    use std::thread;
 
-    use crate::ast::ast::{Expression, Identifier, Infix, Literal, Statement};
+    use crate::ast::ast::{Expression, Identifier, Infix, Literal, Program, Statement};
+    use crate::evaluator::builtins::Builtin;
     use crate::evaluator::environment::Environment;
-    use crate::evaluator::evaluator::Evaluator;
+    use crate::evaluator::evaluator::{Evaluator, DEFAULT_MAX_CALL_DEPTH};
     use crate::evaluator::object::Object;
+    use crate::interpreter::interpreter::MonkeyError;
     use crate::lexer::lexer::Lexer;
     use crate::parser::parser::Parser;
 
+    /// A writer that can still be read after the evaluator that owns it has
+    /// written to it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn eval_with_output(input: &str) -> (Option<Object>, String) {
+        let buffer = SharedBuffer::default();
+        let mut e = Evaluator::with_output(buffer.clone());
+        let result = e.eval(Parser::new(Lexer::new(input)).parse_program());
+        let output = String::from_utf8(buffer.0.take()).unwrap();
+
+        (result, output)
+    }
+
     fn eval(input: &str) -> Option<Object> {
+        let program = Parser::new(Lexer::new(input))
+            .parse()
+            .unwrap_or_else(|failure| panic!("{input:?} failed to parse:\n{failure}"));
+
         let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
-        e.eval(Parser::new(Lexer::new(input)).parse_program())
+        e.eval(program)
     }
 
     #[test]
@@ -343,6 +899,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_integer_range() {
+        let tests = vec![
+            ("-9223372036854775808", Some(Object::Int(i64::MIN))),
+            ("-9223372036854775807 - 1", Some(Object::Int(i64::MIN))),
+            (
+                "--9223372036854775808",
+                Some(Object::Error(String::from(
+                    "integer overflow: --9223372036854775808",
+                ))),
+            ),
+            (
+                "-9223372036854775808 / -1",
+                Some(Object::Error(String::from(
+                    "integer overflow: -9223372036854775808 / -1",
+                ))),
+            ),
+            (
+                "9223372036854775807 + 1",
+                Some(Object::Error(String::from(
+                    "integer overflow: 9223372036854775807 + 1",
+                ))),
+            ),
+            (
+                "-9223372036854775808 * -1",
+                Some(Object::Error(String::from(
+                    "integer overflow: -9223372036854775808 * -1",
+                ))),
+            ),
+            (
+                "5 / 0",
+                Some(Object::Error(String::from("division by zero: 5 / 0"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+
+        // One past `i64::MAX` is rejected before evaluation starts.
+        assert!(Parser::new(Lexer::new("9223372036854775808"))
+            .parse()
+            .is_err());
+    }
+
+    #[test]
+    fn test_integer_literal_bases() {
+        let tests = vec![
+            ("0xFF + 0b1", Some(Object::Int(256))),
+            ("1_000_000 / 0x10", Some(Object::Int(62_500))),
+            ("-0b1000 * 2", Some(Object::Int(-16))),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let input = r#"
+let 반지름 = 3;
+let πr = fn(r) { 3 * r };
+let площадь = fn(r) { πr(r) * r };
+площадь(반지름);
+"#;
+
+        assert_eq!(Some(Object::Int(27)), eval(input));
+    }
+
     #[test]
     fn test_boolean_expression() {
         let tests = vec![
@@ -450,6 +1076,10 @@ if (10 > 1) {
     fn test_function_object() {
         let input = "fn(x) { x + 2; };";
 
+        // The global environment a fresh evaluator closes over holds the builtins.
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        Evaluator::new(Rc::clone(&environment));
+
         assert_eq!(
             Some(Object::Function(
                 vec![Identifier(String::from("x"))],
@@ -458,12 +1088,35 @@ if (10 > 1) {
                     Box::new(Expression::Identifier(Identifier(String::from("x")))),
                     Box::new(Expression::Literal(Literal::Int(2))),
                 ))],
-                Rc::new(RefCell::new(Environment::new())),
+                environment,
             )),
             eval(input),
         )
     }
 
+    #[test]
+    #[cfg(not(feature = "verbose-object-display"))]
+    fn test_function_display() {
+        assert_eq!(
+            "fn(x, y) { ... }",
+            eval("fn(x, y) { x + y }").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "verbose-object-display")]
+    fn test_function_display() {
+        assert_eq!(
+            "fn(x) { (x + 1); }",
+            eval("fn(x) { x + 1 }").unwrap().to_string()
+        );
+
+        let closure = eval("let offset = 2; let add = fn(x) { x + offset }; add")
+            .unwrap()
+            .to_string();
+        assert!(closure.contains("offset"), "{closure}");
+    }
+
     #[test]
     fn test_function_application() {
         let tests = vec![
@@ -559,6 +1212,1145 @@ if (10 > 1) {
             assert_eq!(expect, eval(input));
         }
     }
+
+    #[test]
+    fn test_operand_errors_propagate() {
+        let tests = vec![
+            ("-true + 5", "unknown operator: -true"),
+            ("5 + -true", "unknown operator: -true"),
+            ("-(1 + true)", "type mismatch: 1 + true"),
+            ("!(1 + true)", "type mismatch: 1 + true"),
+            ("if (1 + true) { 1 } else { 2 }", "type mismatch: 1 + true"),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                Some(Object::Error(String::from(expect))),
+                eval(input),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_eval_collect_errors() {
+        let input = "let x = 1; let y = x + true; let z = x + 1; -false; break; z";
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let (result, errors) =
+            e.eval_collect_errors(Parser::new(Lexer::new(input)).parse_program());
+
+        assert_eq!(Some(Object::Int(2)), result);
+        assert_eq!(
+            vec![
+                Object::Error(String::from("type mismatch: 1 + true")),
+                Object::Error(String::from("unknown operator: -false")),
+                Object::Error(String::from("break outside of loop")),
+            ],
+            errors
+        );
+
+        let x = e.eval(Parser::new(Lexer::new("[x, z]")).parse_program());
+        assert_eq!(Some(Object::Array(vec![Object::Int(1), Object::Int(2)])), x);
+        assert_eq!(
+            Some(Object::Error(String::from("identifier not found: y"))),
+            e.eval(Parser::new(Lexer::new("y")).parse_program())
+        );
+    }
+
+    #[test]
+    fn test_eval_collect_errors_stops_at_return() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let program = Parser::new(Lexer::new("1 + true; return 5; 2 + false")).parse_program();
+
+        let (result, errors) = e.eval_collect_errors(program);
+        assert_eq!(Some(Object::Int(5)), result);
+        assert_eq!(1, errors.len());
+
+        let (result, errors) = e.eval_collect_errors(Program::new());
+        assert_eq!((None, Vec::new()), (result, errors));
+    }
+
+    #[test]
+    fn test_max_call_depth() {
+        let input = "let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) } };";
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        e.set_max_call_depth(Some(10));
+        e.eval(Parser::new(Lexer::new(input)).parse_program());
+
+        let mut call = |n: i64| e.eval(Parser::new(Lexer::new(&format!("f({n})"))).parse_program());
+        assert_eq!(Some(Object::Int(0)), call(9));
+        assert_eq!(
+            Some(Object::Error(String::from(
+                "maximum call depth of 10 exceeded"
+            ))),
+            call(10)
+        );
+    }
+
+    #[test]
+    fn test_default_max_call_depth() {
+        let input = "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(100000);";
+
+        // The default limit has to be reached before the stack runs out, even
+        // unoptimized; the test harness's own threads are too small for that.
+        // Objects can't leave the thread, so it sends back the error message.
+        let error = thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(move || {
+                let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+                match e.eval(Parser::new(Lexer::new(input)).parse_program()) {
+                    Some(Object::Error(msg)) => Some(msg),
+                    _ => None,
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(
+            Some(format!(
+                "maximum call depth of {DEFAULT_MAX_CALL_DEPTH} exceeded"
+            )),
+            error
+        );
+
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        e.set_max_call_depth(None);
+        let program = Parser::new(Lexer::new(
+            "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(20);",
+        ))
+        .parse_program();
+        assert_eq!(Some(Object::Int(20)), e.eval(program));
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        e.set_recursion_limit(5);
+        e.eval(
+            Parser::new(Lexer::new(
+                "let deep = fn(n) { if (n == 1) { 1 } else { deep(n - 1) + 1 } };",
+            ))
+            .parse_program(),
+        );
+        assert_eq!(0, e.recursion_depth());
+
+        let mut call = |n: i64| {
+            let result = e.eval(Parser::new(Lexer::new(&format!("deep({n})"))).parse_program());
+            (result, e.recursion_depth(), e.recursion_limit_reached())
+        };
+
+        assert_eq!((Some(Object::Int(4)), 4, false), call(4));
+        assert_eq!((Some(Object::Int(5)), 5, false), call(5));
+        assert_eq!(
+            (
+                Some(Object::Error(String::from(
+                    "maximum call depth of 5 exceeded"
+                ))),
+                5,
+                true
+            ),
+            call(6)
+        );
+        assert_eq!((Some(Object::Int(2)), 2, false), call(2));
+    }
+
+    #[test]
+    fn test_fuel() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        e.set_fuel(Some(3));
+        assert_eq!(
+            Some(Object::Int(3)),
+            e.eval(Parser::new(Lexer::new("1 + 2")).parse_program())
+        );
+        assert_eq!(Some(0), e.fuel());
+        assert_eq!(
+            Some(Object::Error(String::from("out of fuel"))),
+            e.eval(Parser::new(Lexer::new("1")).parse_program())
+        );
+
+        e.set_fuel(Some(1_000));
+        assert_eq!(
+            Some(Object::Error(String::from("out of fuel"))),
+            e.eval(Parser::new(Lexer::new("while (true) { 1 }")).parse_program())
+        );
+    }
+
+    #[test]
+    fn test_array_literal() {
+        assert_eq!(
+            Some(Object::Array(vec![
+                Object::Int(1),
+                Object::Int(4),
+                Object::Int(6),
+            ])),
+            eval("[1, 2 * 2, 3 + 3]"),
+        );
+    }
+
+    #[test]
+    fn test_array_index_expression() {
+        let tests = vec![
+            ("[1, 2, 3][0]", Some(Object::Int(1))),
+            ("[1, 2, 3][1 + 1]", Some(Object::Int(3))),
+            ("let i = 0; [1][i];", Some(Object::Int(1))),
+            (
+                "let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2];",
+                Some(Object::Int(6)),
+            ),
+            ("[1, 2, 3][3]", Some(Object::Null)),
+            ("[1, 2, 3][-1]", Some(Object::Null)),
+            (
+                "let arr = [fn(x) { x + 1 }]; arr[0](5)",
+                Some(Object::Int(6)),
+            ),
+            (
+                "let make = fn() { [1, 2] }; make()[1]",
+                Some(Object::Int(2)),
+            ),
+            (
+                "1[0]",
+                Some(Object::Error(String::from(
+                    "index operator not supported: 1[0]",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_eval_scoped() {
+        let session = Rc::new(RefCell::new(Environment::new()));
+        let mut e = Evaluator::new(Rc::clone(&session));
+        let parse = |input: &str| Parser::new(Lexer::new(input)).parse_program();
+
+        e.eval(parse("let x = 1;"));
+
+        assert_eq!(
+            Some(Object::Int(3)),
+            e.eval_scoped(parse("let y = 2; x + y"))
+        );
+        assert_eq!(
+            Some(Object::Error(String::from("identifier not found: y"))),
+            e.eval(parse("y"))
+        );
+
+        // Shadowing inside the scope leaves the session binding alone.
+        assert_eq!(Some(Object::Int(5)), e.eval_scoped(parse("let x = 5; x")));
+        assert_eq!(Some(Object::Int(1)), e.eval(parse("x")));
+
+        let adder = e
+            .eval_scoped(parse("let offset = 10; fn(n) { n + offset + x }"))
+            .unwrap();
+        session.borrow_mut().define(String::from("adder"), &adder);
+
+        assert_eq!(Some(Object::Int(16)), e.eval(parse("adder(5)")));
+        assert_eq!(
+            Some(Object::Error(String::from("identifier not found: offset"))),
+            e.eval(parse("offset"))
+        );
+    }
+
+    #[test]
+    fn test_eval_in() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let parse = |input: &str| Parser::new(Lexer::new(input)).parse_program();
+        let fixture = Rc::new(RefCell::new(Environment::new()));
+
+        e.eval_in(parse("let z = 7;"), Rc::clone(&fixture));
+
+        assert_eq!(
+            Some(Object::Int(7)),
+            fixture.borrow().get(String::from("z"))
+        );
+        assert_eq!(
+            Some(Object::Error(String::from("identifier not found: z"))),
+            e.eval(parse("z"))
+        );
+    }
+
+    #[test]
+    fn test_string_expression() {
+        let tests = vec![
+            (
+                r#""Hello World!""#,
+                Some(Object::Str(String::from("Hello World!"))),
+            ),
+            (
+                r#""Hello" + " " + "World!""#,
+                Some(Object::Str(String::from("Hello World!"))),
+            ),
+            (r#""a" == "a""#, Some(Object::Bool(true))),
+            (r#""a" != "a""#, Some(Object::Bool(false))),
+            (r#"len("안녕")"#, Some(Object::Int(2))),
+            (r#"len("\u{1F600}\n")"#, Some(Object::Int(2))),
+            (
+                r#""tab\t" + r"\t""#,
+                Some(Object::Str(String::from("tab\t\\t"))),
+            ),
+            (
+                r#""a" - "b""#,
+                Some(Object::Error(String::from("unknown operator: a - b"))),
+            ),
+            (
+                r#""a" + 1"#,
+                Some(Object::Error(String::from("type mismatch: a + 1"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_hash_literal() {
+        let hash = match eval(r#"let two = "two"; {1: "a", true: "b", "key": 42, two: 1 + 1}"#) {
+            Some(Object::Hash(hash)) => hash,
+            object => panic!("expected a hash, got {object:?}"),
+        };
+
+        let expect = vec![
+            (Object::Int(1), Object::Str(String::from("a"))),
+            (Object::Bool(true), Object::Str(String::from("b"))),
+            (Object::Str(String::from("key")), Object::Int(42)),
+            (Object::Str(String::from("two")), Object::Int(2)),
+        ];
+
+        assert_eq!(expect.len(), hash.len());
+
+        for (key, value) in expect {
+            assert_eq!(
+                Some(&(key.clone(), value)),
+                hash.get(&key.to_hash_key().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_index_expression() {
+        let tests = vec![
+            (r#"{"foo": 5}["foo"]"#, Some(Object::Int(5))),
+            (r#"{"foo": 5}["bar"]"#, Some(Object::Null)),
+            (r#"let key = "foo"; {"foo": 5}[key]"#, Some(Object::Int(5))),
+            (r#"{}["foo"]"#, Some(Object::Null)),
+            ("{5: 5}[5]", Some(Object::Int(5))),
+            ("{true: 5}[true]", Some(Object::Int(5))),
+            ("{1: 5}[true]", Some(Object::Null)),
+            (
+                "{[]: 1}",
+                Some(Object::Error(String::from("unusable as hash key: ARRAY"))),
+            ),
+            (
+                "{}[fn(x) { x }]",
+                Some(Object::Error(String::from(
+                    "unusable as hash key: FUNCTION",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_float_expression() {
+        let tests = vec![
+            ("3.14", "3.14"),
+            ("-1.5", "-1.5"),
+            ("1.5 + 2.5", "4.0"),
+            ("1.5 + 2.5 == 4.0", "true"),
+            ("-1.5 * 2.0 == -3.0", "true"),
+            ("10.0 / 4.0", "2.5"),
+            ("0.5 - 1.0 < 0.0", "true"),
+            ("1.0 / 0.0", "inf"),
+            ("1.0 != 1.0", "false"),
+            ("1 + 1.0", "ERROR: type mismatch: 1 + 1.0"),
+            ("1.0 * 2", "ERROR: type mismatch: 1.0 * 2"),
+            ("1.5 == 1", "ERROR: type mismatch: 1.5 == 1"),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                Some(String::from(expect)),
+                eval(input).map(|object| object.to_string()),
+                "{input}"
+            );
+        }
+
+        assert_eq!(
+            Some(Object::Float(0.25)),
+            eval("let half = 0.5; half * half")
+        );
+    }
+
+    #[test]
+    fn test_assign_statement() {
+        let tests = vec![
+            ("let x = 1; x = x + 1; x", Some(Object::Int(2))),
+            ("let x = 1; x = 2;", None),
+            (
+                "let x = 1; let set = fn() { x = 5; }; set(); x",
+                Some(Object::Int(5)),
+            ),
+            (
+                "y = 1",
+                Some(Object::Error(String::from("identifier not found: y"))),
+            ),
+            (
+                "const PI = 3; PI = 4",
+                Some(Object::Error(String::from("cannot assign to constant: PI"))),
+            ),
+            (
+                "let x = 1; x = -true; x",
+                Some(Object::Error(String::from("unknown operator: -true"))),
+            ),
+            (
+                "len = 5; len",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+            (
+                "let f = fn() { len = 1; }; f(); len",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_while_expression() {
+        let tests = vec![
+            (
+                "let i = 0; while (i < 5) { i = i + 1; } i",
+                Some(Object::Int(5)),
+            ),
+            ("while (false) { 1 }", Some(Object::Null)),
+            ("let i = 0; while (i < 5) { i = i + 1; }", Some(Object::Null)),
+            (
+                "let f = fn() { let i = 0; while (true) { i = i + 1; if (i == 3) { return i; } } }; f()",
+                Some(Object::Int(3)),
+            ),
+            (
+                "while (1 + true) { 1 }",
+                Some(Object::Error(String::from("type mismatch: 1 + true"))),
+            ),
+            (
+                "let i = 0; while (i < 5) { i = i + 1; i + true; } i",
+                Some(Object::Error(String::from("type mismatch: 1 + true"))),
+            ),
+            (
+                "let i = 0; while (i < 3) { const c = i; i = i + 1; } i",
+                Some(Object::Int(3)),
+            ),
+            (
+                "let i = 0; while (i < 3) { let c = i; i = i + 1; } c",
+                Some(Object::Error(String::from("identifier not found: c"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let tests = vec![
+            // Early exit from a counting loop.
+            (
+                "let i = 0; while (i < 100) { if (i == 7) { break; } i = i + 1; } i",
+                Some(Object::Int(7)),
+            ),
+            (
+                "let i = 0; while (true) { i = i + 1; if (i > 2) { break } }",
+                Some(Object::Null),
+            ),
+            // Continue skipping even numbers.
+            (
+                r#"
+                let i = 0;
+                let odd = 0;
+                while (i < 10) {
+                    i = i + 1;
+                    if (i / 2 * 2 == i) { continue; }
+                    odd = odd + i;
+                }
+                odd
+                "#,
+                Some(Object::Int(25)),
+            ),
+            // Breaking the inner loop keeps the outer one going.
+            (
+                r#"
+                let i = 0;
+                let steps = 0;
+                while (i < 3) {
+                    i = i + 1;
+                    let j = 0;
+                    while (true) {
+                        j = j + 1;
+                        steps = steps + 1;
+                        if (j == i) { break; }
+                    }
+                };
+                [i, steps]
+                "#,
+                Some(Object::Array(vec![Object::Int(3), Object::Int(6)])),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_break_and_continue_outside_of_loop() {
+        let tests = vec![
+            ("break;", "break outside of loop"),
+            ("continue", "continue outside of loop"),
+            ("if (true) { break; }", "break outside of loop"),
+            (
+                "let f = fn() { continue; }; while (true) { f(); }",
+                "continue outside of loop",
+            ),
+            (
+                "let f = fn() { break; }; let i = 0; while (i < 3) { i = i + 1; f(); }",
+                "break outside of loop",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                Some(Object::Error(String::from(expect))),
+                eval(input),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_const_statement() {
+        let tests = vec![
+            ("const PI = 3; PI", Some(Object::Int(3))),
+            ("const PI = 3; PI * 2", Some(Object::Int(6))),
+            (
+                "const PI = 3; let PI = 4;",
+                Some(Object::Error(String::from("cannot assign to constant: PI"))),
+            ),
+            (
+                "const PI = 3; const PI = 4;",
+                Some(Object::Error(String::from("cannot assign to constant: PI"))),
+            ),
+            (
+                "const PI = 3; let [a, PI] = [1, 2];",
+                Some(Object::Error(String::from("cannot assign to constant: PI"))),
+            ),
+            ("let x = 1; const x = 2; x", Some(Object::Int(2))),
+            (
+                "let f = fn() { const y = 1; let y = 2; y }; f()",
+                Some(Object::Error(String::from("cannot assign to constant: y"))),
+            ),
+            (
+                "const len = 1;",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_const_shadowing_in_nested_scopes() {
+        let tests = vec![
+            (
+                "const PI = 3; let f = fn() { let PI = 4; PI }; f()",
+                Some(Object::Int(4)),
+            ),
+            (
+                "const PI = 3; let f = fn() { let PI = 4; PI }; f(); PI",
+                Some(Object::Int(3)),
+            ),
+            (
+                "const n = 3; let f = fn(n) { n * 2 }; f(5)",
+                Some(Object::Int(10)),
+            ),
+            (
+                "const PI = 3; let f = fn() { const PI = 4; fn() { let PI = 5; PI } }; f()()",
+                Some(Object::Int(5)),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_destructure_array() {
+        let tests = vec![
+            ("let [x, y] = [1, 2]; x", Some(Object::Int(1))),
+            ("let [x, y] = [1, 2]; y", Some(Object::Int(2))),
+            ("let [a, b, c] = [10, 20]; c", Some(Object::Null)),
+            ("let [a] = [1, 2, 3]; a", Some(Object::Int(1))),
+            (
+                "let pair = fn() { [3, 4] }; let [a, b] = pair(); a * b",
+                Some(Object::Int(12)),
+            ),
+            (
+                "let [n] = 5",
+                Some(Object::Error(String::from(
+                    "type mismatch: cannot destructure 5",
+                ))),
+            ),
+            (
+                "let [x, len] = [1, 2]",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_puts() {
+        let tests = vec![
+            ("puts(1)", Some(Object::Null), "1\n"),
+            (
+                "let x = 2; puts(x, x * 2, true); x",
+                Some(Object::Int(2)),
+                "2\n4\ntrue\n",
+            ),
+            ("puts()", Some(Object::Null), ""),
+            (
+                "puts(unknown)",
+                Some(Object::Error(String::from("identifier not found: unknown"))),
+                "",
+            ),
+            (
+                "let puts = fn(x) { x }; puts(3)",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: puts",
+                ))),
+                "",
+            ),
+        ];
+
+        for (input, expect, output) in tests {
+            assert_eq!((expect, String::from(output)), eval_with_output(input));
+        }
+    }
+
+    #[test]
+    fn test_builtins_live_in_the_environment() {
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let _evaluator = Evaluator::new(Rc::clone(&environment));
+
+        for builtin in Builtin::ALL {
+            assert_eq!(
+                Some(Object::Builtin(builtin)),
+                environment.borrow().get(String::from(builtin.name()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_inspect_environment() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+
+        let mut builtins: Vec<(String, Object)> = Builtin::ALL
+            .into_iter()
+            .map(|builtin| (String::from(builtin.name()), Object::Builtin(builtin)))
+            .collect();
+        builtins.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(builtins, e.inspect_environment());
+
+        e.eval(Parser::new(Lexer::new("let x = 42;")).parse().unwrap());
+        let bindings = e.inspect_environment();
+        assert!(bindings.contains(&(String::from("x"), Object::Int(42))));
+        assert_eq!(Builtin::ALL.len() + 1, bindings.len());
+
+        e.enclose_environment();
+        e.eval(Parser::new(Lexer::new("let x = 100;")).parse().unwrap());
+        let bindings = e.inspect_environment();
+        let xs: Vec<_> = bindings.iter().filter(|(name, _)| name == "x").collect();
+        assert_eq!(vec![&(String::from("x"), Object::Int(100))], xs);
+        assert!(bindings.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_builtin_shadowing() {
+        let tests = vec![
+            (
+                "let len = 5; len",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+            (
+                "let f = fn() { let len = 1; len }; f()",
+                Some(Object::Error(String::from(
+                    "cannot shadow builtin function: len",
+                ))),
+            ),
+            ("let f = fn(len) { len }; f(2)", Some(Object::Int(2))),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let mut e = Evaluator::new(Rc::clone(&environment));
+        environment.borrow_mut().set_allow_builtin_shadowing(true);
+
+        assert_eq!(
+            Some(Object::Int(5)),
+            e.eval(Parser::new(Lexer::new("let len = 5; len")).parse_program())
+        );
+    }
+
+    #[test]
+    fn test_len() {
+        let tests = vec![
+            ("len([])", Some(Object::Int(0))),
+            ("len([1, 2, 3])", Some(Object::Int(3))),
+            (
+                "len(1)",
+                Some(Object::Error(String::from(
+                    "argument to `len` not supported, got 1",
+                ))),
+            ),
+            (
+                "len([1], [2])",
+                Some(Object::Error(String::from(
+                    "wrong number of arguments: 1 expected but 2 given",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let tests = vec![
+            ("sort([3, 1, 2])", "[1, 2, 3]"),
+            (r#"sort(["banana", "apple"])"#, "[apple, banana]"),
+            ("sort([2.5, -1.0, 0.5])", "[-1.0, 0.5, 2.5]"),
+            ("sort([])", "[]"),
+            (
+                "let a = [2, 1]; let b = sort(a); [a, b]",
+                "[[2, 1], [1, 2]]",
+            ),
+            (
+                r#"sort([1, "a"])"#,
+                "ERROR: argument to `sort` not supported, cannot compare INTEGER with STRING",
+            ),
+            (
+                "sort([true, false])",
+                "ERROR: argument to `sort` not supported, cannot compare BOOLEAN with BOOLEAN",
+            ),
+            (
+                "sort([1.0, 0.0 / 0.0])",
+                "ERROR: argument to `sort` not supported, cannot compare FLOAT with FLOAT",
+            ),
+            ("sort(1)", "ERROR: argument to `sort` not supported, got 1"),
+            (
+                "sort([1], [2])",
+                "ERROR: wrong number of arguments: 1 expected but 2 given",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                Some(String::from(expect)),
+                eval(input).map(|object| object.to_string()),
+                "{input}"
+            );
+        }
+
+        assert!(matches!(eval(r#"sort([1, "a"])"#), Some(Object::Error(_))));
+    }
+
+    #[test]
+    fn test_format() {
+        let function = if cfg!(feature = "verbose-object-display") {
+            "fn(x) { x; }"
+        } else {
+            "fn(x) { ... }"
+        };
+        let tests = vec![
+            (r#"format("{} + {} = {}", 1, 2, 3)"#, "1 + 2 = 3"),
+            (r#"format("{}", fn(x) { x })"#, function),
+            (r#"format("[{}] {}", [1, "a"], true)"#, "[[1, a]] true"),
+            (r#"format("{{}} {}", 1)"#, "{} 1"),
+            (r#"format("plain")"#, "plain"),
+            (
+                r#"format("{} and {}", 1)"#,
+                "ERROR: format string has 2 placeholders but 1 values given",
+            ),
+            (
+                r#"format("{}", 1, 2)"#,
+                "ERROR: format string has 1 placeholders but 2 values given",
+            ),
+            (
+                r#"format("{")"#,
+                "ERROR: invalid format string: unmatched `{`",
+            ),
+            (
+                r#"format("a } b")"#,
+                "ERROR: invalid format string: unmatched `}`",
+            ),
+            (
+                "format(1)",
+                "ERROR: argument to `format` must be STRING, got 1",
+            ),
+            (
+                "format()",
+                "ERROR: wrong number of arguments: at least 1 expected but 0 given",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                Some(String::from(expect)),
+                eval(input).map(|object| object.to_string()),
+                "{input}"
+            );
+        }
+
+        assert!(matches!(eval(r#"format("{}")"#), Some(Object::Error(_))));
+        assert_eq!(
+            Some(Object::Str(String::from("1 + 2 = 3"))),
+            eval(r#"format("{} + {} = {}", 1, 2, 3)"#)
+        );
+    }
+
+    #[test]
+    fn test_assert() {
+        let tests = vec![
+            ("assert(true)", Object::Null),
+            ("assert(1 < 2, \"ordered\")", Object::Null),
+            ("assert(0)", Object::Null),
+            ("assert([])", Object::Null),
+            (
+                "assert(false)",
+                Object::Error(String::from(
+                    "assertion failed: expected a truthy value, got false",
+                )),
+            ),
+            (
+                "assert(if (false) { 1 })",
+                Object::Error(String::from(
+                    "assertion failed: expected a truthy value, got null",
+                )),
+            ),
+            (
+                "assert(1 > 2, \"one is not greater than two\")",
+                Object::Error(String::from(
+                    "assertion failed: one is not greater than two",
+                )),
+            ),
+            (
+                "assert(false, 42)",
+                Object::Error(String::from("assertion failed: 42")),
+            ),
+            (
+                "assert()",
+                Object::Error(String::from(
+                    "wrong number of arguments: 1 or 2 expected but 0 given",
+                )),
+            ),
+            (
+                "assert(true, \"a\", \"b\")",
+                Object::Error(String::from(
+                    "wrong number of arguments: 1 or 2 expected but 3 given",
+                )),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{input}");
+        }
+
+        assert_eq!(
+            Some(Object::Int(1)),
+            eval("let f = fn() { assert(true); 1 }; f()")
+        );
+        assert_eq!(
+            Some(Object::Error(String::from("assertion failed: stop"))),
+            eval("let f = fn() { assert(false, \"stop\"); 1 }; f()")
+        );
+    }
+
+    #[test]
+    fn test_type() {
+        let str = |s: &str| Object::Str(String::from(s));
+        let tests = vec![
+            ("type(1)", str("INTEGER")),
+            ("type(1.5)", str("FLOAT")),
+            ("type(\"a\")", str("STRING")),
+            ("type([1])", str("ARRAY")),
+            ("type({})", str("HASH")),
+            ("type(fn() { 1 })", str("FUNCTION")),
+            ("type(len)", str("BUILTIN")),
+            ("type(if (false) { 1 })", str("NULL")),
+            ("type(1) == type(2)", Object::Bool(true)),
+            ("type(1) == type(\"1\")", Object::Bool(false)),
+            (
+                "let describe = fn(x) { if (type(x) == type(1)) { x + 1 } else { len(x) } }; [describe(1), describe(\"abc\")]",
+                Object::Array(vec![Object::Int(2), Object::Int(3)]),
+            ),
+            (
+                "type()",
+                Object::Error(String::from(
+                    "wrong number of arguments: 1 expected but 0 given",
+                )),
+            ),
+            (
+                "type(1 + true)",
+                Object::Error(String::from("type mismatch: 1 + true")),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        let str = |s: &str| Object::Str(String::from(s));
+        let tests = vec![
+            ("inspect(1)", str("1")),
+            ("inspect(2.0)", str("2.0")),
+            ("inspect([1, [true, \"a\"]])", str("[1, [true, \"a\"]]")),
+            ("inspect(\"say \\\"hi\\\"\")", str("\"say \\\"hi\\\"\"")),
+            ("inspect({2: 1, 1: 2})", str("{1: 2, 2: 1}")),
+            ("inspect(puts)", str("builtin function puts")),
+            ("len(inspect([1, 2]))", Object::Int(6)),
+            (
+                "inspect(1, 2)",
+                Object::Error(String::from(
+                    "wrong number of arguments: 1 expected but 2 given",
+                )),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_is_error() {
+        let tests = vec![
+            ("is_error(1)", Object::Bool(false)),
+            ("is_error(if (false) { 1 })", Object::Bool(false)),
+            ("is_error(5 / 0)", Object::Bool(true)),
+            ("is_error(-true)", Object::Bool(true)),
+            ("is_error(unknown)", Object::Bool(true)),
+            (
+                "let safe_div = fn(a, b) { if (is_error(a / b)) { 0 } else { a / b } }; [safe_div(6, 3), safe_div(1, 0)]",
+                Object::Array(vec![Object::Int(2), Object::Int(0)]),
+            ),
+            (
+                "is_error()",
+                Object::Error(String::from(
+                    "wrong number of arguments: 1 expected but 0 given",
+                )),
+            ),
+            // Other calls still stop at an error argument.
+            (
+                "len(5 / 0)",
+                Object::Error(String::from("division by zero: 5 / 0")),
+            ),
+            (
+                "let f = fn(x) { true }; f(5 / 0)",
+                Object::Error(String::from("division by zero: 5 / 0")),
+            ),
+            (
+                "let check = is_error; check(5 / 0)",
+                Object::Bool(true),
+            ),
+            (
+                "missing(5 / 0)",
+                Object::Error(String::from("division by zero: 5 / 0")),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_is_error_handles_the_error() {
+        let input = r#"
+let fail = fn() { 1 / 0 };
+let recover = fn() { is_error(fail()) };
+let later = fn(x) { x + true };
+recover();
+later(1);
+"#;
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+
+        assert_eq!(
+            Some(Object::Error(String::from("type mismatch: 1 + true"))),
+            e.eval(Parser::new(Lexer::new(input)).parse_program()),
+        );
+        assert_eq!(vec![String::from("in later (1 argument)")], e.last_trace());
+    }
+
+    #[test]
+    fn test_builtin_object() {
+        assert_eq!(Some(Object::Builtin(Builtin::Puts)), eval("puts"));
+        assert_eq!(
+            "builtin function puts",
+            Object::Builtin(Builtin::Puts).to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_trace() {
+        let input = r#"
+let inner = fn(x) { x + true; };
+let middle = fn(x, y) { inner(x); };
+let outer = fn() { middle(1, 2); };
+outer();
+"#;
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+
+        assert_eq!(
+            Some(Object::Error(String::from("type mismatch: 1 + true"))),
+            e.eval(Parser::new(Lexer::new(input)).parse_program()),
+        );
+        assert_eq!(
+            vec![
+                String::from("in outer (0 arguments)"),
+                String::from("in middle (2 arguments)"),
+                String::from("in inner (1 argument)"),
+            ],
+            e.last_trace(),
+        );
+
+        e.eval(Parser::new(Lexer::new("fn(x) { x; }(1);")).parse_program());
+        assert!(e.last_trace().is_empty());
+
+        e.eval(Parser::new(Lexer::new("fn(x) { -true; }(1);")).parse_program());
+        assert_eq!(
+            vec![String::from("in <anonymous> (1 argument)")],
+            e.last_trace()
+        );
+    }
+
+    #[test]
+    fn test_error_in_arguments_bubbles_up() {
+        let input = "let f = fn(x) { 1; }; f(unknown);";
+
+        assert_eq!(
+            Some(Object::Error(String::from("identifier not found: unknown"))),
+            eval(input),
+        );
+    }
+
+    #[test]
+    fn test_nested_calls_in_arguments() {
+        let tests = vec![
+            (
+                "let inc = fn(x) { x + 1 }; inc(inc(inc(inc(inc(inc(0))))))",
+                Object::Int(6),
+            ),
+            (
+                "let add = fn(a, b) { a + b }; let twice = fn(f, x) { f(f(x, x), f(x, x)) };
+                 add(twice(add, add(1, 1)), twice(add, twice(add, 1)))",
+                Object::Int(24),
+            ),
+            (
+                "let n = 0; let bump = fn() { n = n + 1; n };
+                 let add = fn(a, b) { a + b }; add(bump(), add(bump(), add(bump(), n)))",
+                Object::Int(9),
+            ),
+            (
+                "let make = fn(x) { fn(y) { let z = x + y; z } };
+                 let f = make(1); f(f(f(make(2)(3))))",
+                Object::Int(8),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{input}");
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "environment is still borrowed")]
+    fn test_held_environment_borrow_fails_loudly() {
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let mut e = Evaluator::new(Rc::clone(&environment));
+        let _held = environment.borrow();
+
+        e.eval(Parser::new(Lexer::new("1")).parse_program());
+    }
+
+    #[test]
+    fn test_new_with_prelude() {
+        let mut e = Evaluator::new_with_prelude("let double = fn(x) { x * 2 };").unwrap();
+        let program = Parser::new(Lexer::new("double(5)")).parse_program();
+
+        assert_eq!(Some(Object::Int(10)), e.eval(program));
+
+        let program = Parser::new(Lexer::new("let double = 3; double")).parse_program();
+        assert_eq!(Some(Object::Int(3)), e.eval(program));
+    }
+
+    #[test]
+    fn test_new_with_prelude_errors() {
+        match Evaluator::new_with_prelude("let double = fn(x) { x * 2 ;") {
+            Err(MonkeyError::Parse(errors)) => assert!(!errors.is_empty()),
+            _ => panic!("a prelude that doesn't parse should fail"),
+        }
+
+        match Evaluator::new_with_prelude("let x = 1; let y = x + true; let z = 2;") {
+            Err(MonkeyError::Runtime(msg)) => assert_eq!("type mismatch: 1 + true", msg),
+            _ => panic!("a prelude that fails at runtime should fail"),
+        }
+    }
+
+    #[test]
+    fn test_load_prelude_keeps_trace() {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let prelude = "let f = fn(x) { x + true }; f(1);";
+
+        match e.load_prelude(prelude) {
+            Err(MonkeyError::Runtime(msg)) => assert_eq!("type mismatch: 1 + true", msg),
+            _ => panic!("a prelude that fails at runtime should fail"),
+        }
+        assert!(!e.last_trace().is_empty());
+    }
 }
 
 // let x = 5 * 5;
@@ -1,25 +1,190 @@
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
-use crate::ast::ast::{Identifier, Statement};
+#[cfg(feature = "verbose-object-display")]
+use crate::ast::ast::fmt_block;
+use crate::ast::ast::{fmt_float, fmt_str, Identifier, Statement};
+use crate::evaluator::builtins::Builtin;
 use crate::evaluator::environment::Environment;
+use crate::interpreter::interpreter::MonkeyError;
+
+/// What an `Object` is hashed by when used as a hash key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HashKey {
+    pub object_type: &'static str,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashableError {
+    pub object_type: &'static str,
+}
+
+impl fmt::Display for HashableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unusable as hash key: {}", self.object_type)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Object {
     Int(i64),
+    Float(f64),
     Bool(bool),
+    Str(String),
+    Array(Vec<Object>),
+    /// Pairs are keyed by the key's `HashKey`; the key object itself is kept
+    /// alongside the value so the hash can still be displayed.
+    Hash(HashMap<HashKey, (Object, Object)>),
     Function(Vec<Identifier>, Vec<Statement>, Rc<RefCell<Environment>>),
+    Builtin(Builtin),
     Null,
     ReturnValue(Box<Object>),
+    /// Carries `break` up to the innermost enclosing loop.
+    Break,
+    /// Carries `continue` up to the innermost enclosing loop.
+    Continue,
     Error(String),
 }
 
+impl Object {
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Object::Int(_) => "INTEGER",
+            Object::Float(_) => "FLOAT",
+            Object::Bool(_) => "BOOLEAN",
+            Object::Str(_) => "STRING",
+            Object::Array(_) => "ARRAY",
+            Object::Hash(_) => "HASH",
+            Object::Function(_, _, _) => "FUNCTION",
+            Object::Builtin(_) => "BUILTIN",
+            Object::Null => "NULL",
+            Object::ReturnValue(_) => "RETURN_VALUE",
+            Object::Break => "BREAK",
+            Object::Continue => "CONTINUE",
+            Object::Error(_) => "ERROR",
+        }
+    }
+
+    /// Turns an error object into an `Err`, so an embedder can use `?` on
+    /// what evaluation gave back. Every other object is `Ok`.
+    pub fn into_result(self) -> Result<Object, MonkeyError> {
+        match self {
+            Object::Error(msg) => Err(MonkeyError::Runtime(msg)),
+            object => Ok(object),
+        }
+    }
+
+    pub fn to_hash_key(&self) -> Result<HashKey, HashableError> {
+        let value = match *self {
+            Object::Int(value) => value as u64,
+            Object::Bool(value) => u64::from(value),
+            Object::Str(ref value) => {
+                let mut hasher = DefaultHasher::new();
+                value.hash(&mut hasher);
+                hasher.finish()
+            }
+            _ => {
+                return Err(HashableError {
+                    object_type: self.type_name(),
+                })
+            }
+        };
+
+        Ok(HashKey {
+            object_type: self.type_name(),
+            value,
+        })
+    }
+}
+
+/// Integers, floats and strings are ordered among their own kind; any other
+/// pair of objects can't be compared.
+impl PartialOrd for Object {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Object::Int(left), Object::Int(right)) => left.partial_cmp(right),
+            (Object::Float(left), Object::Float(right)) => left.partial_cmp(right),
+            (Object::Str(left), Object::Str(right)) => left.partial_cmp(right),
+            _ => None,
+        }
+    }
+}
+
+/// How many arrays and hashes deep `Display` goes before writing `...` in
+/// place of whatever is nested further in.
+pub const MAX_DISPLAY_DEPTH: usize = 32;
+
+/// Arrays and hashes are written the way Monkey source spells them, with a
+/// hash's pairs sorted by how their keys display so that the output doesn't
+/// depend on `HashMap`'s iteration order. Strings are written as they are,
+/// the way `puts` prints them, unless `{:#}` asks for them to be quoted and
+/// escaped as string literals.
 impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_nested(f, 0)
+    }
+}
+
+impl Object {
+    /// Writes the object as `Display` does, `depth` collections down.
+    fn fmt_nested(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
         match *self {
             Object::Int(ref value) => write!(f, "{value}"),
+            Object::Float(value) => fmt_float(f, value),
             Object::Bool(ref value) => write!(f, "{value}"),
+            Object::Str(ref value) if f.alternate() => fmt_str(f, value),
+            Object::Str(ref value) => write!(f, "{value}"),
+            Object::Array(_) | Object::Hash(_) if depth >= MAX_DISPLAY_DEPTH => write!(f, "..."),
+            Object::Array(ref objects) => {
+                write!(f, "[")?;
+
+                for (i, object) in objects.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    object.fmt_nested(f, depth + 1)?;
+                }
+
+                write!(f, "]")
+            }
+            Object::Hash(ref pairs) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in sorted_pairs(pairs).into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    key.fmt_nested(f, depth + 1)?;
+                    write!(f, ": ")?;
+                    value.fmt_nested(f, depth + 1)?;
+                }
+
+                write!(f, "}}")
+            }
+            #[cfg(feature = "verbose-object-display")]
+            Object::Function(ref params, ref body, _) => {
+                write!(f, "fn(")?;
+
+                for (i, Identifier(ref s)) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{s}")?;
+                }
+
+                write!(f, ") ")?;
+                fmt_block(f, body)
+            }
+            #[cfg(not(feature = "verbose-object-display"))]
             Object::Function(ref params, _, _) => {
                 let mut result = String::new();
 
@@ -33,9 +198,339 @@ impl fmt::Display for Object {
 
                 write!(f, "fn({result}) {{ ... }}")
             }
+            Object::Builtin(ref builtin) => write!(f, "{builtin}"),
             Object::Null => write!(f, "null"),
-            Object::ReturnValue(ref value) => write!(f, "{value}"),
-            Object::Error(ref value) => write!(f, "{value}"),
+            Object::ReturnValue(ref value) => value.fmt_nested(f, depth),
+            Object::Break => write!(f, "break"),
+            Object::Continue => write!(f, "continue"),
+            Object::Error(ref value) => write!(f, "ERROR: {value}"),
+        }
+    }
+}
+
+/// A hash's pairs in display order: by the key as displayed, then by its
+/// type so that `1` and `"1"` always come out the same way round.
+fn sorted_pairs(pairs: &HashMap<HashKey, (Object, Object)>) -> Vec<&(Object, Object)> {
+    let mut sorted: Vec<_> = pairs.values().collect();
+    sorted.sort_by_cached_key(|(key, _)| (key.to_string(), key.type_name()));
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::cmp::Ordering;
+    use std::error::Error;
+    use std::rc::Rc;
+
+    use crate::evaluator::environment::Environment;
+    use crate::evaluator::evaluator::Evaluator;
+    use crate::evaluator::object::Object;
+    use crate::interpreter::interpreter::MonkeyError;
+    use crate::lexer::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    fn eval(input: &str) -> Object {
+        let mut e = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        e.eval(Parser::new(Lexer::new(input)).parse().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_partial_ord() {
+        let tests = vec![
+            (Object::Int(1), Object::Int(2), Some(Ordering::Less)),
+            (Object::Int(2), Object::Int(2), Some(Ordering::Equal)),
+            (
+                Object::Float(2.5),
+                Object::Float(-1.0),
+                Some(Ordering::Greater),
+            ),
+            (Object::Float(f64::NAN), Object::Float(1.0), None),
+            (
+                Object::Str(String::from("apple")),
+                Object::Str(String::from("banana")),
+                Some(Ordering::Less),
+            ),
+            (Object::Int(1), Object::Float(1.0), None),
+            (Object::Int(1), Object::Str(String::from("1")), None),
+            (Object::Bool(false), Object::Bool(true), None),
+            (Object::Null, Object::Null, None),
+        ];
+
+        for (left, right, expect) in tests {
+            assert_eq!(expect, left.partial_cmp(&right), "{left:?} and {right:?}");
+        }
+    }
+
+    #[test]
+    fn test_into_result() {
+        assert_eq!(Object::Int(10), eval("5 + 5").into_result().unwrap());
+        assert_eq!(Object::Null, Object::Null.into_result().unwrap());
+
+        match eval("5 + true").into_result() {
+            Err(MonkeyError::Runtime(msg)) => assert_eq!("type mismatch: 5 + true", msg),
+            other => panic!("expected a runtime error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_into_result_works_with_question_mark() {
+        fn sum(input: &str) -> Result<i64, Box<dyn Error>> {
+            match eval(input).into_result()? {
+                Object::Int(value) => Ok(value),
+                other => Err(format!("expected an integer, got {other}").into()),
+            }
+        }
+
+        assert_eq!(7, sum("3 + 4").unwrap());
+
+        let err = sum("-true").unwrap_err();
+        assert!(err.downcast_ref::<MonkeyError>().is_some());
+        assert_eq!("unknown operator: -true", err.to_string());
+    }
+}
+
+#[cfg(test)]
+mod display {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use crate::ast::ast::{Expression, Identifier, Statement};
+    use crate::evaluator::builtins::Builtin;
+    use crate::evaluator::environment::Environment;
+    use crate::evaluator::object::{Object, MAX_DISPLAY_DEPTH};
+
+    fn function(params: &[&str]) -> Object {
+        Object::Function(
+            params
+                .iter()
+                .map(|s| Identifier(String::from(*s)))
+                .collect(),
+            vec![Statement::Expression(Expression::Identifier(Identifier(
+                String::from("x"),
+            )))],
+            Rc::new(RefCell::new(Environment::new())),
+        )
+    }
+
+    fn hash(pairs: Vec<(Object, Object)>) -> Object {
+        let pairs = pairs
+            .into_iter()
+            .map(|(key, value)| (key.to_hash_key().unwrap(), (key, value)))
+            .collect::<HashMap<_, _>>();
+
+        Object::Hash(pairs)
+    }
+
+    #[test]
+    fn test_scalars() {
+        let tests = vec![
+            (Object::Int(42), "42"),
+            (Object::Int(-7), "-7"),
+            (Object::Float(2.5), "2.5"),
+            (Object::Float(3.0), "3.0"),
+            (Object::Bool(true), "true"),
+            (Object::Bool(false), "false"),
+            (Object::Str(String::from("monkey")), "monkey"),
+            (Object::Null, "null"),
+            (Object::Break, "break"),
+            (Object::Continue, "continue"),
+        ];
+
+        for (object, expect) in tests {
+            assert_eq!(expect, object.to_string(), "{object:?}");
+        }
+    }
+
+    #[test]
+    fn test_collections() {
+        assert_eq!("[]", Object::Array(vec![]).to_string());
+        assert_eq!(
+            "[1, true, a, null]",
+            Object::Array(vec![
+                Object::Int(1),
+                Object::Bool(true),
+                Object::Str(String::from("a")),
+                Object::Null,
+            ])
+            .to_string()
+        );
+        assert_eq!(
+            "[[1], []]",
+            Object::Array(vec![
+                Object::Array(vec![Object::Int(1)]),
+                Object::Array(vec![])
+            ])
+            .to_string()
+        );
+
+        assert_eq!("{}", hash(vec![]).to_string());
+        assert_eq!(
+            "{one: 1}",
+            hash(vec![(Object::Str(String::from("one")), Object::Int(1))]).to_string()
+        );
+    }
+
+    #[test]
+    fn test_alternate_quotes_strings() {
+        let str = |s: &str| Object::Str(String::from(s));
+        let object = Object::Array(vec![
+            str("say \"hi\"\n"),
+            hash(vec![(str("k"), str("a\\b"))]),
+        ]);
+
+        assert_eq!("\"tab\\t\"", format!("{:#}", str("tab\t")));
+        assert_eq!("tab\t", format!("{}", str("tab\t")));
+        assert_eq!(r#"["say \"hi\"\n", {"k": "a\\b"}]"#, format!("{object:#}"));
+        assert_eq!("[say \"hi\"\n, {k: a\\b}]", object.to_string());
+    }
+
+    #[test]
+    fn test_hash_pairs_are_sorted_by_displayed_key() {
+        let str = |s: &str| Object::Str(String::from(s));
+
+        let pairs = vec![
+            (str("b"), Object::Int(2)),
+            (Object::Int(10), str("ten")),
+            (Object::Bool(true), Object::Null),
+            (str("a"), Object::Int(1)),
+            (Object::Int(2), str("two")),
+            (str("1"), str("one")),
+            (Object::Int(1), Object::Int(1)),
+        ];
+        let expect = "{1: 1, 1: one, 10: ten, 2: two, a: 1, b: 2, true: null}";
+
+        // Every insertion order gives the same output.
+        for rotation in 0..pairs.len() {
+            let mut pairs = pairs.clone();
+            pairs.rotate_left(rotation);
+            assert_eq!(expect, hash(pairs.clone()).to_string());
+
+            pairs.reverse();
+            assert_eq!(expect, hash(pairs).to_string());
+        }
+    }
+
+    #[test]
+    fn test_nested_collections() {
+        let inner = hash(vec![
+            (Object::Str(String::from("y")), Object::Array(vec![])),
+            (
+                Object::Str(String::from("x")),
+                Object::Array(vec![Object::Int(1), Object::Int(2)]),
+            ),
+        ]);
+        let object = Object::Array(vec![
+            Object::Int(0),
+            hash(vec![(Object::Int(1), inner.clone())]),
+            Object::Array(vec![inner, Object::Array(vec![Object::Null])]),
+        ]);
+
+        assert_eq!(
+            "[0, {1: {x: [1, 2], y: []}}, [{x: [1, 2], y: []}, [null]]]",
+            object.to_string()
+        );
+        assert_eq!(
+            object.to_string(),
+            Object::ReturnValue(Box::new(object)).to_string()
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting_is_cut_off() {
+        let nest = |levels: usize| {
+            (0..levels).fold(Object::Int(7), |object, _| Object::Array(vec![object]))
+        };
+
+        let at_cap = nest(MAX_DISPLAY_DEPTH);
+        assert_eq!(
+            format!(
+                "{}7{}",
+                "[".repeat(MAX_DISPLAY_DEPTH),
+                "]".repeat(MAX_DISPLAY_DEPTH)
+            ),
+            at_cap.to_string()
+        );
+
+        let past_cap = nest(MAX_DISPLAY_DEPTH + 10);
+        assert_eq!(
+            format!(
+                "{}...{}",
+                "[".repeat(MAX_DISPLAY_DEPTH),
+                "]".repeat(MAX_DISPLAY_DEPTH)
+            ),
+            past_cap.to_string()
+        );
+
+        let hash_past_cap = (0..MAX_DISPLAY_DEPTH + 1).fold(Object::Null, |object, _| {
+            hash(vec![(Object::Int(0), object)])
+        });
+        assert_eq!(
+            format!(
+                "{}...{}",
+                "{0: ".repeat(MAX_DISPLAY_DEPTH),
+                "}".repeat(MAX_DISPLAY_DEPTH)
+            ),
+            hash_past_cap.to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "verbose-object-display"))]
+    fn test_functions() {
+        assert_eq!("fn() { ... }", function(&[]).to_string());
+        assert_eq!("fn(x) { ... }", function(&["x"]).to_string());
+        assert_eq!("fn(x, y) { ... }", function(&["x", "y"]).to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "verbose-object-display")]
+    fn test_functions() {
+        assert_eq!("fn() { x; }", function(&[]).to_string());
+        assert_eq!("fn(x, y) { x; }", function(&["x", "y"]).to_string());
+    }
+
+    #[test]
+    fn test_builtins() {
+        for builtin in Builtin::ALL {
+            assert_eq!(
+                format!("builtin function {}", builtin.name()),
+                Object::Builtin(builtin).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            "ERROR: type mismatch: 1 + true",
+            Object::Error(String::from("type mismatch: 1 + true")).to_string()
+        );
+        assert_eq!("ERROR: ", Object::Error(String::new()).to_string());
+    }
+
+    #[test]
+    fn test_return_values_show_their_inner_value() {
+        let tests = vec![
+            (Object::ReturnValue(Box::new(Object::Int(10))), "10"),
+            (Object::ReturnValue(Box::new(Object::Null)), "null"),
+            (
+                Object::ReturnValue(Box::new(Object::ReturnValue(Box::new(Object::Bool(false))))),
+                "false",
+            ),
+            (
+                Object::ReturnValue(Box::new(Object::Error(String::from(
+                    "unknown operator: -true",
+                )))),
+                "ERROR: unknown operator: -true",
+            ),
+        ];
+
+        for (object, expect) in tests {
+            assert_eq!(expect, object.to_string(), "{object:?}");
         }
     }
 }
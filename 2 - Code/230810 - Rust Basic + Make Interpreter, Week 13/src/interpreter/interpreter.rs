@@ -0,0 +1,310 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::evaluator::environment::Environment;
+use crate::evaluator::evaluator::Evaluator;
+use crate::evaluator::object::Object;
+use crate::lexer::lexer::Lexer;
+use crate::parser::parser::{ParseError, Parser};
+
+/// Why `Interpreter::run` failed.
+#[derive(Debug, Clone)]
+pub enum MonkeyError {
+    /// The source didn't parse; nothing was evaluated.
+    Parse(Vec<ParseError>),
+    /// Evaluation stopped with an error object, whose message this is.
+    Runtime(String),
+}
+
+impl fmt::Display for MonkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MonkeyError::Parse(errors) => {
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+
+                    write!(f, "{error}")?;
+                }
+
+                Ok(())
+            }
+            MonkeyError::Runtime(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for MonkeyError {}
+
+/// Configures an `Interpreter`. Every option is off unless it's set, except
+/// that calls nest at most `DEFAULT_MAX_CALL_DEPTH` deep by default.
+///
+/// Each interpreter owns its environment, so two of them never see each
+/// other's bindings or limits, even when their runs are interleaved:
+///
+/// ```
+/// use monkey::evaluator::object::Object;
+/// use monkey::interpreter::interpreter::InterpreterBuilder;
+///
+/// let mut limited = InterpreterBuilder::new().fuel(100).capture_output().build();
+/// let mut unlimited = InterpreterBuilder::new().build();
+///
+/// limited.run("let x = 1; puts(x);").unwrap();
+/// unlimited.run("let x = 2;").unwrap();
+///
+/// assert_eq!(Object::Int(1), limited.run("x").unwrap());
+/// assert_eq!(Object::Int(2), unlimited.run("x").unwrap());
+/// assert_eq!("1\n", limited.output());
+///
+/// let spin = "let i = 0; while (i < 1000) { i = i + 1; } i";
+/// assert!(limited.run(spin).is_err());
+/// assert_eq!(Object::Int(1000), unlimited.run(spin).unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InterpreterBuilder {
+    max_depth: Option<usize>,
+    fuel: Option<u64>,
+    capture_output: bool,
+}
+
+impl InterpreterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits how deeply function calls may nest, in place of
+    /// `DEFAULT_MAX_CALL_DEPTH`.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Limits how many expressions each `run` may evaluate.
+    pub fn fuel(mut self, fuel: u64) -> Self {
+        self.fuel = Some(fuel);
+        self
+    }
+
+    /// Collects what `puts` writes, to be read with `Interpreter::output`,
+    /// instead of writing it to stdout.
+    pub fn capture_output(mut self) -> Self {
+        self.capture_output = true;
+        self
+    }
+
+    pub fn build(self) -> Interpreter {
+        let captured = self.capture_output.then(CapturedOutput::default);
+
+        let mut evaluator = match captured {
+            Some(ref captured) => Evaluator::with_output(captured.clone()),
+            None => Evaluator::new(Rc::new(RefCell::new(Environment::new()))),
+        };
+        if let Some(max_depth) = self.max_depth {
+            evaluator.set_max_call_depth(Some(max_depth));
+        }
+
+        Interpreter {
+            evaluator,
+            captured,
+            fuel: self.fuel,
+        }
+    }
+
+    /// Builds the interpreter and runs `prelude` in it before anything else.
+    /// Later runs see the prelude's bindings and may shadow them with `let`.
+    pub fn build_with_prelude(self, prelude: &str) -> Result<Interpreter, MonkeyError> {
+        let mut interpreter = self.build();
+        interpreter.evaluator.set_fuel(interpreter.fuel);
+        interpreter.evaluator.load_prelude(prelude)?;
+
+        Ok(interpreter)
+    }
+}
+
+/// Runs Monkey source in a session that keeps its bindings between runs.
+/// Build one with `InterpreterBuilder`.
+pub struct Interpreter {
+    evaluator: Evaluator,
+    captured: Option<CapturedOutput>,
+    fuel: Option<u64>,
+}
+
+impl Interpreter {
+    /// An interpreter with default options whose runs start from the
+    /// bindings `prelude` defines. Fails with the prelude's own errors.
+    pub fn with_prelude(prelude: &str) -> Result<Self, MonkeyError> {
+        InterpreterBuilder::new().build_with_prelude(prelude)
+    }
+
+    /// Parses and evaluates `source`. A program that evaluates to nothing,
+    /// such as a lone `let`, gives `Object::Null`.
+    pub fn run(&mut self, source: &str) -> Result<Object, MonkeyError> {
+        let program = Parser::new(Lexer::new(source))
+            .parse()
+            .map_err(|failure| MonkeyError::Parse(failure.errors))?;
+
+        self.evaluator.set_fuel(self.fuel);
+
+        self.evaluator
+            .eval(program)
+            .unwrap_or(Object::Null)
+            .into_result()
+    }
+
+    /// Takes what `puts` has written since the last call. Always empty unless
+    /// the interpreter was built with `capture_output`.
+    pub fn output(&mut self) -> String {
+        match self.captured {
+            Some(ref captured) => String::from_utf8_lossy(&captured.0.take()).into_owned(),
+            None => String::new(),
+        }
+    }
+
+    /// Call frames that were active when the last runtime error was created.
+    pub fn last_trace(&self) -> &[String] {
+        self.evaluator.last_trace()
+    }
+}
+
+/// A writer that the interpreter can still read after handing it to the
+/// evaluator.
+#[derive(Clone, Default)]
+struct CapturedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evaluator::object::Object;
+    use crate::interpreter::interpreter::{Interpreter, InterpreterBuilder, MonkeyError};
+    use crate::parser::parser::ParseErrorKind;
+
+    #[test]
+    fn test_run_keeps_bindings() {
+        let mut interpreter = InterpreterBuilder::new().build();
+
+        assert_eq!(
+            Object::Null,
+            interpreter.run("let add = fn(a, b) { a + b };").unwrap()
+        );
+        assert_eq!(Object::Int(3), interpreter.run("add(1, 2)").unwrap());
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let mut interpreter = InterpreterBuilder::new().build();
+
+        match interpreter.run("let = 1;") {
+            Err(MonkeyError::Parse(errors)) => {
+                assert_eq!(ParseErrorKind::ExpectedIdentifier, *errors[0].kind())
+            }
+            result => panic!("expected a parse error, got {result:?}"),
+        }
+
+        let err = interpreter.run("1 + true").unwrap_err();
+        assert_eq!("type mismatch: 1 + true", err.to_string());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut interpreter = InterpreterBuilder::new().max_depth(20).build();
+        interpreter
+            .run("let down = fn(n) { if (n == 0) { 0 } else { down(n - 1) } };")
+            .unwrap();
+
+        assert_eq!(Object::Int(0), interpreter.run("down(19)").unwrap());
+        assert_eq!(
+            "maximum call depth of 20 exceeded",
+            interpreter.run("down(20)").unwrap_err().to_string()
+        );
+        assert_eq!(20, interpreter.last_trace().len());
+    }
+
+    #[test]
+    fn test_fuel() {
+        let mut interpreter = InterpreterBuilder::new().fuel(50).build();
+
+        assert_eq!(
+            "out of fuel",
+            interpreter.run("while (true) { }").unwrap_err().to_string()
+        );
+        // Every run starts with a full tank.
+        assert_eq!(Object::Int(6), interpreter.run("1 + 2 + 3").unwrap());
+    }
+
+    #[test]
+    fn test_capture_output() {
+        let mut interpreter = InterpreterBuilder::new().capture_output().build();
+
+        interpreter.run(r#"puts("hello", 1); puts([2])"#).unwrap();
+        assert_eq!("hello\n1\n[2]\n", interpreter.output());
+        assert_eq!("", interpreter.output());
+
+        let mut uncaptured = InterpreterBuilder::new().build();
+        uncaptured.run("1").unwrap();
+        assert_eq!("", uncaptured.output());
+    }
+
+    const PRELUDE: &str = "
+        let square = fn(x) { x * x };
+        const unit = 0.5;
+        let sum_of_squares = fn(a, b) { square(a) + square(b) };
+    ";
+
+    #[test]
+    fn test_prelude_functions_are_callable() {
+        let mut interpreter = Interpreter::with_prelude(PRELUDE).unwrap();
+
+        assert_eq!(
+            Object::Int(25),
+            interpreter.run("sum_of_squares(3, 4)").unwrap()
+        );
+        assert_eq!(Object::Float(0.5), interpreter.run("unit").unwrap());
+    }
+
+    #[test]
+    fn test_prelude_errors_fail_construction() {
+        match Interpreter::with_prelude("let x = 1 + true;") {
+            Err(err) => assert_eq!("type mismatch: 1 + true", err.to_string()),
+            Ok(_) => panic!("expected the prelude to fail"),
+        }
+
+        match Interpreter::with_prelude("let = 1;") {
+            Err(MonkeyError::Parse(errors)) => {
+                assert_eq!(ParseErrorKind::ExpectedIdentifier, *errors[0].kind())
+            }
+            Err(err) => panic!("expected a parse error, got {err:?}"),
+            Ok(_) => panic!("expected the prelude to fail"),
+        }
+    }
+
+    #[test]
+    fn test_prelude_names_can_be_shadowed() {
+        let mut interpreter = InterpreterBuilder::new()
+            .capture_output()
+            .build_with_prelude(PRELUDE)
+            .unwrap();
+
+        interpreter
+            .run("let square = fn(x) { x + x }; const unit = 6;")
+            .unwrap();
+        assert_eq!(Object::Int(6), interpreter.run("square(3)").unwrap());
+        assert_eq!(Object::Int(6), interpreter.run("unit").unwrap());
+        // The prelude's own functions still see the prelude's bindings.
+        assert_eq!(
+            Object::Int(25),
+            interpreter.run("sum_of_squares(3, 4)").unwrap()
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::interpreter::interpreter::InterpreterBuilder;
+
+/// How running one Monkey test file went.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestOutcome {
+    /// The whole file evaluated without producing an error object.
+    Passed,
+    /// The file couldn't be read or parsed, or evaluating it gave an error,
+    /// such as a failed `assert`. This is that error's message.
+    Failed(String),
+}
+
+impl TestOutcome {
+    pub fn is_passed(&self) -> bool {
+        *self == TestOutcome::Passed
+    }
+}
+
+/// Evaluates `source` in an interpreter of its own, so no test sees another's
+/// bindings. What it prints with `puts` is discarded.
+pub fn run_test_source(source: &str) -> TestOutcome {
+    let mut interpreter = InterpreterBuilder::new().capture_output().build();
+
+    match interpreter.run(source) {
+        Ok(_) => TestOutcome::Passed,
+        Err(err) => TestOutcome::Failed(err.to_string()),
+    }
+}
+
+/// Reads the file at `path` and runs it with `run_test_source`.
+pub fn run_test_file(path: &Path) -> TestOutcome {
+    match fs::read_to_string(path) {
+        Ok(source) => run_test_source(&source),
+        Err(err) => TestOutcome::Failed(format!("cannot read file: {err}")),
+    }
+}
+
+/// Whether `path` names a test file, i.e. one ending in `_test.monkey`.
+pub fn is_test_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with("_test.monkey"))
+}
+
+/// The outcome of each test file, in the order they ran. Displays as one line
+/// per file followed by a tally.
+#[derive(Debug, Clone, Default)]
+pub struct TestSummary {
+    pub results: Vec<(String, TestOutcome)>,
+}
+
+impl TestSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs each `(name, source)` pair with `run_test_source`.
+    pub fn run_sources<'a>(sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut summary = Self::new();
+
+        for (name, source) in sources {
+            summary.record(name, run_test_source(source));
+        }
+
+        summary
+    }
+
+    pub fn record(&mut self, name: &str, outcome: TestOutcome) {
+        self.results.push((String::from(name), outcome));
+    }
+
+    pub fn passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| outcome.is_passed())
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    /// True if no test failed, including when there were none.
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+}
+
+impl fmt::Display for TestSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, outcome) in &self.results {
+            match outcome {
+                TestOutcome::Passed => writeln!(f, "PASS {name}")?,
+                TestOutcome::Failed(msg) => {
+                    // Parse failures span several lines; indent the rest.
+                    writeln!(f, "FAIL {name}: {}", msg.replace('\n', "\n    "))?
+                }
+            }
+        }
+
+        write!(
+            f,
+            "{} passed; {} failed; {} total",
+            self.passed(),
+            self.failed(),
+            self.results.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::interpreter::test_runner::{
+        is_test_file, run_test_file, run_test_source, TestOutcome, TestSummary,
+    };
+
+    #[test]
+    fn test_passing_source() {
+        let source = "
+            let double = fn(x) { x * 2 };
+            assert(double(2) == 4, \"double(2)\");
+            assert(double(0) == 0);
+            puts(\"not shown\");
+        ";
+
+        assert_eq!(TestOutcome::Passed, run_test_source(source));
+        assert_eq!(TestOutcome::Passed, run_test_source(""));
+    }
+
+    #[test]
+    fn test_failing_source() {
+        let tests = vec![
+            (
+                "assert(1 + 1 == 3, \"arithmetic\"); assert(true);",
+                "assertion failed: arithmetic",
+            ),
+            ("let x = 1; x + true;", "type mismatch: 1 + true"),
+            (
+                "let f = fn() { missing }; f(); 1",
+                "identifier not found: missing",
+            ),
+        ];
+
+        for (source, msg) in tests {
+            assert_eq!(
+                TestOutcome::Failed(String::from(msg)),
+                run_test_source(source),
+                "{source}"
+            );
+        }
+
+        match run_test_source("let = 1;") {
+            TestOutcome::Failed(msg) => assert!(msg.contains("Expected Identifier"), "{msg}"),
+            TestOutcome::Passed => panic!("a parse error should fail the test"),
+        }
+    }
+
+    #[test]
+    fn test_sources_run_in_isolation() {
+        let summary = TestSummary::run_sources([
+            ("defines_test.monkey", "let shared = 1;"),
+            ("uses_test.monkey", "shared"),
+        ]);
+
+        assert!(summary.results[0].1.is_passed());
+        assert_eq!(
+            TestOutcome::Failed(String::from("identifier not found: shared")),
+            summary.results[1].1
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = TestSummary::run_sources([
+            ("a_test.monkey", "assert(true);"),
+            ("b_test.monkey", "assert(false, \"b\");"),
+            ("c_test.monkey", "1"),
+        ]);
+
+        assert_eq!(2, summary.passed());
+        assert_eq!(1, summary.failed());
+        assert!(!summary.is_success());
+        assert_eq!(
+            "PASS a_test.monkey\n\
+             FAIL b_test.monkey: assertion failed: b\n\
+             PASS c_test.monkey\n\
+             2 passed; 1 failed; 3 total",
+            summary.to_string()
+        );
+
+        let empty = TestSummary::new();
+        assert!(empty.is_success());
+        assert_eq!("0 passed; 0 failed; 0 total", empty.to_string());
+    }
+
+    #[test]
+    fn test_missing_file_fails() {
+        match run_test_file(Path::new("no/such/dir/missing_test.monkey")) {
+            TestOutcome::Failed(msg) => assert!(msg.starts_with("cannot read file: "), "{msg}"),
+            TestOutcome::Passed => panic!("a missing file should fail"),
+        }
+    }
+
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file(Path::new("dir/math_test.monkey")));
+        assert!(is_test_file(Path::new("_test.monkey")));
+        assert!(!is_test_file(Path::new("dir/math.monkey")));
+        assert!(!is_test_file(Path::new("dir/math_test.monkey.bak")));
+        assert!(!is_test_file(Path::new("dir/math_test.rs")));
+    }
+}
@@ -1,10 +1,19 @@
-use crate::token::token::Token;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::token::token::{Position, Span, Token};
 
 pub struct Lexer<'a> {
     input: &'a str,
     position: usize,
     read_position: usize,
-    ch: u8,
+    ch: char,
+    line: usize,
+    column: usize,
+    token_position: Position,
+    token_start: usize,
+    finished: bool,
+    keyword_overrides: HashMap<String, Token>,
 }
 
 impl<'a> Lexer<'a> {
@@ -13,78 +22,142 @@ impl<'a> Lexer<'a> {
             input,
             position: 0,
             read_position: 0,
-            ch: 0,
+            ch: '\0',
+            line: 1,
+            column: 0,
+            token_position: Position::default(),
+            token_start: 0,
+            finished: false,
+            keyword_overrides: HashMap::new(),
         };
 
         lexer.read_char();
         lexer
     }
 
+    /// Lexes each word in `extra` as the token it maps to, on top of the
+    /// built-in keywords. An entry for a built-in keyword replaces it, so
+    /// mapping `"fn"` to an identifier frees the name up.
+    pub fn with_keyword_override(mut self, extra: HashMap<String, Token>) -> Self {
+        self.keyword_overrides.extend(extra);
+        self
+    }
+
+    /// Where the most recently returned token starts, counted in characters
+    /// rather than bytes so that it lines up with what a terminal shows.
+    pub fn token_position(&self) -> Position {
+        self.token_position
+    }
+
+    /// Like `next_token`, but also returns the byte range the token was read
+    /// from. Only whitespace lies between consecutive spans, and `Eof` is the
+    /// empty range at the end of the input.
+    pub fn next_token_with_span(&mut self) -> (Token, Range<usize>) {
+        let tok = self.next_token();
+        let span = self.token_span();
+
+        (tok, span.start..span.end)
+    }
+
+    /// The bytes the most recently returned token was read from.
+    pub fn token_span(&self) -> Span {
+        let end = self.position.min(self.input.len());
+
+        Span {
+            start: self.token_start.min(end),
+            end,
+        }
+    }
+
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = 0;
-        } else {
-            self.ch = self.input.as_bytes()[self.read_position];
+        if self.ch == '\n' {
+            self.line += 1;
+            self.column = 0;
         }
 
+        self.ch = self.char_at(self.read_position);
+        self.column += 1;
+
         self.position = self.read_position;
-        self.read_position += 1;
+        self.read_position += self.ch.len_utf8();
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn char_at(&self, position: usize) -> char {
+        self.input
+            .get(position..)
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or('\0')
     }
 
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
 
+        self.token_position = Position {
+            line: self.line,
+            column: self.column,
+        };
+        self.token_start = self.position;
+
         let tok = match self.ch {
-            b'=' => {
-                if self.peek_char() == b'=' {
+            '=' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::Equal
                 } else {
                     Token::Assign
                 }
             }
-            b'+' => Token::Plus,
-            b'-' => Token::Minus,
-            b'!' => {
-                if self.peek_char() == b'=' {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '!' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::NotEqual
                 } else {
                     Token::Bang
                 }
             }
-            b'*' => Token::Asterisk,
-            b'/' => Token::Slash,
-            b'<' => {
-                if self.peek_char() == b'=' {
+            '*' => Token::Asterisk,
+            '/' => Token::Slash,
+            '<' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::LessThanEqual
                 } else {
                     Token::LessThan
                 }
             }
-            b'>' => {
-                if self.peek_char() == b'=' {
+            '>' => {
+                if self.peek_char() == '=' {
                     self.read_char();
                     Token::GreaterThanEqual
                 } else {
                     Token::GreaterThan
                 }
             }
-            b',' => Token::Comma,
-            b';' => Token::Semicolon,
-            b'(' => Token::Lparen,
-            b')' => Token::Rparen,
-            b'{' => Token::Lbrace,
-            b'}' => Token::Rbrace,
-            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
-                return self.read_identifier();
-            }
-            b'0'..=b'9' => {
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            '(' => Token::Lparen,
+            ')' => Token::Rparen,
+            '{' => Token::Lbrace,
+            '}' => Token::Rbrace,
+            '[' => Token::Lbracket,
+            ']' => Token::Rbracket,
+            '"' => self.read_string(),
+            'r' if self.char_at(self.read_position) == '"' => self.read_raw_string(),
+            '0'..='9' => {
                 return self.read_number();
             }
-            0 => Token::Eof,
-            _ => Token::Illegal,
+            ch if Self::is_identifier_start(ch) => {
+                return self.read_identifier();
+            }
+            '\0' if self.is_at_end() => Token::Eof,
+            ch => Token::Illegal(ch),
         };
 
         self.read_char();
@@ -95,7 +168,7 @@ impl<'a> Lexer<'a> {
     fn skip_whitespace(&mut self) {
         loop {
             match self.ch {
-                b' ' | b'\t' | b'\n' | b'\r' => {
+                ' ' | '\t' | '\n' | '\r' => {
                     self.read_char();
                 }
                 _ => {
@@ -105,75 +178,228 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn peek_char(&mut self) -> u8 {
-        if self.read_position >= self.input.len() {
-            0
-        } else {
-            self.input.as_bytes()[self.read_position]
-        }
+    fn peek_char(&mut self) -> char {
+        self.char_at(self.read_position)
     }
 
-    fn read_identifier(&mut self) -> Token {
-        let position = self.position;
+    fn is_identifier_start(ch: char) -> bool {
+        ch.is_alphabetic() || ch == '_'
+    }
+
+    fn is_identifier_continue(ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_'
+    }
+
+    /// Reads a string literal, replacing each escape with what it stands
+    /// for: `\n`, `\t`, `\\`, `\"`, or `\u{...}` with one to six hex digits.
+    /// A literal with any other escape is read up to its closing quote all
+    /// the same, and comes out as `InvalidEscape` naming the first one.
+    fn read_string(&mut self) -> Token {
+        let mut value = String::new();
+        let mut invalid = None;
 
         loop {
+            self.read_char();
+
             match self.ch {
-                b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                '"' => break,
+                '\0' if self.is_at_end() => return Token::Illegal('"'),
+                '\\' => {
                     self.read_char();
+
+                    let escaped = match self.ch {
+                        'n' => Ok('\n'),
+                        't' => Ok('\t'),
+                        '\\' => Ok('\\'),
+                        '"' => Ok('"'),
+                        'u' => self.read_unicode_escape(),
+                        '\0' if self.is_at_end() => return Token::Illegal('"'),
+                        ch => Err(format!("\\{ch}")),
+                    };
+
+                    match escaped {
+                        Ok(ch) => value.push(ch),
+                        Err(escape) => {
+                            invalid.get_or_insert(escape);
+                        }
+                    }
                 }
-                _ => {
-                    break;
-                }
+                ch => value.push(ch),
             }
         }
 
+        match invalid {
+            Some(escape) => Token::InvalidEscape(escape),
+            None => Token::Str(value),
+        }
+    }
+
+    /// Reads the rest of a `\u{...}` escape, starting on the `u`. On failure
+    /// gives the escape as far as it goes, stopping before a closing quote.
+    fn read_unicode_escape(&mut self) -> Result<char, String> {
+        let mut escape = String::from("\\u");
+
+        if self.peek_char() != '{' {
+            return Err(escape);
+        }
+
+        self.read_char();
+        escape.push('{');
+
+        while self.peek_char().is_ascii_hexdigit() {
+            self.read_char();
+            escape.push(self.ch);
+        }
+
+        let digits = escape.len() - "\\u{".len();
+        if self.peek_char() != '}' || !(1..=6).contains(&digits) {
+            return Err(escape);
+        }
+
+        self.read_char();
+        let ch = u32::from_str_radix(&escape[3..], 16)
+            .ok()
+            .and_then(char::from_u32);
+        escape.push('}');
+
+        ch.ok_or(escape)
+    }
+
+    /// Reads `r"..."`, which ends at the first `"` and has no escapes.
+    fn read_raw_string(&mut self) -> Token {
+        self.read_char();
+        let position = self.read_position;
+
+        loop {
+            self.read_char();
+
+            match self.ch {
+                '"' => break,
+                '\0' if self.is_at_end() => return Token::Illegal('"'),
+                _ => {}
+            }
+        }
+
+        Token::Str(String::from(&self.input[position..self.position]))
+    }
+
+    fn read_identifier(&mut self) -> Token {
+        let position = self.position;
+
+        while Self::is_identifier_continue(self.ch) {
+            self.read_char();
+        }
+
         let literal = &self.input[position..self.position];
 
+        if let Some(tok) = self.keyword_overrides.get(literal) {
+            return tok.clone();
+        }
+
         match literal {
             "fn" => Token::Function,
             "let" => Token::Let,
+            "const" => Token::Const,
             "true" => Token::Bool(true),
             "false" => Token::Bool(false),
             "if" => Token::If,
             "else" => Token::Else,
             "return" => Token::Return,
+            "while" => Token::While,
+            "break" => Token::Break,
+            "continue" => Token::Continue,
             _ => Token::Ident(String::from(literal)),
         }
     }
 
+    /// Reads a decimal, `0x` hexadecimal or `0b` binary integer, or a decimal
+    /// float. Digits may be separated by `_`. Any letters or digits running
+    /// on from the literal belong to it, so `0b2` and `123abc` are each one
+    /// illegal token rather than a number followed by an identifier.
     fn read_number(&mut self) -> Token {
         let position = self.position;
-        let mut is_floating_point = false;
 
-        loop {
-            match self.ch {
-                b'0'..=b'9' => {
-                    self.read_char();
-                }
-                b'.' => {
-                    self.read_char();
-                    is_floating_point = true;
-                }
-                _ => {
-                    break;
-                }
-            }
+        while Self::is_identifier_continue(self.ch) || self.ch == '.' {
+            self.read_char();
         }
 
         let literal = &self.input[position..self.position];
+        // A malformed literal is reported by its first digit, which is where
+        // the parser will point the error at.
+        let illegal = Token::Illegal(self.char_at(position));
+
+        let (radix, body) = match literal.get(..2) {
+            Some("0x" | "0X") => (16, &literal[2..]),
+            Some("0b" | "0B") => (2, &literal[2..]),
+            _ => (10, literal),
+        };
+
+        if radix == 10 {
+            if let Some((integer, fraction)) = body.split_once('.') {
+                let float = Self::digits(integer, 10).and_then(|integer| {
+                    // `1.` is a float, like `1.0`.
+                    let fraction = if fraction.is_empty() {
+                        String::new()
+                    } else {
+                        Self::digits(fraction, 10)?
+                    };
 
-        if is_floating_point {
-            Token::Double(literal.parse::<f64>().unwrap())
-        } else {
-            Token::Int(literal.parse::<i64>().unwrap())
+                    format!("{integer}.{fraction}").parse::<f64>().ok()
+                });
+
+                return float.map_or(illegal, Token::Float);
+            }
+        }
+
+        // The sign is a separate token, so literals are lexed unsigned and
+        // range-checked by the parser once it knows whether they are negated.
+        match Self::digits(body, radix) {
+            Some(digits) => match u64::from_str_radix(&digits, radix) {
+                Ok(int) => Token::Int(int),
+                Err(_) => Token::BigInt(String::from(literal)),
+            },
+            None => illegal,
         }
     }
+
+    /// `text` without its `_` separators, if it's digits in `radix` with
+    /// every separator between two of them.
+    fn digits(text: &str, radix: u32) -> Option<String> {
+        if text.is_empty() || text.starts_with('_') || text.ends_with('_') {
+            return None;
+        }
+
+        if !text.chars().all(|ch| ch == '_' || ch.is_digit(radix)) {
+            return None;
+        }
+
+        Some(text.chars().filter(|&ch| ch != '_').collect())
+    }
+}
+
+/// Yields every token up to and including the final `Token::Eof`.
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if self.finished {
+            return None;
+        }
+
+        let tok = self.next_token();
+        self.finished = tok == Token::Eof;
+
+        Some(tok)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::ops::Range;
+
     use crate::lexer::lexer::Lexer;
-    use crate::token::token::Token;
+    use crate::token::token::{Position, Token};
 
     #[test]
     fn test_next_token() {
@@ -199,7 +425,7 @@ if (5 < 10) {
 10 <= 9;
 10 >= 9;
 
-let pi = 3.14;
+let rate = 2.5;
 let e = 2.71;
 let mul = fn(x, y) {
     x * y;
@@ -289,14 +515,14 @@ let answer = mul(pi, e);
             Token::Int(9),
             Token::Semicolon,
             Token::Let,
-            Token::Ident(String::from("pi")),
+            Token::Ident(String::from("rate")),
             Token::Assign,
-            Token::Double(3.14),
+            Token::Float(2.5),
             Token::Semicolon,
             Token::Let,
             Token::Ident(String::from("e")),
             Token::Assign,
-            Token::Double(2.71),
+            Token::Float(2.71),
             Token::Semicolon,
             Token::Let,
             Token::Ident(String::from("mul")),
@@ -334,4 +560,406 @@ let answer = mul(pi, e);
             assert_eq!(expect, tok);
         }
     }
+
+    #[test]
+    fn test_keyword_override() {
+        let extra = HashMap::from([
+            (String::from("yield"), Token::Return),
+            (String::from("fn"), Token::Ident(String::from("fn"))),
+        ]);
+        let tests = vec![
+            Token::Return,
+            Token::Ident(String::from("fn")),
+            Token::Ident(String::from("yielded")),
+            Token::Let,
+            Token::Eof,
+        ];
+
+        let lexer = Lexer::new("yield fn yielded let").with_keyword_override(extra);
+        assert_eq!(tests, lexer.collect::<Vec<Token>>());
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let input = "let 이름 = π_2 + αβγ; 🙂";
+        let tests = vec![
+            Token::Let,
+            Token::Ident(String::from("이름")),
+            Token::Assign,
+            Token::Ident(String::from("π_2")),
+            Token::Plus,
+            Token::Ident(String::from("αβγ")),
+            Token::Semicolon,
+            Token::Illegal('🙂'),
+            Token::Eof,
+        ];
+
+        assert_eq!(tests, Lexer::new(input).collect::<Vec<Token>>());
+    }
+
+    #[test]
+    fn test_strings_and_hashes() {
+        let input = r#""foobar" "foo bar" "안녕 🙂" {"a": 1} "open"#;
+        let tests = vec![
+            Token::Str(String::from("foobar")),
+            Token::Str(String::from("foo bar")),
+            Token::Str(String::from("안녕 🙂")),
+            Token::Lbrace,
+            Token::Str(String::from("a")),
+            Token::Colon,
+            Token::Int(1),
+            Token::Rbrace,
+            Token::Illegal('"'),
+            Token::Eof,
+        ];
+
+        assert_eq!(tests, Lexer::new(input).collect::<Vec<Token>>());
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let str = |value: &str| vec![Token::Str(String::from(value)), Token::Eof];
+        let invalid = |escape: &str| vec![Token::InvalidEscape(String::from(escape)), Token::Eof];
+        let tests = vec![
+            (r#""a\nb""#, str("a\nb")),
+            (r#""\t\\\"""#, str("\t\\\"")),
+            (r#""\u{41}\u{1F600}""#, str("A😀")),
+            (r#""\u{0}""#, str("\0")),
+            (r#"r"a\nb""#, str("a\\nb")),
+            (r#"r"""#, str("")),
+            (r#""a\qb""#, invalid("\\q")),
+            (r#""\u12""#, invalid("\\u")),
+            (r#""\u{}""#, invalid("\\u{")),
+            (r#""\u{12""#, invalid("\\u{12")),
+            (r#""\u{1234567}""#, invalid("\\u{1234567")),
+            (r#""\u{D800}""#, invalid("\\u{D800}")),
+            (r#""\q\u{D800}""#, invalid("\\q")),
+            (r#""abc\"#, vec![Token::Illegal('"'), Token::Eof]),
+            (r#"r"abc"#, vec![Token::Illegal('"'), Token::Eof]),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, Lexer::new(input).collect::<Vec<Token>>(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_raw_prefix_needs_a_quote() {
+        let tests = vec![
+            Token::Ident(String::from("r")),
+            Token::Str(String::from("a")),
+            Token::Ident(String::from("rx")),
+            Token::Eof,
+        ];
+
+        assert_eq!(tests, Lexer::new(r#"r "a" rx"#).collect::<Vec<Token>>());
+    }
+
+    #[test]
+    fn test_token_position() {
+        let input = "let 변수 = 1;\n  π + 2";
+        let tests = vec![
+            (Token::Let, 1, 1),
+            (Token::Ident(String::from("변수")), 1, 5),
+            (Token::Assign, 1, 8),
+            (Token::Int(1), 1, 10),
+            (Token::Semicolon, 1, 11),
+            (Token::Ident(String::from("π")), 2, 3),
+            (Token::Plus, 2, 5),
+            (Token::Int(2), 2, 7),
+            (Token::Eof, 2, 8),
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for (expect, line, column) in tests {
+            assert_eq!(expect, lexer.next_token());
+            assert_eq!(Position { line, column }, lexer.token_position());
+            assert_eq!(
+                lexer.token_position(),
+                lexer.token_span().start_position(input)
+            );
+        }
+    }
+
+    fn spans_of(input: &str) -> Vec<(Token, Range<usize>)> {
+        let mut lexer = Lexer::new(input);
+        let mut spans = Vec::new();
+
+        loop {
+            let (tok, span) = lexer.next_token_with_span();
+            let finished = tok == Token::Eof;
+            spans.push((tok, span));
+
+            if finished {
+                return spans;
+            }
+        }
+    }
+
+    /// Rebuilds `input` from its token spans and the gaps between them,
+    /// checking that every gap is whitespace.
+    fn reconstruct(input: &str) -> String {
+        let mut output = String::new();
+        let mut end = 0;
+
+        for (_, span) in spans_of(input) {
+            let gap = &input[end..span.start];
+            assert!(gap.chars().all(char::is_whitespace), "{input:?}: {gap:?}");
+
+            output.push_str(gap);
+            output.push_str(&input[span.clone()]);
+            end = span.end;
+        }
+
+        output.push_str(&input[end..]);
+        output
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let tests = vec![
+            (Token::Let, 0..3),
+            (Token::Ident(String::from("변수")), 4..10),
+            (Token::Assign, 11..12),
+            (Token::Str(String::from("a b")), 13..18),
+            (Token::Semicolon, 18..19),
+            (Token::GreaterThanEqual, 22..24),
+            (Token::Int(10), 25..27),
+            (Token::Eof, 28..28),
+        ];
+
+        assert_eq!(tests, spans_of("let 변수 = \"a b\";\n\t >= 10 "));
+    }
+
+    #[test]
+    fn test_token_spans_reconstruct_input() {
+        let tests = vec![
+            "",
+            "   ",
+            "let five = 5;\nlet add = fn(x, y) {\n  x + y;\n};\n",
+            "  if (5 <= 10) { return true; } else { return false; }  ",
+            "\"unterminated",
+            "@ 1.5.5 π != \"x\"\r\n",
+        ];
+
+        for input in tests {
+            assert_eq!(input, reconstruct(input));
+        }
+    }
+
+    #[test]
+    fn test_eof_span_repeats_at_end() {
+        let mut lexer = Lexer::new("x");
+
+        assert_eq!(
+            (Token::Ident(String::from("x")), 0..1),
+            lexer.next_token_with_span()
+        );
+        assert_eq!((Token::Eof, 1..1), lexer.next_token_with_span());
+        assert_eq!((Token::Eof, 1..1), lexer.next_token_with_span());
+    }
+
+    #[test]
+    fn test_number_bases_and_separators() {
+        let tests = vec![
+            ("0xFF", Token::Int(255)),
+            ("0Xff", Token::Int(255)),
+            ("0b1010", Token::Int(10)),
+            ("0B1", Token::Int(1)),
+            ("1_000_000", Token::Int(1_000_000)),
+            ("0xdead_beef", Token::Int(0xdead_beef)),
+            ("0b1111_0000", Token::Int(0b1111_0000)),
+            ("0xFFFFFFFFFFFFFFFF", Token::Int(u64::MAX)),
+            (
+                "99999999999999999999",
+                Token::BigInt(String::from("99999999999999999999")),
+            ),
+            (
+                "0x1_0000_0000_0000_0000",
+                Token::BigInt(String::from("0x1_0000_0000_0000_0000")),
+            ),
+            ("0", Token::Int(0)),
+            ("007", Token::Int(7)),
+            ("1_000.000_1", Token::Float(1000.0001)),
+            ("1.", Token::Float(1.0)),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                vec![expect, Token::Eof],
+                Lexer::new(input).collect::<Vec<Token>>(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_malformed_numbers() {
+        let tests = vec![
+            ("1.2.3", vec![Token::Illegal('1'), Token::Eof]),
+            ("0x", vec![Token::Illegal('0'), Token::Eof]),
+            ("0b", vec![Token::Illegal('0'), Token::Eof]),
+            ("0b2", vec![Token::Illegal('0'), Token::Eof]),
+            ("0xZZ", vec![Token::Illegal('0'), Token::Eof]),
+            ("0x_1", vec![Token::Illegal('0'), Token::Eof]),
+            ("1_", vec![Token::Illegal('1'), Token::Eof]),
+            ("1_.5", vec![Token::Illegal('1'), Token::Eof]),
+            ("1._5", vec![Token::Illegal('1'), Token::Eof]),
+            ("0x1.5", vec![Token::Illegal('0'), Token::Eof]),
+            (
+                "123abc + 1",
+                vec![Token::Illegal('1'), Token::Plus, Token::Int(1), Token::Eof],
+            ),
+            ("1e5", vec![Token::Illegal('1'), Token::Eof]),
+            (" \t\n", vec![Token::Eof]),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, Lexer::new(input).collect::<Vec<Token>>());
+        }
+    }
+}
+
+#[cfg(test)]
+mod fuzz {
+    use proptest::prelude::*;
+
+    use crate::ast::ast::Literal;
+    use crate::lexer::lexer::Lexer;
+    use crate::token::token::Token;
+
+    const KEYWORDS: [&str; 11] = [
+        "fn", "let", "const", "true", "false", "if", "else", "return", "while", "break", "continue",
+    ];
+
+    fn source_of(tok: &Token) -> String {
+        match tok {
+            Token::Ident(name) => name.clone(),
+            Token::Int(value) => value.to_string(),
+            Token::Bool(value) => value.to_string(),
+            Token::Assign => String::from("="),
+            Token::Plus => String::from("+"),
+            Token::Minus => String::from("-"),
+            Token::Bang => String::from("!"),
+            Token::Asterisk => String::from("*"),
+            Token::Slash => String::from("/"),
+            Token::Equal => String::from("=="),
+            Token::NotEqual => String::from("!="),
+            Token::LessThan => String::from("<"),
+            Token::LessThanEqual => String::from("<="),
+            Token::GreaterThan => String::from(">"),
+            Token::GreaterThanEqual => String::from(">="),
+            Token::Str(value) => Literal::Str(value.clone()).to_string(),
+            Token::Comma => String::from(","),
+            Token::Colon => String::from(":"),
+            Token::Semicolon => String::from(";"),
+            Token::Lparen => String::from("("),
+            Token::Rparen => String::from(")"),
+            Token::Lbrace => String::from("{"),
+            Token::Rbrace => String::from("}"),
+            Token::Lbracket => String::from("["),
+            Token::Rbracket => String::from("]"),
+            Token::Function => String::from("fn"),
+            Token::Let => String::from("let"),
+            Token::Const => String::from("const"),
+            Token::If => String::from("if"),
+            Token::Else => String::from("else"),
+            Token::Return => String::from("return"),
+            Token::While => String::from("while"),
+            Token::Break => String::from("break"),
+            Token::Continue => String::from("continue"),
+            _ => unreachable!("{tok:?} is never generated"),
+        }
+    }
+
+    fn valid_token() -> impl Strategy<Value = Token> {
+        prop_oneof![
+            "[a-zA-Z_]{1,8}"
+                .prop_filter("keywords lex as keywords", |name| {
+                    !KEYWORDS.contains(&name.as_str())
+                })
+                .prop_map(Token::Ident),
+            any::<u64>().prop_map(Token::Int),
+            any::<bool>().prop_map(Token::Bool),
+            "(?s).{0,8}".prop_map(Token::Str),
+            prop::sample::select(vec![
+                Token::Assign,
+                Token::Plus,
+                Token::Minus,
+                Token::Bang,
+                Token::Asterisk,
+                Token::Slash,
+                Token::Equal,
+                Token::NotEqual,
+                Token::LessThan,
+                Token::LessThanEqual,
+                Token::GreaterThan,
+                Token::GreaterThanEqual,
+                Token::Comma,
+                Token::Colon,
+                Token::Semicolon,
+                Token::Lparen,
+                Token::Rparen,
+                Token::Lbrace,
+                Token::Rbrace,
+                Token::Lbracket,
+                Token::Rbracket,
+                Token::Function,
+                Token::Let,
+                Token::Const,
+                Token::If,
+                Token::Else,
+                Token::Return,
+                Token::While,
+                Token::Break,
+                Token::Continue,
+            ]),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_input_never_panics(input in any::<String>()) {
+            let tokens = Lexer::new(&input).collect::<Vec<Token>>();
+
+            prop_assert_eq!(Some(&Token::Eof), tokens.last());
+            prop_assert_eq!(1, tokens.iter().filter(|tok| **tok == Token::Eof).count());
+        }
+
+        #[test]
+        fn numeric_looking_input_never_panics(input in "[0-9a-fA-F._xXbB \\t\\n]{0,32}") {
+            let tokens = Lexer::new(&input).collect::<Vec<Token>>();
+
+            prop_assert_eq!(Some(&Token::Eof), tokens.last());
+        }
+
+        #[test]
+        fn spans_cover_arbitrary_input(input in any::<String>()) {
+            let mut lexer = Lexer::new(&input);
+            let mut end = 0;
+
+            loop {
+                let (tok, span) = lexer.next_token_with_span();
+                prop_assert!(end <= span.start && span.start <= span.end);
+                prop_assert!(input[end..span.start].chars().all(char::is_whitespace));
+                end = span.end;
+
+                if tok == Token::Eof {
+                    break;
+                }
+            }
+
+            prop_assert_eq!(input.len(), end);
+        }
+
+        #[test]
+        fn valid_tokens_round_trip(expect in prop::collection::vec(valid_token(), 0..64)) {
+            let input = expect.iter().map(source_of).collect::<Vec<_>>().join(" ");
+            let mut tokens = Lexer::new(&input).collect::<Vec<Token>>();
+
+            prop_assert_eq!(Some(Token::Eof), tokens.pop());
+            prop_assert_eq!(expect, tokens);
+        }
+    }
 }
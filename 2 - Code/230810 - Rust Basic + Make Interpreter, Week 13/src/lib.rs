@@ -1,5 +1,8 @@
 pub mod ast;
 pub mod evaluator;
+pub mod interpreter;
 pub mod lexer;
+pub mod lints;
 pub mod parser;
+pub mod repl;
 pub mod token;
@@ -0,0 +1,582 @@
+use std::fmt;
+
+use crate::ast::ast::{Expression, Identifier, Literal, Program, SpannedProgram, Statement};
+use crate::token::token::Span;
+
+/// Something legal that is probably a mistake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// A `let` at the top level of a function body whose name is never read
+    /// afterwards. Names starting with `_` are never reported.
+    UnusedBinding,
+    /// A statement after a `return`, `break` or `continue` in the same
+    /// block, which can never run.
+    UnreachableCode,
+    /// An `if` whose condition is a literal, so it always takes one branch.
+    ConstantCondition,
+    /// A `let` that rebinds one of the enclosing function's parameters.
+    ShadowedParameter,
+}
+
+impl LintKind {
+    /// A stable identifier for tools that shouldn't match on the message.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            LintKind::UnusedBinding => "W001",
+            LintKind::UnreachableCode => "W002",
+            LintKind::ConstantCondition => "W003",
+            LintKind::ShadowedParameter => "W004",
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LintKind::UnusedBinding => write!(f, "Unused Binding"),
+            LintKind::UnreachableCode => write!(f, "Unreachable Code"),
+            LintKind::ConstantCondition => write!(f, "Constant Condition"),
+            LintKind::ShadowedParameter => write!(f, "Shadowed Parameter"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    kind: LintKind,
+    msg: String,
+    span: Option<Span>,
+}
+
+impl LintWarning {
+    pub fn kind(&self) -> LintKind {
+        self.kind
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// The top-level statement the warning is in, if the program was parsed
+    /// with spans.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.msg)
+    }
+}
+
+/// Every warning in `program`, in source order. Warnings have no span.
+pub fn lint_program(program: &Program) -> Vec<LintWarning> {
+    let mut linter = Linter::default();
+    linter.lint_top_level(program.iter());
+
+    linter.warnings
+}
+
+/// Like `lint_program`, but each warning has the span of the top-level
+/// statement it's in.
+pub fn lint_spanned_program(program: &SpannedProgram) -> Vec<LintWarning> {
+    let mut linter = Linter::default();
+    linter.lint_top_level(program.statements.iter().map(|statement| &statement.node));
+
+    for (warning, &i) in linter.warnings.iter_mut().zip(&linter.statements) {
+        warning.span = Some(program.statements[i].span);
+    }
+
+    linter.warnings
+}
+
+/// Walks the program the way the evaluator does, a block at a time, so the
+/// lints see the same scopes that evaluation does.
+#[derive(Default)]
+struct Linter {
+    warnings: Vec<LintWarning>,
+    // The index of the top-level statement each warning is in.
+    statements: Vec<usize>,
+    current: usize,
+    // The parameters of the innermost function being linted.
+    parameters: Vec<Identifier>,
+}
+
+impl Linter {
+    fn warn(&mut self, kind: LintKind, msg: String) {
+        self.warnings.push(LintWarning {
+            kind,
+            msg,
+            span: None,
+        });
+        self.statements.push(self.current);
+    }
+
+    fn lint_top_level<'a>(&mut self, statements: impl Iterator<Item = &'a Statement>) {
+        let mut exit = Exit::default();
+
+        for (i, statement) in statements.enumerate() {
+            self.current = i;
+            self.lint_block_statement(&mut exit, statement);
+        }
+    }
+
+    fn lint_block(&mut self, statements: &[Statement]) {
+        let mut exit = Exit::default();
+
+        for statement in statements {
+            self.lint_block_statement(&mut exit, statement);
+        }
+    }
+
+    // Lints one statement of a block, after reporting it if an earlier one
+    // in the block always leaves it.
+    fn lint_block_statement(&mut self, exit: &mut Exit, statement: &Statement) {
+        if let Exit::After(keyword) = *exit {
+            self.warn(
+                LintKind::UnreachableCode,
+                format!("`{statement}` can never run, it follows a `{keyword}`"),
+            );
+            *exit = Exit::Reported;
+        }
+
+        if *exit == Exit::None {
+            *exit = match statement {
+                Statement::Return(_) => Exit::After("return"),
+                Statement::Break => Exit::After("break"),
+                Statement::Continue => Exit::After("continue"),
+                _ => Exit::None,
+            };
+        }
+
+        self.lint_statement(statement);
+    }
+
+    fn lint_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(name, value) | Statement::Const(name, value) => {
+                self.check_shadowing(name);
+                self.lint_expression(value);
+            }
+            Statement::DestructureArray(names, value) => {
+                for name in names {
+                    self.check_shadowing(name);
+                }
+
+                self.lint_expression(value);
+            }
+            Statement::Assign(_, value)
+            | Statement::Return(value)
+            | Statement::Expression(value) => self.lint_expression(value),
+            Statement::Break | Statement::Continue => {}
+        }
+    }
+
+    fn lint_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(_) => {}
+            Expression::Literal(literal) => self.lint_literal(literal),
+            Expression::Prefix(_, right) => self.lint_expression(right),
+            Expression::Infix(_, left, right) => {
+                self.lint_expression(left);
+                self.lint_expression(right);
+            }
+            Expression::If {
+                condition,
+                consequence,
+                alternative,
+            } => {
+                self.check_condition(condition);
+                self.lint_expression(condition);
+                self.lint_block(consequence);
+
+                if let Some(alternative) = alternative {
+                    self.lint_block(alternative);
+                }
+            }
+            Expression::While { condition, body } => {
+                self.lint_expression(condition);
+                self.lint_block(body);
+            }
+            Expression::Function { parameters, body } => self.lint_function(parameters, body),
+            Expression::Call {
+                function,
+                arguments,
+            } => {
+                self.lint_expression(function);
+
+                for argument in arguments {
+                    self.lint_expression(argument);
+                }
+            }
+            Expression::Index(left, index) => {
+                self.lint_expression(left);
+                self.lint_expression(index);
+            }
+        }
+    }
+
+    fn lint_literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::Array(elements) => {
+                for element in elements {
+                    self.lint_expression(element);
+                }
+            }
+            Literal::Hash(pairs) => {
+                for (key, value) in pairs {
+                    self.lint_expression(key);
+                    self.lint_expression(value);
+                }
+            }
+            Literal::Int(_) | Literal::Float(_) | Literal::Bool(_) | Literal::Str(_) => {}
+        }
+    }
+
+    fn lint_function(&mut self, parameters: &[Identifier], body: &[Statement]) {
+        let outer = std::mem::replace(&mut self.parameters, parameters.to_vec());
+        let mut exit = Exit::default();
+
+        for (i, statement) in body.iter().enumerate() {
+            let names = match statement {
+                Statement::Let(name, _) => std::slice::from_ref(name),
+                Statement::DestructureArray(names, _) => names.as_slice(),
+                _ => &[],
+            };
+
+            for Identifier(name) in names {
+                if !name.starts_with('_') && !is_read_after(name, &body[i + 1..]) {
+                    self.warn(
+                        LintKind::UnusedBinding,
+                        format!("`{name}` is bound but never used"),
+                    );
+                }
+            }
+
+            self.lint_block_statement(&mut exit, statement);
+        }
+
+        self.parameters = outer;
+    }
+
+    fn check_shadowing(&mut self, name: &Identifier) {
+        if self.parameters.contains(name) {
+            self.warn(
+                LintKind::ShadowedParameter,
+                format!("`{name}` shadows a parameter of the same name"),
+            );
+        }
+    }
+
+    fn check_condition(&mut self, condition: &Expression) {
+        let always = match condition {
+            Expression::Literal(Literal::Bool(value)) => *value,
+            Expression::Literal(Literal::Int(_) | Literal::Float(_) | Literal::Str(_)) => true,
+            _ => return,
+        };
+
+        self.warn(
+            LintKind::ConstantCondition,
+            format!("`if` condition `{condition}` is constant, so it is always {always}"),
+        );
+    }
+}
+
+/// Whether a block has left through one of its statements yet.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Exit {
+    #[default]
+    None,
+    After(&'static str),
+    // The unreachable statements have been reported already.
+    Reported,
+}
+
+// Whether `name` is read in `statements` before a `let` rebinds it.
+fn is_read_after(name: &str, statements: &[Statement]) -> bool {
+    for statement in statements {
+        match statement {
+            Statement::Let(Identifier(rebound), value) if rebound == name => {
+                return reads(name, value);
+            }
+            _ if statement_reads(name, statement) => return true,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+fn statement_reads(name: &str, statement: &Statement) -> bool {
+    match statement {
+        Statement::Let(_, value)
+        | Statement::Const(_, value)
+        | Statement::DestructureArray(_, value)
+        | Statement::Assign(_, value)
+        | Statement::Return(value)
+        | Statement::Expression(value) => reads(name, value),
+        Statement::Break | Statement::Continue => false,
+    }
+}
+
+fn block_reads(name: &str, statements: &[Statement]) -> bool {
+    statements
+        .iter()
+        .any(|statement| statement_reads(name, statement))
+}
+
+fn reads(name: &str, expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(Identifier(read)) => read == name,
+        Expression::Literal(Literal::Array(elements)) => {
+            elements.iter().any(|element| reads(name, element))
+        }
+        Expression::Literal(Literal::Hash(pairs)) => pairs
+            .iter()
+            .any(|(key, value)| reads(name, key) || reads(name, value)),
+        Expression::Literal(_) => false,
+        Expression::Prefix(_, right) => reads(name, right),
+        Expression::Infix(_, left, right) | Expression::Index(left, right) => {
+            reads(name, left) || reads(name, right)
+        }
+        Expression::If {
+            condition,
+            consequence,
+            alternative,
+        } => {
+            reads(name, condition)
+                || block_reads(name, consequence)
+                || alternative
+                    .as_ref()
+                    .is_some_and(|alternative| block_reads(name, alternative))
+        }
+        Expression::While { condition, body } => reads(name, condition) || block_reads(name, body),
+        Expression::Function { parameters, body } => {
+            !parameters
+                .iter()
+                .any(|Identifier(parameter)| parameter == name)
+                && block_reads(name, body)
+        }
+        Expression::Call {
+            function,
+            arguments,
+        } => reads(name, function) || arguments.iter().any(|argument| reads(name, argument)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::lexer::Lexer;
+    use crate::lints::lints::{lint_program, lint_spanned_program, LintKind};
+    use crate::parser::parser::Parser;
+    use crate::token::token::Span;
+
+    fn lint(input: &str) -> Vec<(LintKind, String)> {
+        let program = Parser::new(Lexer::new(input)).parse().unwrap();
+
+        lint_program(&program)
+            .into_iter()
+            .map(|warning| (warning.kind(), warning.to_string()))
+            .collect()
+    }
+
+    fn kinds(input: &str) -> Vec<LintKind> {
+        lint(input).into_iter().map(|(kind, _)| kind).collect()
+    }
+
+    #[test]
+    fn test_clean_programs() {
+        let tests = vec![
+            "",
+            "let x = 1; x + 1;",
+            "let add = fn(a, b) { let sum = a + b; sum }; add(1, 2);",
+            "fn(x) { let [a, b] = x; a + b };",
+            "fn() { let _ignored = 1; 2 };",
+            "fn(n) { let i = 0; while (i < n) { i = i + 1; }; i };",
+            "fn() { let f = fn() { 1 }; let g = fn() { f() }; g };",
+            "fn(x) { if (x) { return 1; }; return 2; };",
+            "while (true) { break; };",
+            "if (x > 1) { 1 } else { 2 };",
+            "fn(x) { fn(y) { let x = y; x } };",
+        ];
+
+        for input in tests {
+            assert_eq!(Vec::<(LintKind, String)>::new(), lint(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_unused_bindings() {
+        let tests = vec![
+            (
+                "fn() { let x = 1; 2 };",
+                vec!["Unused Binding: `x` is bound but never used"],
+            ),
+            (
+                "fn() { let [a, b] = [1, 2]; a };",
+                vec!["Unused Binding: `b` is bound but never used"],
+            ),
+            // Reading the name in a function that takes it as a parameter
+            // doesn't read the binding.
+            (
+                "fn() { let x = 1; fn(x) { x } };",
+                vec!["Unused Binding: `x` is bound but never used"],
+            ),
+            // Only the first binding is lost; the second one is read.
+            (
+                "fn() { let x = 1; let x = 2; x };",
+                vec!["Unused Binding: `x` is bound but never used"],
+            ),
+            (
+                "fn() { let x = 1; x = 2; };",
+                vec!["Unused Binding: `x` is bound but never used"],
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let (kinds, messages): (Vec<_>, Vec<_>) = lint(input).into_iter().unzip();
+
+            assert!(kinds.iter().all(|kind| *kind == LintKind::UnusedBinding));
+            assert_eq!(expect, messages, "{input}");
+        }
+
+        // A read of the old value while rebinding it counts.
+        assert!(lint("fn() { let x = 1; let x = x + 1; x };").is_empty());
+        // Top-level bindings may be used by later inputs, so aren't reported.
+        assert!(lint("let x = 1;").is_empty());
+        // Nor are bindings in a nested block.
+        assert!(lint("fn(y) { if (y) { let x = 1; }; y };").is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        let tests = vec![
+            (
+                "fn() { return 1; 2; 3 };",
+                vec!["Unreachable Code: `2;` can never run, it follows a `return`"],
+            ),
+            (
+                "while (true) { break; puts(1); };",
+                vec!["Unreachable Code: `puts(1);` can never run, it follows a `break`"],
+            ),
+            (
+                "while (true) { if (x) { continue; let y = 1; }; };",
+                vec!["Unreachable Code: `let y = 1;` can never run, it follows a `continue`"],
+            ),
+            (
+                "return 1; let x = 2;",
+                vec!["Unreachable Code: `let x = 2;` can never run, it follows a `return`"],
+            ),
+            (
+                "fn() { return 1; return 2; 3 };",
+                vec!["Unreachable Code: `return 2;` can never run, it follows a `return`"],
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let messages: Vec<String> = lint(input).into_iter().map(|(_, msg)| msg).collect();
+
+            assert_eq!(expect, messages, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_constant_conditions() {
+        let tests = vec![
+            (
+                "if (true) { 1 };",
+                "Constant Condition: `if` condition `true` is constant, so it is always true",
+            ),
+            (
+                "if (false) { 1 } else { 2 };",
+                "Constant Condition: `if` condition `false` is constant, so it is always false",
+            ),
+            (
+                "if (1) { 1 };",
+                "Constant Condition: `if` condition `1` is constant, so it is always true",
+            ),
+            (
+                "if (\"\") { 1 };",
+                "Constant Condition: `if` condition `\"\"` is constant, so it is always true",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(
+                vec![(LintKind::ConstantCondition, String::from(expect))],
+                lint(input),
+                "{input}"
+            );
+        }
+
+        assert_eq!(
+            vec![LintKind::ConstantCondition],
+            kinds("fn(x) { if (x) { if (true) { 1 } } };")
+        );
+        assert!(lint("if (!true) { 1 };").is_empty());
+    }
+
+    #[test]
+    fn test_shadowed_parameters() {
+        assert_eq!(
+            vec![(
+                LintKind::ShadowedParameter,
+                String::from("Shadowed Parameter: `x` shadows a parameter of the same name")
+            )],
+            lint("fn(x) { let x = x + 1; x };")
+        );
+        assert_eq!(
+            vec![LintKind::ShadowedParameter],
+            kinds("fn(x, y) { if (y) { const x = 1; x } };")
+        );
+        assert_eq!(
+            vec![LintKind::ShadowedParameter, LintKind::ShadowedParameter],
+            kinds("fn(a, b) { let [b, a] = [a, b]; a + b };")
+        );
+        // An inner function's parameters are its own.
+        assert!(lint("fn(x) { fn(y) { let x = y; x }(x) };").is_empty());
+    }
+
+    #[test]
+    fn test_several_lints_in_source_order() {
+        assert_eq!(
+            vec![
+                LintKind::UnusedBinding,
+                LintKind::ShadowedParameter,
+                LintKind::ConstantCondition,
+                LintKind::UnreachableCode,
+            ],
+            kinds("fn(x) { let x = 1; if (true) { return 2; 3 }; 4 };")
+        );
+    }
+
+    #[test]
+    fn test_spanned_warnings_point_at_their_statement() {
+        let input = "let ok = 1;\nlet f = fn() { let unused = 2; 3 };\nif (true) { 4 };";
+        let program = Parser::new(Lexer::new(input)).parse_spanned().unwrap();
+        let warnings = lint_spanned_program(&program);
+
+        let spans: Vec<Option<Span>> = warnings.iter().map(|warning| warning.span()).collect();
+        assert_eq!(
+            vec![
+                Some(Span { start: 12, end: 47 }),
+                Some(Span { start: 48, end: 64 })
+            ],
+            spans
+        );
+        assert_eq!(
+            vec!["W001", "W003"],
+            warnings
+                .iter()
+                .map(|warning| warning.code())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, warnings[1].span().unwrap().start_position(input).line);
+
+        let program = program.into_program();
+        assert!(lint_program(&program)
+            .iter()
+            .all(|warning| warning.span().is_none()));
+    }
+}
@@ -1,20 +1,54 @@
 use std::fmt;
 
 use crate::ast::ast::{
-    Expression, Identifier, Infix, Literal, Precedence, Prefix, Program, Statement,
+    Expression, Identifier, Infix, Literal, Precedence, Prefix, Program, Spanned, SpannedProgram,
+    Statement,
 };
 use crate::lexer::lexer::Lexer;
-use crate::token::token::Token;
+use crate::token::token::{Position, Span, Token};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseErrorKind {
     UnexpectedToken,
+    ExpectedIdentifier,
+    ExpectedToken { expected: Token, found: Token },
+    UnclosedDelimiter { open: Token },
+    InvalidIntegerLiteral,
+    UnexpectedEof,
+    NestingTooDeep,
+    IllegalCharacter,
+    InvalidEscape,
+}
+
+impl ParseErrorKind {
+    /// A stable identifier for tools that shouldn't match on the message.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            ParseErrorKind::UnexpectedToken => "P001",
+            ParseErrorKind::ExpectedIdentifier => "P002",
+            ParseErrorKind::ExpectedToken { .. } => "P003",
+            ParseErrorKind::UnclosedDelimiter { .. } => "P004",
+            ParseErrorKind::InvalidIntegerLiteral => "P005",
+            ParseErrorKind::UnexpectedEof => "P006",
+            ParseErrorKind::NestingTooDeep => "P007",
+            ParseErrorKind::IllegalCharacter => "P008",
+            ParseErrorKind::InvalidEscape => "P009",
+        }
+    }
 }
 
 impl fmt::Display for ParseErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ParseErrorKind::UnexpectedToken => write!(f, "Unexpected Token"),
+            ParseErrorKind::ExpectedIdentifier => write!(f, "Expected Identifier"),
+            ParseErrorKind::ExpectedToken { .. } => write!(f, "Expected Token"),
+            ParseErrorKind::UnclosedDelimiter { .. } => write!(f, "Unclosed Delimiter"),
+            ParseErrorKind::InvalidIntegerLiteral => write!(f, "Invalid Integer Literal"),
+            ParseErrorKind::UnexpectedEof => write!(f, "Unexpected End Of Input"),
+            ParseErrorKind::NestingTooDeep => write!(f, "Nesting Too Deep"),
+            ParseErrorKind::IllegalCharacter => write!(f, "Illegal Character"),
+            ParseErrorKind::InvalidEscape => write!(f, "Invalid Escape"),
         }
     }
 }
@@ -23,11 +57,28 @@ impl fmt::Display for ParseErrorKind {
 pub struct ParseError {
     kind: ParseErrorKind,
     msg: String,
+    position: Position,
 }
 
 impl ParseError {
-    fn new(kind: ParseErrorKind, msg: String) -> Self {
-        ParseError { kind, msg }
+    fn new(kind: ParseErrorKind, msg: String, position: Position) -> Self {
+        ParseError {
+            kind,
+            msg,
+            position,
+        }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
     }
 }
 
@@ -37,20 +88,75 @@ impl fmt::Display for ParseError {
     }
 }
 
+/// What `Parser::parse` gives back when the source has errors: every error
+/// found, along with the statements that did parse.
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    pub partial: Program,
+    pub errors: Vec<ParseError>,
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, err) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            let position = err.position();
+            write!(f, "{}:{}: {err}", position.line, position.column)?;
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
     cur_token: Token,
     peek_token: Token,
+    cur_position: Position,
+    peek_position: Position,
+    cur_span: Span,
+    peek_span: Span,
+    /// Every infix expression parsed so far, kept only while
+    /// `parse_program_spanned` runs since each one is a clone.
+    infix_spans: Vec<Span>,
+    record_infix: bool,
     errors: Vec<ParseError>,
+    depth: usize,
+    max_depth: usize,
+    /// Set once `max_depth` is exceeded, so the statement being parsed is
+    /// abandoned without recursing any further or reporting follow-on errors.
+    aborting: bool,
+    /// Open minus closed delimiters up to and including `cur_token`.
+    delimiter_depth: isize,
 }
 
 impl<'a> Parser<'a> {
+    pub const DEFAULT_MAX_DEPTH: usize = 256;
+
     pub fn new(lexer: Lexer<'a>) -> Self {
+        Self::with_max_depth(lexer, Self::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Like `new`, but expressions may nest at most `max_depth` levels deep.
+    pub fn with_max_depth(lexer: Lexer<'a>, max_depth: usize) -> Self {
         let mut parser = Parser {
             lexer,
             cur_token: Token::Eof,
             peek_token: Token::Eof,
+            cur_position: Position::default(),
+            peek_position: Position::default(),
+            cur_span: Span::default(),
+            peek_span: Span::default(),
+            infix_spans: Vec::new(),
+            record_infix: false,
             errors: Vec::new(),
+            depth: 0,
+            max_depth,
+            aborting: false,
+            delimiter_depth: 0,
         };
 
         parser.next_token();
@@ -67,17 +173,57 @@ impl<'a> Parser<'a> {
             Token::Plus | Token::Minus => Precedence::Sum,
             Token::Asterisk | Token::Slash => Precedence::Product,
             Token::Lparen => Precedence::Call,
+            Token::Lbracket => Precedence::Index,
             _ => Precedence::Lowest,
         }
     }
 
-    pub fn get_errors(&mut self) -> Vec<ParseError> {
+    pub fn get_errors(&self) -> Vec<ParseError> {
         self.errors.clone()
     }
 
     fn next_token(&mut self) {
         self.cur_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        self.cur_position = self.peek_position;
+        self.peek_token = self.next_legal_token();
+        self.peek_position = self.lexer.token_position();
+        self.cur_span = self.peek_span;
+        self.peek_span = self.lexer.token_span();
+        self.delimiter_depth += Self::delimiter_change(&self.cur_token);
+    }
+
+    /// Reports and drops illegal characters as they come out of the lexer,
+    /// so the statement around them carries on as if they weren't there.
+    fn next_legal_token(&mut self) -> Token {
+        loop {
+            match self.lexer.next_token() {
+                Token::Illegal(ch) => {
+                    // The lexer also gives up on whole literals, reporting
+                    // them by the character they start with.
+                    let msg = match ch {
+                        '0'..='9' => String::from("malformed number literal"),
+                        '"' => String::from("unterminated string literal"),
+                        _ => format!("illegal character {:?}", ch),
+                    };
+                    let position = self.lexer.token_position();
+                    self.error(ParseErrorKind::IllegalCharacter, msg, position);
+                }
+                Token::InvalidEscape(escape) => {
+                    let msg = format!("invalid escape `{escape}` in string literal");
+                    let position = self.lexer.token_position();
+                    self.error(ParseErrorKind::InvalidEscape, msg, position);
+                }
+                token => return token,
+            }
+        }
+    }
+
+    fn delimiter_change(token: &Token) -> isize {
+        match token {
+            Token::Lparen | Token::Lbrace | Token::Lbracket => 1,
+            Token::Rparen | Token::Rbrace | Token::Rbracket => -1,
+            _ => 0,
+        }
     }
 
     fn cur_token_is(&mut self, token: Token) -> bool {
@@ -106,66 +252,212 @@ impl<'a> Parser<'a> {
         Self::token_to_precedence(&self.peek_token)
     }
 
+    fn error(&mut self, kind: ParseErrorKind, msg: String, position: Position) {
+        if self.aborting {
+            return;
+        }
+
+        self.errors.push(ParseError::new(kind, msg, position));
+    }
+
     fn error_next_token(&mut self, token: Token) {
-        self.errors.push(ParseError::new(
-            ParseErrorKind::UnexpectedToken,
+        let open = match token {
+            Token::Rparen => Some(Token::Lparen),
+            Token::Rbrace => Some(Token::Lbrace),
+            Token::Rbracket => Some(Token::Lbracket),
+            _ => None,
+        };
+
+        let kind = match (open, &self.peek_token) {
+            (Some(open), Token::Eof) => ParseErrorKind::UnclosedDelimiter { open },
+            (None, Token::Eof) => ParseErrorKind::UnexpectedEof,
+            _ => ParseErrorKind::ExpectedToken {
+                expected: token.clone(),
+                found: self.peek_token.clone(),
+            },
+        };
+
+        self.error(
+            kind,
             format!(
                 "expected next token to be {:?}, got {:?} instead",
                 token, self.peek_token
             ),
-        ));
+            self.peek_position,
+        );
+    }
+
+    fn error_expected_identifier(&mut self, token: Token, position: Position) {
+        self.error(
+            ParseErrorKind::ExpectedIdentifier,
+            format!("expected an identifier, got {:?} instead", token),
+            position,
+        );
+    }
+
+    /// Parses the whole input, failing if any error was recorded. Unlike
+    /// `parse_program`, the errors can't be forgotten.
+    pub fn parse(&mut self) -> Result<Program, ParseFailure> {
+        let program = self.parse_program();
+
+        if self.errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(ParseFailure {
+                partial: program,
+                errors: self.get_errors(),
+            })
+        }
+    }
+
+    /// Like `parse`, but keeps the spans `parse_program_spanned` records.
+    pub fn parse_spanned(&mut self) -> Result<SpannedProgram, ParseFailure> {
+        let program = self.parse_program_spanned();
+
+        if self.errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(ParseFailure {
+                partial: program.into_program(),
+                errors: self.get_errors(),
+            })
+        }
     }
 
     pub fn parse_program(&mut self) -> Program {
-        let mut program = Vec::new();
+        self.parse_statements().into_program()
+    }
+
+    /// Like `parse_program`, but also records where in the source each
+    /// top-level statement and each infix expression came from.
+    pub fn parse_program_spanned(&mut self) -> SpannedProgram {
+        self.record_infix = true;
+        let program = self.parse_statements();
+        self.record_infix = false;
+
+        program
+    }
+
+    fn parse_statements(&mut self) -> SpannedProgram {
+        let mut statements = Vec::new();
 
         while self.cur_token != Token::Eof {
-            match self.parse_statement() {
-                Some(statement) => program.push(statement),
-                None => {}
+            let start = self.delimiter_depth - Self::delimiter_change(&self.cur_token).max(0);
+            let start_span = self.cur_span;
+            let recorded = self.infix_spans.len();
+            let statement = self.parse_statement();
+
+            if self.aborting {
+                self.infix_spans.truncate(recorded);
+                self.skip_statement(start);
+                self.aborting = false;
+            } else if let Some(statement) = statement {
+                statements.push(Spanned {
+                    node: statement,
+                    span: start_span.to(self.cur_span),
+                });
             }
 
             self.next_token();
         }
 
-        program
+        SpannedProgram {
+            statements,
+            infix_spans: std::mem::take(&mut self.infix_spans),
+        }
+    }
+
+    /// Skips to the `;` that ends an abandoned statement, i.e. the first one
+    /// outside any delimiter the statement opened, or to the end of input.
+    fn skip_statement(&mut self, start: isize) {
+        while !(self.cur_token_is(Token::Eof)
+            || (self.cur_token_is(Token::Semicolon) && self.delimiter_depth == start))
+        {
+            self.next_token();
+        }
     }
 
     fn parse_statement(&mut self) -> Option<Statement> {
         match self.cur_token {
+            Token::Semicolon => None,
             Token::Let => self.parse_let_statement(),
+            Token::Const => self.parse_const_statement(),
             Token::Return => self.parse_return_statement(),
+            Token::Break => Some(self.parse_loop_control_statement(Statement::Break)),
+            Token::Continue => Some(self.parse_loop_control_statement(Statement::Continue)),
+            Token::Ident(_) if self.peek_token == Token::Assign => self.parse_assign_statement(),
             _ => self.parse_expression_statement(),
         }
     }
 
     fn parse_let_statement(&mut self) -> Option<Statement> {
+        if self.peek_token_is(Token::Lbracket) {
+            self.next_token();
+            return self.parse_destructure_array_statement();
+        }
+
+        let (identifier, expression) = self.parse_binding()?;
+
+        Some(Statement::Let(identifier, expression))
+    }
+
+    fn parse_const_statement(&mut self) -> Option<Statement> {
+        let (identifier, expression) = self.parse_binding()?;
+
+        Some(Statement::Const(identifier, expression))
+    }
+
+    fn parse_destructure_array_statement(&mut self) -> Option<Statement> {
+        let names = self.parse_identifier_list(Token::Rbracket)?;
+        let expression = self.parse_bound_expression()?;
+
+        Some(Statement::DestructureArray(names, expression))
+    }
+
+    /// Parses the `x = e;` that follows `let` or `const`.
+    fn parse_binding(&mut self) -> Option<(Identifier, Expression)> {
         match &self.peek_token {
             Token::Ident(_) => self.next_token(),
-            _ => return None,
+            _ => {
+                self.error_expected_identifier(self.peek_token.clone(), self.peek_position);
+                return None;
+            }
         };
 
-        let identifier = match self.parse_identifier() {
-            Some(identifier) => identifier,
-            None => return None,
-        };
+        let identifier = self.parse_identifier()?;
+        let expression = self.parse_bound_expression()?;
+
+        Some((identifier, expression))
+    }
 
+    /// Parses the `= e;` that follows the names being bound.
+    fn parse_bound_expression(&mut self) -> Option<Expression> {
         if !self.expect_peek(Token::Assign) {
             return None;
         }
 
         self.next_token();
 
-        let expression = match self.parse_expression(Precedence::Lowest) {
-            Some(expression) => expression,
-            None => return None,
-        };
+        let expression = self.parse_expression(Precedence::Lowest)?;
+        self.end_statement(&expression);
+
+        Some(expression)
+    }
+
+    fn parse_assign_statement(&mut self) -> Option<Statement> {
+        let identifier = self.parse_identifier()?;
+        let expression = self.parse_bound_expression()?;
+
+        Some(Statement::Assign(identifier, expression))
+    }
 
-        if self.cur_token_is(Token::Semicolon) {
+    /// Parses `break;` or `continue;`, where the `;` may be left out.
+    fn parse_loop_control_statement(&mut self, statement: Statement) -> Statement {
+        if self.peek_token_is(Token::Semicolon) {
             self.next_token();
         }
 
-        Some(Statement::Let(identifier, expression))
+        statement
     }
 
     fn parse_return_statement(&mut self) -> Option<Statement> {
@@ -175,10 +467,7 @@ impl<'a> Parser<'a> {
             Some(expression) => expression,
             None => return None,
         };
-
-        if self.cur_token_is(Token::Semicolon) {
-            self.next_token();
-        }
+        self.end_statement(&expression);
 
         Some(Statement::Return(expression))
     }
@@ -186,21 +475,56 @@ impl<'a> Parser<'a> {
     fn parse_expression_statement(&mut self) -> Option<Statement> {
         match self.parse_expression(Precedence::Lowest) {
             Some(expression) => {
-                if self.peek_token_is(Token::Semicolon) {
-                    self.next_token();
-                }
+                self.end_statement(&expression);
                 Some(Statement::Expression(expression))
             }
             None => None,
         }
     }
 
+    /// Consumes the `;` after a statement ending in `expression`. It may be
+    /// left out when the statement ends in a block, like an `if` or `fn`, or
+    /// when nothing but the end of the enclosing block or input follows.
+    /// Otherwise a single error is recorded and parsing carries on with the
+    /// next token as the start of a new statement.
+    fn end_statement(&mut self, expression: &Expression) {
+        if self.peek_token_is(Token::Semicolon) {
+            self.next_token();
+        } else if !Self::ends_with_block(expression)
+            && !self.peek_token_is(Token::Rbrace)
+            && !self.peek_token_is(Token::Eof)
+        {
+            self.error(
+                ParseErrorKind::ExpectedToken {
+                    expected: Token::Semicolon,
+                    found: self.peek_token.clone(),
+                },
+                format!(
+                    "expected Semicolon to end the statement, got {:?} instead",
+                    self.peek_token
+                ),
+                self.peek_position,
+            );
+        }
+    }
+
+    fn ends_with_block(expression: &Expression) -> bool {
+        match expression {
+            Expression::If { .. } | Expression::While { .. } | Expression::Function { .. } => true,
+            Expression::Prefix(_, right) | Expression::Infix(_, _, right) => {
+                Self::ends_with_block(right)
+            }
+            _ => false,
+        }
+    }
+
     fn parse_block_statement(&mut self) -> Vec<Statement> {
         self.next_token();
 
         let mut block = Vec::new();
 
-        while !self.cur_token_is(Token::Rbrace) && !self.cur_token_is(Token::Eof) {
+        while !self.aborting && !self.cur_token_is(Token::Rbrace) && !self.cur_token_is(Token::Eof)
+        {
             match self.parse_statement() {
                 Some(statement) => block.push(statement),
                 None => {}
@@ -209,20 +533,79 @@ impl<'a> Parser<'a> {
             self.next_token();
         }
 
+        if self.cur_token_is(Token::Eof) {
+            self.error(
+                ParseErrorKind::UnclosedDelimiter {
+                    open: Token::Lbrace,
+                },
+                String::from("expected Rbrace to close the block, got Eof instead"),
+                self.cur_position,
+            );
+        }
+
         block
     }
 
+    /// Every nested construct, whether a grouped, prefix, `if` or `fn`
+    /// expression, recurses through here, so this is where depth is limited.
     fn parse_expression(&mut self, precedence: Precedence) -> Option<Expression> {
+        if self.aborting {
+            return None;
+        }
+
+        if self.depth >= self.max_depth {
+            self.error(
+                ParseErrorKind::NestingTooDeep,
+                format!(
+                    "expression nesting exceeds the maximum depth of {}",
+                    self.max_depth
+                ),
+                self.cur_position,
+            );
+            self.aborting = true;
+            return None;
+        }
+
+        self.depth += 1;
+        let expression = self.parse_nested_expression(precedence);
+        self.depth -= 1;
+
+        expression
+    }
+
+    fn parse_nested_expression(&mut self, precedence: Precedence) -> Option<Expression> {
+        let start_span = self.cur_span;
+
         // Prefix
         let mut left = match self.cur_token {
             Token::Ident(_) => self.parse_identifier_expression(),
-            Token::Int(_) => self.parse_int_expression(),
+            Token::Int(_) | Token::BigInt(_) => self.parse_int_expression(),
+            Token::Float(_) => self.parse_float_expression(),
             Token::Bool(_) => self.parse_bool_expression(),
+            Token::Str(_) => self.parse_string_expression(),
             Token::Bang | Token::Minus => self.parse_prefix_expression(),
             Token::Lparen => self.parse_grouped_expression(),
+            Token::Lbracket => self.parse_array_expression(),
+            Token::Lbrace => self.parse_hash_expression(),
             Token::If => self.parse_if_expression(),
+            Token::While => self.parse_while_expression(),
             Token::Function => self.parse_function_expression(),
-            _ => None,
+            Token::Eof => {
+                self.error(
+                    ParseErrorKind::UnexpectedEof,
+                    String::from("expected an expression, got Eof instead"),
+                    self.cur_position,
+                );
+                None
+            }
+            _ => {
+                self.error(
+                    ParseErrorKind::UnexpectedToken,
+                    format!("no prefix parse function for {:?} found", self.cur_token),
+                    self.cur_position,
+                );
+                None
+            }
         };
 
         // Infix
@@ -237,11 +620,19 @@ impl<'a> Parser<'a> {
                 | Token::LessThan
                 | Token::GreaterThan => {
                     self.next_token();
-                    left = self.parse_infix_expression(left.unwrap());
+                    left = self.parse_infix_expression(left?);
+
+                    if left.is_some() && self.record_infix {
+                        self.infix_spans.push(start_span.to(self.cur_span));
+                    }
                 }
                 Token::Lparen => {
                     self.next_token();
-                    left = self.parse_call_expression(left.unwrap());
+                    left = self.parse_call_expression(left?);
+                }
+                Token::Lbracket => {
+                    self.next_token();
+                    left = self.parse_index_expression(left?);
                 }
                 _ => return left,
             }
@@ -265,8 +656,26 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_int_expression(&mut self) -> Option<Expression> {
-        match &self.cur_token {
-            Token::Int(int) => Some(Expression::Literal(Literal::Int(int.clone()))),
+        let literal = match self.cur_token {
+            Token::Int(int) => match i64::try_from(int) {
+                Ok(value) => return Some(Expression::Literal(Literal::Int(value))),
+                Err(_) => int.to_string(),
+            },
+            Token::BigInt(ref literal) => literal.clone(),
+            _ => return None,
+        };
+
+        self.error(
+            ParseErrorKind::InvalidIntegerLiteral,
+            format!("integer literal {literal} does not fit in a 64-bit signed integer"),
+            self.cur_position,
+        );
+        None
+    }
+
+    fn parse_float_expression(&mut self) -> Option<Expression> {
+        match self.cur_token {
+            Token::Float(value) => Some(Expression::Literal(Literal::Float(value))),
             _ => None,
         }
     }
@@ -319,6 +728,12 @@ impl<'a> Parser<'a> {
 
         self.next_token();
 
+        // `i64::MIN` has no positive counterpart, so `-9223372036854775808`
+        // is folded into a single literal instead of negating an out-of-range one.
+        if prefix == Prefix::Minus && self.cur_token == Token::Int(i64::MIN.unsigned_abs()) {
+            return Some(Expression::Literal(Literal::Int(i64::MIN)));
+        }
+
         match self.parse_expression(Precedence::Prefix) {
             Some(expr) => Some(Expression::Prefix(prefix, Box::new(expr))),
             None => None,
@@ -360,6 +775,59 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn parse_string_expression(&mut self) -> Option<Expression> {
+        match self.cur_token {
+            Token::Str(ref value) => Some(Expression::Literal(Literal::Str(value.clone()))),
+            _ => None,
+        }
+    }
+
+    fn parse_hash_expression(&mut self) -> Option<Expression> {
+        let mut pairs = Vec::new();
+
+        while !self.peek_token_is(Token::Rbrace) {
+            self.next_token();
+
+            let key = self.parse_expression(Precedence::Lowest)?;
+
+            if !self.expect_peek(Token::Colon) {
+                return None;
+            }
+
+            self.next_token();
+
+            let value = self.parse_expression(Precedence::Lowest)?;
+            pairs.push((key, value));
+
+            if !self.peek_token_is(Token::Rbrace) && !self.expect_peek(Token::Comma) {
+                return None;
+            }
+        }
+
+        if !self.expect_peek(Token::Rbrace) {
+            return None;
+        }
+
+        Some(Expression::Literal(Literal::Hash(pairs)))
+    }
+
+    fn parse_array_expression(&mut self) -> Option<Expression> {
+        self.parse_expression_list(Token::Rbracket)
+            .map(|list| Expression::Literal(Literal::Array(list)))
+    }
+
+    fn parse_index_expression(&mut self, left: Expression) -> Option<Expression> {
+        self.next_token();
+
+        let index = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(Token::Rbracket) {
+            return None;
+        }
+
+        Some(Expression::Index(Box::new(left), Box::new(index)))
+    }
+
     fn parse_grouped_expression(&mut self) -> Option<Expression> {
         self.next_token();
 
@@ -408,12 +876,31 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn parse_while_expression(&mut self) -> Option<Expression> {
+        if !self.expect_peek(Token::Lparen) {
+            return None;
+        }
+
+        self.next_token();
+
+        let condition = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(Token::Rparen) || !self.expect_peek(Token::Lbrace) {
+            return None;
+        }
+
+        Some(Expression::While {
+            condition: Box::new(condition),
+            body: self.parse_block_statement(),
+        })
+    }
+
     fn parse_function_expression(&mut self) -> Option<Expression> {
         if !self.expect_peek(Token::Lparen) {
             return None;
         }
 
-        let parameters = match self.parse_function_parameters() {
+        let parameters = match self.parse_identifier_list(Token::Rparen) {
             Some(parameters) => parameters,
             None => return None,
         };
@@ -428,44 +915,87 @@ impl<'a> Parser<'a> {
         })
     }
 
-    fn parse_function_parameters(&mut self) -> Option<Vec<Identifier>> {
-        let mut parameters = Vec::new();
+    fn parse_identifier_list(&mut self, end: Token) -> Option<Vec<Identifier>> {
+        let mut identifiers = Vec::new();
 
-        if self.peek_token_is(Token::Rparen) {
+        if self.peek_token_is(end.clone()) {
             self.next_token();
-            return Some(parameters);
+            return Some(identifiers);
         }
 
         self.next_token();
 
-        match self.parse_identifier() {
-            Some(ident) => parameters.push(ident),
-            None => return None,
-        };
+        loop {
+            match self.parse_identifier() {
+                Some(ident) => identifiers.push(ident),
+                None => {
+                    self.error_expected_identifier(self.cur_token.clone(), self.cur_position);
+                    return None;
+                }
+            };
+
+            if !self.peek_token_is(Token::Comma) {
+                break;
+            }
 
-        while self.peek_token_is(Token::Comma) {
             self.next_token();
             self.next_token();
-
-            match self.parse_identifier() {
-                Some(ident) => parameters.push(ident),
-                None => return None,
-            }
         }
 
-        if !self.expect_peek(Token::Rparen) {
+        if !self.expect_peek(end) {
             return None;
         }
 
-        Some(parameters)
+        Some(identifiers)
+    }
+}
+
+/// Parses `input` as a single expression, optionally followed by `;`.
+/// Anything else that parses, such as a `let` or a second statement, is
+/// reported as an error at the start of the input.
+pub fn parse_expression_str(input: &str) -> Result<Expression, Vec<ParseError>> {
+    let mut parser = Parser::new(Lexer::new(input));
+    let position = parser.cur_position;
+    let program = parser.parse_program();
+
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
     }
+
+    let (kind, msg) = match program.len() {
+        0 => (
+            ParseErrorKind::UnexpectedEof,
+            String::from("expected an expression, got Eof instead"),
+        ),
+        1 => match program.into_iter().next() {
+            Some(Statement::Expression(expression)) => return Ok(expression),
+            Some(statement) => (
+                ParseErrorKind::UnexpectedToken,
+                format!("expected an expression, got `{statement}` instead"),
+            ),
+            None => unreachable!("the program has one statement"),
+        },
+        len => (
+            ParseErrorKind::UnexpectedToken,
+            format!("expected a single expression, got {len} statements instead"),
+        ),
+    };
+
+    Err(vec![ParseError::new(kind, msg, position)])
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::ast::{Expression, Identifier, Infix, Literal, Prefix, Statement};
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
+    use crate::ast::ast::{
+        to_monkey_source, Expression, Identifier, Infix, Literal, Prefix, Program, Statement,
+    };
     use crate::lexer::lexer::Lexer;
-    use crate::parser::parser::Parser;
+    use crate::parser::parser::{parse_expression_str, ParseErrorKind, Parser};
+    use crate::token::token::{Position, Token};
 
     fn check_parse_errors(parser: &mut Parser) {
         let errors = parser.get_errors();
@@ -485,6 +1015,19 @@ mod tests {
         panic!("failed");
     }
 
+    /// Renders `program` as source and checks that it parses back unchanged.
+    fn check_round_trip(program: &Program) {
+        let source = to_monkey_source(program.as_slice());
+        let mut p = Parser::new(Lexer::new(&source));
+        let reparsed = p.parse_program();
+        check_parse_errors(&mut p);
+
+        assert_eq!(
+            *program, reparsed,
+            "{source} does not parse back into the same program"
+        );
+    }
+
     #[test]
     fn test_let_statement() {
         let input = r#"
@@ -497,6 +1040,7 @@ let foobar = 838383;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![
@@ -529,6 +1073,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![
@@ -540,6 +1085,21 @@ return 993322;
         );
     }
 
+    #[test]
+    fn test_keyword_override_synonym() {
+        let extra = HashMap::from([(String::from("yield"), Token::Return)]);
+        let l = Lexer::new("yield 5;").with_keyword_override(extra);
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+
+        assert_eq!(
+            vec![Statement::Return(Expression::Literal(Literal::Int(5)))],
+            program,
+        );
+    }
+
     #[test]
     fn test_identifier_expression() {
         let input = "foobar;";
@@ -549,6 +1109,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::Identifier(Identifier(
@@ -567,6 +1128,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::Literal(Literal::Int(5)))],
@@ -575,24 +1137,256 @@ return 993322;
     }
 
     #[test]
-    fn test_boolean_literal_expression() {
+    fn test_integer_literal_range() {
         let tests = vec![
             (
-                "true;",
-                Statement::Expression(Expression::Literal(Literal::Bool(true))),
+                "-9223372036854775808;",
+                Statement::Expression(Expression::Literal(Literal::Int(i64::MIN))),
             ),
             (
-                "false;",
-                Statement::Expression(Expression::Literal(Literal::Bool(false))),
+                "--9223372036854775808;",
+                Statement::Expression(Expression::Prefix(
+                    Prefix::Minus,
+                    Box::new(Expression::Literal(Literal::Int(i64::MIN))),
+                )),
             ),
-        ];
-
+            (
+                "-9223372036854775807;",
+                Statement::Expression(Expression::Prefix(
+                    Prefix::Minus,
+                    Box::new(Expression::Literal(Literal::Int(i64::MAX))),
+                )),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(vec![expect], program);
+        }
+    }
+
+    #[test]
+    fn test_integer_literal_bases() {
+        let input = "0xFF + 0b1; 1_000; -0x8000_0000_0000_0000;";
+
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+        check_round_trip(&program);
+
+        assert_eq!(
+            vec![
+                Statement::Expression(Expression::Infix(
+                    Infix::Plus,
+                    Box::new(Expression::Literal(Literal::Int(255))),
+                    Box::new(Expression::Literal(Literal::Int(1))),
+                )),
+                Statement::Expression(Expression::Literal(Literal::Int(1000))),
+                Statement::Expression(Expression::Literal(Literal::Int(i64::MIN))),
+            ],
+            program,
+        );
+        // Only the value is kept, so the source comes back in decimal.
+        assert_eq!(
+            "(255 + 1);\n1000;\n-9223372036854775808;",
+            to_monkey_source(program.as_slice()).trim_end()
+        );
+    }
+
+    #[test]
+    fn test_integer_literal_base_errors() {
+        let tests = vec![
+            (
+                "0x8000_0000_0000_0000;",
+                "Invalid Integer Literal: integer literal 9223372036854775808 does not fit in a 64-bit signed integer",
+            ),
+            ("0b102;", "Illegal Character: malformed number literal"),
+            ("123abc;", "Illegal Character: malformed number literal"),
+        ];
+
+        for (input, expect) in tests {
+            let errors = Parser::new(Lexer::new(input)).parse().unwrap_err().errors;
+
+            assert_eq!(1, errors.len(), "{input}");
+            assert_eq!(expect, errors[0].to_string(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_integer_literal_out_of_range() {
+        let l = Lexer::new("9223372036854775808;");
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        let errors = p.get_errors();
+
+        assert!(program.is_empty());
+        assert_eq!(1, errors.len());
+        assert_eq!(
+            "Invalid Integer Literal: integer literal 9223372036854775808 does not fit in a 64-bit signed integer",
+            errors[0].to_string(),
+        );
+    }
+
+    #[test]
+    fn test_integer_literal_above_u64_max() {
+        let tests = vec![
+            "let x = 99999999999999999999;",
+            "let x = -99999999999999999999;",
+            "let x = 0x1_0000_0000_0000_0000;",
+        ];
+
+        for input in tests {
+            let errors = Parser::new(Lexer::new(input)).parse().unwrap_err().errors;
+            let literal = input
+                .trim_start_matches("let x = ")
+                .trim_start_matches('-')
+                .trim_end_matches(';');
+
+            assert_eq!(1, errors.len(), "{input}");
+            assert_eq!(&ParseErrorKind::InvalidIntegerLiteral, errors[0].kind());
+            assert_eq!("P005", errors[0].code());
+            assert_eq!(
+                format!("Invalid Integer Literal: integer literal {literal} does not fit in a 64-bit signed integer"),
+                errors[0].to_string(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_position_counts_characters() {
+        let l = Lexer::new("let 한글 = 9223372036854775808;\nlet π = fn(x { x };");
+        let mut p = Parser::new(l);
+
+        p.parse_program();
+        let positions = p
+            .get_errors()
+            .iter()
+            .map(|error| error.position())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Position {
+                    line: 1,
+                    column: 10
+                },
+                Position {
+                    line: 2,
+                    column: 14
+                },
+            ],
+            positions[..2],
+        );
+    }
+
+    #[test]
+    fn test_parse_error_kinds() {
+        let tests = vec![
+            (")", ParseErrorKind::UnexpectedToken, "P001"),
+            ("let = 5;", ParseErrorKind::ExpectedIdentifier, "P002"),
+            ("let;", ParseErrorKind::ExpectedIdentifier, "P002"),
+            ("fn(x, 1) { x }", ParseErrorKind::ExpectedIdentifier, "P002"),
+            (
+                "let x 5;",
+                ParseErrorKind::ExpectedToken {
+                    expected: Token::Assign,
+                    found: Token::Int(5),
+                },
+                "P003",
+            ),
+            (
+                "(1 + 2",
+                ParseErrorKind::UnclosedDelimiter {
+                    open: Token::Lparen,
+                },
+                "P004",
+            ),
+            (
+                "[1, 2",
+                ParseErrorKind::UnclosedDelimiter {
+                    open: Token::Lbracket,
+                },
+                "P004",
+            ),
+            (
+                "fn(x) { x",
+                ParseErrorKind::UnclosedDelimiter {
+                    open: Token::Lbrace,
+                },
+                "P004",
+            ),
+            ("let x = 5 @ + 1;", ParseErrorKind::IllegalCharacter, "P008"),
+            ("let x = \"a\\qb\";", ParseErrorKind::InvalidEscape, "P009"),
+            (
+                "9223372036854775808;",
+                ParseErrorKind::InvalidIntegerLiteral,
+                "P005",
+            ),
+            ("1 +", ParseErrorKind::UnexpectedEof, "P006"),
+            (
+                "if (x",
+                ParseErrorKind::UnclosedDelimiter {
+                    open: Token::Lparen,
+                },
+                "P004",
+            ),
+        ];
+
+        for (input, kind, code) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            p.parse_program();
+            let errors = p.get_errors();
+
+            assert!(!errors.is_empty(), "{input} produced no errors");
+            assert_eq!(&kind, errors[0].kind(), "{input}");
+            assert_eq!(code, errors[0].code(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_empty_statements_are_not_errors() {
+        let l = Lexer::new(";; let x = 1;; x;");
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+        check_round_trip(&program);
+
+        assert_eq!(2, program.len());
+    }
+
+    #[test]
+    fn test_boolean_literal_expression() {
+        let tests = vec![
+            (
+                "true;",
+                Statement::Expression(Expression::Literal(Literal::Bool(true))),
+            ),
+            (
+                "false;",
+                Statement::Expression(Expression::Literal(Literal::Bool(false))),
+            ),
+        ];
+
         for (input, expect) in tests {
             let l = Lexer::new(input);
             let mut p = Parser::new(l);
 
             let program = p.parse_program();
             check_parse_errors(&mut p);
+            check_round_trip(&program);
 
             assert_eq!(vec![expect], program);
         }
@@ -623,6 +1417,7 @@ return 993322;
 
             let program = p.parse_program();
             check_parse_errors(&mut p);
+            check_round_trip(&program);
 
             assert_eq!(vec![expect], program);
         }
@@ -703,6 +1498,7 @@ return 993322;
 
             let program = p.parse_program();
             check_parse_errors(&mut p);
+            check_round_trip(&program);
 
             assert_eq!(vec![expect], program);
         }
@@ -717,6 +1513,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::If {
@@ -743,6 +1540,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::If {
@@ -771,6 +1569,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::Function {
@@ -806,6 +1605,7 @@ return 993322;
 
             let program = p.parse_program();
             check_parse_errors(&mut p);
+            check_round_trip(&program);
 
             assert_eq!(
                 vec![Statement::Expression(Expression::Function {
@@ -826,6 +1626,7 @@ return 993322;
 
         let program = p.parse_program();
         check_parse_errors(&mut p);
+        check_round_trip(&program);
 
         assert_eq!(
             vec![Statement::Expression(Expression::Call {
@@ -848,6 +1649,482 @@ return 993322;
         );
     }
 
+    #[test]
+    fn test_array_literal_expression() {
+        let input = "[1, 2 * 2]";
+
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+        check_round_trip(&program);
+
+        assert_eq!(
+            vec![Statement::Expression(Expression::Literal(Literal::Array(
+                vec![
+                    Expression::Literal(Literal::Int(1)),
+                    Expression::Infix(
+                        Infix::Multiply,
+                        Box::new(Expression::Literal(Literal::Int(2))),
+                        Box::new(Expression::Literal(Literal::Int(2))),
+                    ),
+                ]
+            )))],
+            program,
+        );
+    }
+
+    #[test]
+    fn test_hash_literal_expression() {
+        let tests = vec![
+            ("{}", vec![]),
+            (
+                r#"{"one": 1, true: 2 * 1}"#,
+                vec![
+                    (
+                        Expression::Literal(Literal::Str(String::from("one"))),
+                        Expression::Literal(Literal::Int(1)),
+                    ),
+                    (
+                        Expression::Literal(Literal::Bool(true)),
+                        Expression::Infix(
+                            Infix::Multiply,
+                            Box::new(Expression::Literal(Literal::Int(2))),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        ),
+                    ),
+                ],
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(
+                vec![Statement::Expression(Expression::Literal(Literal::Hash(
+                    expect
+                )))],
+                program,
+                "{input}"
+            );
+        }
+    }
+
+    fn nested(open: &str, inner: &str, close: &str, depth: usize) -> String {
+        format!("{}{inner}{}", open.repeat(depth), close.repeat(depth))
+    }
+
+    #[test]
+    fn test_nesting_too_deep() {
+        let tests = vec![
+            nested("(", "1", ")", 5000),
+            nested("-", "1", "", 5000),
+            nested("[", "1", "]", 5000),
+            nested("fn() { ", "1", " }", 1000),
+            nested("if (true) { ", "1", " }", 1000),
+        ];
+
+        for input in tests {
+            let mut p = Parser::new(Lexer::new(&input));
+            let program = p.parse_program();
+            let errors = p.get_errors();
+
+            assert!(program.is_empty());
+            assert_eq!(1, errors.len(), "{:?}", errors);
+            assert_eq!(ParseErrorKind::NestingTooDeep, *errors[0].kind());
+            assert_eq!("P007", errors[0].code());
+        }
+    }
+
+    #[test]
+    fn test_nesting_just_under_the_limit() {
+        // The statement's own expression is the first level.
+        let within = Parser::DEFAULT_MAX_DEPTH - 1;
+
+        for input in [nested("(", "1", ")", within), nested("-", "1", "", within)] {
+            let mut p = Parser::new(Lexer::new(&input));
+            let program = p.parse_program();
+
+            check_parse_errors(&mut p);
+            assert_eq!(1, program.len());
+        }
+
+        let input = nested("(", "1", ")", within + 1);
+        let mut p = Parser::new(Lexer::new(&input));
+        p.parse_program();
+        assert_eq!(ParseErrorKind::NestingTooDeep, *p.get_errors()[0].kind());
+    }
+
+    #[test]
+    fn test_with_max_depth() {
+        let mut p = Parser::with_max_depth(Lexer::new("((1))"), 3);
+        p.parse_program();
+        check_parse_errors(&mut p);
+
+        let mut p = Parser::with_max_depth(Lexer::new("(((1)))"), 3);
+        p.parse_program();
+        assert_eq!(ParseErrorKind::NestingTooDeep, *p.get_errors()[0].kind());
+    }
+
+    #[test]
+    fn test_parsing_continues_after_nesting_too_deep() {
+        let input = format!(
+            "let a = {}; let b = fn() {{ {}; 2 }}; let c = 3; c; )",
+            nested("(", "1", ")", 10),
+            nested("-", "1", "", 10),
+        );
+        let mut p = Parser::with_max_depth(Lexer::new(&input), 5);
+        let program = p.parse_program();
+        let errors = p.get_errors();
+
+        let kinds = errors
+            .iter()
+            .map(|err| err.kind().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ParseErrorKind::NestingTooDeep,
+                ParseErrorKind::NestingTooDeep,
+                ParseErrorKind::UnexpectedToken,
+            ],
+            kinds
+        );
+        assert_eq!(
+            vec![
+                Statement::Let(
+                    Identifier(String::from("c")),
+                    Expression::Literal(Literal::Int(3))
+                ),
+                Statement::Expression(Expression::Identifier(Identifier(String::from("c")))),
+            ],
+            program
+        );
+    }
+
+    #[test]
+    fn test_illegal_character_is_skipped() {
+        let mut p = Parser::new(Lexer::new("let x = 5 @ + 1;"));
+        let program = p.parse_program();
+        let errors = p.get_errors();
+
+        assert_eq!(1, errors.len(), "{:?}", errors);
+        assert_eq!(ParseErrorKind::IllegalCharacter, *errors[0].kind());
+        assert_eq!("P008", errors[0].code());
+        assert_eq!(
+            Position {
+                line: 1,
+                column: 11
+            },
+            errors[0].position()
+        );
+        assert_eq!(
+            "Illegal Character: illegal character '@'",
+            errors[0].to_string()
+        );
+        assert_eq!(
+            vec![Statement::Let(
+                Identifier(String::from("x")),
+                Expression::Infix(
+                    Infix::Plus,
+                    Box::new(Expression::Literal(Literal::Int(5))),
+                    Box::new(Expression::Literal(Literal::Int(1))),
+                ),
+            )],
+            program
+        );
+    }
+
+    // Every token is separated by a space and no string contains one, so a
+    // character injected at a space can never split or join tokens.
+    const SPACED_PROGRAMS: [&str; 5] = [
+        "let x = 5 + 1 ;",
+        "let add = fn ( a , b ) { return a + b ; } ; add ( 1 , 2 ) ;",
+        "if ( x < 10 ) { x } else { [ 1 , 2 ] [ 0 ] }",
+        "let h = { \"a\" : 1 , true : - 2.5 } ; h [ \"a\" ] ;",
+        "while ( i < 3 ) { i = i + 1 ; if ( i == 2 ) { continue ; } break ; }",
+    ];
+
+    /// One of `SPACED_PROGRAMS`, with an optional illegal character to put
+    /// before each of its tokens.
+    fn program_with_illegal_characters() -> impl Strategy<Value = (&'static str, Vec<Option<char>>)>
+    {
+        let illegal = vec!['@', '#', '$', '&', '|', '?', '~', '`', '§'];
+
+        prop::sample::select(SPACED_PROGRAMS.to_vec()).prop_flat_map(move |source| {
+            let injections = prop::collection::vec(
+                prop::option::weighted(1.0 / 3.0, prop::sample::select(illegal.clone())),
+                source.split(' ').count(),
+            );
+
+            (Just(source), injections)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn illegal_characters_are_reported_once_each(
+            (source, injections) in program_with_illegal_characters()
+        ) {
+            let mut input = String::new();
+
+            for (i, (piece, injection)) in source.split(' ').zip(&injections).enumerate() {
+                if i > 0 {
+                    input.push(' ');
+                }
+                if let Some(ch) = injection {
+                    input.push(*ch);
+                    input.push(' ');
+                }
+                input.push_str(piece);
+            }
+
+            let mut p = Parser::new(Lexer::new(&input));
+            let program = p.parse_program();
+            let errors = p.get_errors();
+            let injected = injections.iter().flatten().count();
+
+            prop_assert_eq!(injected, errors.len(), "{}: {:?}", input, errors);
+            prop_assert!(errors
+                .iter()
+                .all(|err| *err.kind() == ParseErrorKind::IllegalCharacter));
+
+            let mut clean = Parser::new(Lexer::new(source));
+            prop_assert_eq!(clean.parse_program(), program, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_float_literal_expression() {
+        let tests = vec![
+            ("0.5", Expression::Literal(Literal::Float(0.5))),
+            (
+                "-1.5",
+                Expression::Prefix(
+                    Prefix::Minus,
+                    Box::new(Expression::Literal(Literal::Float(1.5))),
+                ),
+            ),
+            (
+                "2.5 * 2",
+                Expression::Infix(
+                    Infix::Multiply,
+                    Box::new(Expression::Literal(Literal::Float(2.5))),
+                    Box::new(Expression::Literal(Literal::Int(2))),
+                ),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(vec![Statement::Expression(expect)], program, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_while_expression() {
+        let l = Lexer::new("while (x < 10) { x = x + 1; if (x == 5) { break } continue; }");
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+        check_round_trip(&program);
+
+        let x = || Box::new(Expression::Identifier(Identifier(String::from("x"))));
+        assert_eq!(
+            vec![Statement::Expression(Expression::While {
+                condition: Box::new(Expression::Infix(
+                    Infix::LessThan,
+                    x(),
+                    Box::new(Expression::Literal(Literal::Int(10))),
+                )),
+                body: vec![
+                    Statement::Assign(
+                        Identifier(String::from("x")),
+                        Expression::Infix(
+                            Infix::Plus,
+                            x(),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        ),
+                    ),
+                    Statement::Expression(Expression::If {
+                        condition: Box::new(Expression::Infix(
+                            Infix::Equal,
+                            x(),
+                            Box::new(Expression::Literal(Literal::Int(5))),
+                        )),
+                        consequence: vec![Statement::Break],
+                        alternative: None,
+                    }),
+                    Statement::Continue,
+                ],
+            })],
+            program
+        );
+
+        let mut p = Parser::new(Lexer::new("while x { x }"));
+        p.parse_program();
+
+        assert_eq!(
+            ParseErrorKind::ExpectedToken {
+                expected: Token::Lparen,
+                found: Token::Ident(String::from("x")),
+            },
+            *p.get_errors()[0].kind()
+        );
+    }
+
+    #[test]
+    fn test_const_statement() {
+        let l = Lexer::new("const PI = 3; const e = PI - 1");
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program();
+        check_parse_errors(&mut p);
+        check_round_trip(&program);
+
+        assert_eq!(
+            vec![
+                Statement::Const(
+                    Identifier(String::from("PI")),
+                    Expression::Literal(Literal::Int(3))
+                ),
+                Statement::Const(
+                    Identifier(String::from("e")),
+                    Expression::Infix(
+                        Infix::Minus,
+                        Box::new(Expression::Identifier(Identifier(String::from("PI")))),
+                        Box::new(Expression::Literal(Literal::Int(1))),
+                    )
+                ),
+            ],
+            program
+        );
+
+        let mut p = Parser::new(Lexer::new("const [a] = [1];"));
+        p.parse_program();
+
+        assert_eq!(
+            ParseErrorKind::ExpectedIdentifier,
+            *p.get_errors()[0].kind()
+        );
+    }
+
+    #[test]
+    fn test_destructure_array_statement() {
+        let tests = vec![
+            (
+                "let [x, y] = [1, 2];",
+                Statement::DestructureArray(
+                    vec![Identifier(String::from("x")), Identifier(String::from("y"))],
+                    Expression::Literal(Literal::Array(vec![
+                        Expression::Literal(Literal::Int(1)),
+                        Expression::Literal(Literal::Int(2)),
+                    ])),
+                ),
+            ),
+            (
+                "let [] = arr",
+                Statement::DestructureArray(
+                    vec![],
+                    Expression::Identifier(Identifier(String::from("arr"))),
+                ),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(vec![expect], program, "{input}");
+        }
+
+        let mut p = Parser::new(Lexer::new("let [x, 1] = arr;"));
+        p.parse_program();
+
+        assert_eq!(
+            ParseErrorKind::ExpectedIdentifier,
+            *p.get_errors()[0].kind()
+        );
+    }
+
+    #[test]
+    fn test_index_precedence() {
+        let tests = vec![
+            (
+                "arr[0](5)",
+                Statement::Expression(Expression::Call {
+                    function: Box::new(Expression::Index(
+                        Box::new(Expression::Identifier(Identifier(String::from("arr")))),
+                        Box::new(Expression::Literal(Literal::Int(0))),
+                    )),
+                    arguments: vec![Expression::Literal(Literal::Int(5))],
+                }),
+            ),
+            (
+                "f(1)[2]",
+                Statement::Expression(Expression::Index(
+                    Box::new(Expression::Call {
+                        function: Box::new(Expression::Identifier(Identifier(String::from("f")))),
+                        arguments: vec![Expression::Literal(Literal::Int(1))],
+                    }),
+                    Box::new(Expression::Literal(Literal::Int(2))),
+                )),
+            ),
+            (
+                "a * b[1 + 1]",
+                Statement::Expression(Expression::Infix(
+                    Infix::Multiply,
+                    Box::new(Expression::Identifier(Identifier(String::from("a")))),
+                    Box::new(Expression::Index(
+                        Box::new(Expression::Identifier(Identifier(String::from("b")))),
+                        Box::new(Expression::Infix(
+                            Infix::Plus,
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                            Box::new(Expression::Literal(Literal::Int(1))),
+                        )),
+                    )),
+                )),
+            ),
+            (
+                "-a[0]",
+                Statement::Expression(Expression::Prefix(
+                    Prefix::Minus,
+                    Box::new(Expression::Index(
+                        Box::new(Expression::Identifier(Identifier(String::from("a")))),
+                        Box::new(Expression::Literal(Literal::Int(0))),
+                    )),
+                )),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let l = Lexer::new(input);
+            let mut p = Parser::new(l);
+
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(vec![expect], program, "{input}");
+        }
+    }
+
     #[test]
     fn test_operator_precedence_parsing() {
         let tests = vec![
@@ -1118,8 +2395,309 @@ return 993322;
 
             let program = p.parse_program();
             check_parse_errors(&mut p);
+            check_round_trip(&program);
 
             assert_eq!(vec![expect], program);
         }
     }
+
+    #[test]
+    fn test_parse_ok() {
+        let program = Parser::new(Lexer::new("let x = 5; x;")).parse().unwrap();
+
+        assert_eq!(
+            vec![
+                Statement::Let(
+                    Identifier(String::from("x")),
+                    Expression::Literal(Literal::Int(5)),
+                ),
+                Statement::Expression(Expression::Identifier(Identifier(String::from("x")))),
+            ],
+            program
+        );
+    }
+
+    #[test]
+    fn test_parse_failure_keeps_partial_program() {
+        let failure = Parser::new(Lexer::new("let x = 5; ); x;"))
+            .parse()
+            .unwrap_err();
+
+        assert_eq!(1, failure.errors.len());
+        assert_eq!(&ParseErrorKind::UnexpectedToken, failure.errors[0].kind());
+        assert_eq!(
+            vec![
+                Statement::Let(
+                    Identifier(String::from("x")),
+                    Expression::Literal(Literal::Int(5)),
+                ),
+                Statement::Expression(Expression::Identifier(Identifier(String::from("x")))),
+            ],
+            failure.partial
+        );
+        assert!(failure.to_string().starts_with("1:12: "), "{failure}");
+    }
+
+    #[test]
+    fn test_semicolon_optional_after_block() {
+        let tests = vec![
+            ("if (x) { 1 } else { 2 } let y = 1;", 2),
+            ("if (x) { 1 } else { 2 }; let y = 1;", 2),
+            ("if (x) { 1 }\nx;", 2),
+            ("fn(x) { x }\nlet y = 1;", 2),
+            ("fn(x) { x }; let y = 1;", 2),
+            ("while (x) { x } x;", 2),
+            ("-if (x) { 1 } else { 2 } x;", 2),
+            ("1 + fn(x) { x } x;", 2),
+            ("let f = fn(x) { x }\nlet y = 1;", 2),
+            ("return fn(x) { x } x;", 2),
+            ("fn(x) { x }(1); x;", 2),
+            ("x + 1; let y = 1;", 2),
+            ("let x = 1; x", 2),
+            ("x", 1),
+            ("fn() { let x = 1; x + 1 }", 1),
+            ("if (x) { y; z } else { w }", 1),
+        ];
+
+        for (input, len) in tests {
+            let mut p = Parser::new(Lexer::new(input));
+            let program = p.parse_program();
+            check_parse_errors(&mut p);
+            check_round_trip(&program);
+
+            assert_eq!(len, program.len(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_missing_semicolon_is_reported_once() {
+        let tests = vec![
+            ("x + 1 let y = 2; y;", Token::Let, 3),
+            ("x + 1\nlet y = 2; y;", Token::Let, 3),
+            ("let x = 1 let y = 2;", Token::Let, 2),
+            ("const x = 1 x;", Token::Ident(String::from("x")), 2),
+            ("x = 1 x;", Token::Ident(String::from("x")), 2),
+            ("return 1 x;", Token::Ident(String::from("x")), 2),
+            ("x y;", Token::Ident(String::from("y")), 2),
+            ("f(1) if (x) { 1 }", Token::If, 2),
+            ("{1: 2} x;", Token::Ident(String::from("x")), 2),
+            ("fn() { x y }", Token::Ident(String::from("y")), 1),
+        ];
+
+        for (input, found, len) in tests {
+            let failure = Parser::new(Lexer::new(input)).parse().unwrap_err();
+
+            assert_eq!(1, failure.errors.len(), "{input}: {failure}");
+            assert_eq!(
+                &ParseErrorKind::ExpectedToken {
+                    expected: Token::Semicolon,
+                    found,
+                },
+                failure.errors[0].kind(),
+                "{input}"
+            );
+            assert_eq!(len, failure.partial.len(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_expression_str() {
+        let int = |value| Box::new(Expression::Literal(Literal::Int(value)));
+        let expected = Expression::Infix(
+            Infix::Plus,
+            int(1),
+            Box::new(Expression::Infix(Infix::Multiply, int(2), int(3))),
+        );
+
+        assert_eq!(expected, parse_expression_str("1 + 2 * 3").unwrap());
+        assert_eq!(expected, parse_expression_str(" 1 + 2 * 3; ").unwrap());
+    }
+
+    #[test]
+    fn test_parse_expression_str_errors() {
+        let tests = vec![
+            (
+                "",
+                ParseErrorKind::UnexpectedEof,
+                "expected an expression, got Eof instead",
+            ),
+            (
+                ";",
+                ParseErrorKind::UnexpectedEof,
+                "expected an expression, got Eof instead",
+            ),
+            (
+                "let x = 1;",
+                ParseErrorKind::UnexpectedToken,
+                "expected an expression, got `let x = 1;` instead",
+            ),
+            (
+                "1; 2",
+                ParseErrorKind::UnexpectedToken,
+                "expected a single expression, got 2 statements instead",
+            ),
+            (
+                "1 +",
+                ParseErrorKind::UnexpectedEof,
+                "expected an expression, got Eof instead",
+            ),
+        ];
+
+        for (input, kind, msg) in tests {
+            let errors = parse_expression_str(input).unwrap_err();
+
+            assert_eq!(1, errors.len(), "{input}");
+            assert_eq!(&kind, errors[0].kind(), "{input}");
+            assert_eq!(format!("{kind}: {msg}"), errors[0].to_string(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_statement_spans_slice_their_source() {
+        let input =
+            "let x = 1 + 2;\n  x * (3 - \"\u{e9}\");;\nif (x) { x } else { 0 }\nreturn x;\n3 - 4";
+        let expected = vec![
+            "let x = 1 + 2;",
+            "x * (3 - \"\u{e9}\");",
+            "if (x) { x } else { 0 }",
+            "return x;",
+            "3 - 4",
+        ];
+
+        let spanned = Parser::new(Lexer::new(input)).parse_program_spanned();
+        let slices: Vec<&str> = spanned
+            .statements
+            .iter()
+            .map(|statement| statement.span.slice(input))
+            .collect();
+
+        assert_eq!(expected, slices);
+        assert_eq!(
+            Parser::new(Lexer::new(input)).parse_program(),
+            spanned.into_program()
+        );
+    }
+
+    #[test]
+    fn test_infix_spans_slice_their_source() {
+        let input = "1 + 2 * 3; f(a - b)[c == d]; -(x + y) < z; fn(n) { n / 2 }(8) > 1";
+        let expected = vec![
+            "2 * 3",
+            "1 + 2 * 3",
+            "a - b",
+            "c == d",
+            "x + y",
+            "-(x + y) < z",
+            "n / 2",
+            "fn(n) { n / 2 }(8) > 1",
+        ];
+
+        let spanned = Parser::new(Lexer::new(input)).parse_program_spanned();
+        let slices: Vec<&str> = spanned
+            .infix_spans
+            .iter()
+            .map(|span| span.slice(input))
+            .collect();
+
+        assert_eq!(expected, slices);
+
+        // Each slice parses back into a lone infix expression.
+        for source in slices {
+            let program = Parser::new(Lexer::new(source)).parse_program();
+
+            assert!(
+                matches!(
+                    program.as_slice(),
+                    [Statement::Expression(Expression::Infix(..))]
+                ),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_infix_chain_spans() {
+        let terms = 20_000;
+        let input = format!("let x = 1{};", " + 1".repeat(terms - 1));
+
+        let spanned = Parser::new(Lexer::new(&input)).parse_program_spanned();
+
+        assert_eq!(1, spanned.statements.len());
+        assert_eq!(terms - 1, spanned.infix_spans.len());
+        assert_eq!("1 + 1", spanned.infix_spans[0].slice(&input));
+        assert_eq!(
+            &input["let x = ".len()..input.len() - 1],
+            spanned.infix_spans[terms - 2].slice(&input)
+        );
+    }
+
+    #[test]
+    fn test_abandoned_statements_leave_no_spans() {
+        let depth = Parser::DEFAULT_MAX_DEPTH;
+        let input = format!(
+            "let x = {}1 + 2{}; y + 1;",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        );
+        let spanned = Parser::new(Lexer::new(&input)).parse_program_spanned();
+
+        assert_eq!(1, spanned.statements.len());
+        assert_eq!("y + 1;", spanned.statements[0].span.slice(&input));
+        assert_eq!(1, spanned.infix_spans.len());
+        assert_eq!("y + 1", spanned.infix_spans[0].slice(&input));
+    }
+}
+
+#[cfg(test)]
+mod snapshots {
+    use insta::assert_debug_snapshot;
+
+    use crate::ast::ast::{to_monkey_source, Program};
+    use crate::lexer::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    fn parse(input: &str) -> Program {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+
+        let errors = p.get_errors();
+        assert!(errors.is_empty(), "parse errors: {:?}", errors);
+        assert_eq!(
+            program,
+            Parser::new(Lexer::new(&to_monkey_source(program.as_slice()))).parse_program()
+        );
+
+        program
+    }
+
+    #[test]
+    fn let_statement() {
+        assert_debug_snapshot!("let_statement", parse("let x = 5; let y = x;"));
+    }
+
+    #[test]
+    fn return_statement() {
+        assert_debug_snapshot!("return_statement", parse("return 5; return x + 1;"));
+    }
+
+    #[test]
+    fn operator_precedence() {
+        assert_debug_snapshot!(
+            "operator_precedence",
+            parse("-a * b + c / d == !e; 3 + 4 * 5 > 1 - -2")
+        );
+    }
+
+    #[test]
+    fn if_else_expression() {
+        assert_debug_snapshot!("if_else_expression", parse("if (x < y) { x } else { y }"));
+    }
+
+    #[test]
+    fn function_and_call() {
+        assert_debug_snapshot!(
+            "function_and_call",
+            parse("let add = fn(x, y) { return x + y; }; add(1, add(2, 3));")
+        );
+    }
 }
@@ -0,0 +1,387 @@
+use std::fmt;
+
+/// A REPL meta-command: a line starting with `:` that talks to the REPL
+/// itself instead of being evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    Quit,
+    Reset,
+    Env,
+    Trace,
+    Type(String),
+    Load(String),
+    Save(String),
+    Color(bool),
+    Lint(bool),
+}
+
+/// Why a `:` line isn't a valid command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Unknown {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    MissingArgument {
+        command: &'static str,
+        usage: String,
+    },
+    UnexpectedArgument {
+        command: &'static str,
+    },
+    InvalidFlag {
+        command: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Unknown {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "unknown command `:{name}`, did you mean `:{suggestion}`?"
+            ),
+            CommandError::Unknown {
+                name,
+                suggestion: None,
+            } => write!(f, "unknown command `:{name}`, see `:help`"),
+            CommandError::MissingArgument { command, usage } => {
+                write!(f, "`:{command}` needs an argument: {usage}")
+            }
+            CommandError::UnexpectedArgument { command } => {
+                write!(f, "`:{command}` doesn't take an argument")
+            }
+            CommandError::InvalidFlag { command, value } => {
+                write!(f, "`:{command}` expects `on` or `off`, got `{value}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// What follows a command's name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Argument {
+    None,
+    Path,
+    Source,
+    Flag,
+}
+
+impl Argument {
+    fn placeholder(self) -> &'static str {
+        match self {
+            Argument::None => "",
+            Argument::Path => "PATH",
+            Argument::Source => "EXPR",
+            Argument::Flag => "on|off",
+        }
+    }
+}
+
+struct CommandSpec {
+    name: &'static str,
+    argument: Argument,
+    description: &'static str,
+}
+
+impl CommandSpec {
+    fn usage(&self) -> String {
+        match self.argument {
+            Argument::None => format!(":{}", self.name),
+            argument => format!(":{} {}", self.name, argument.placeholder()),
+        }
+    }
+}
+
+/// Every command, in the order `:help` lists them. Parsing looks names up
+/// here too, so a command can't be dispatched without being documented.
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "help",
+        argument: Argument::None,
+        description: "show this list",
+    },
+    CommandSpec {
+        name: "quit",
+        argument: Argument::None,
+        description: "leave the REPL",
+    },
+    CommandSpec {
+        name: "reset",
+        argument: Argument::None,
+        description: "forget every binding made in this session",
+    },
+    CommandSpec {
+        name: "env",
+        argument: Argument::None,
+        description: "list the bindings made so far, without builtins",
+    },
+    CommandSpec {
+        name: "trace",
+        argument: Argument::None,
+        description: "show the call stack of the last error",
+    },
+    CommandSpec {
+        name: "type",
+        argument: Argument::Source,
+        description: "evaluate an expression and show its type",
+    },
+    CommandSpec {
+        name: "load",
+        argument: Argument::Path,
+        description: "evaluate a file in this session",
+    },
+    CommandSpec {
+        name: "save",
+        argument: Argument::Path,
+        description: "write the inputs evaluated so far to a file",
+    },
+    CommandSpec {
+        name: "color",
+        argument: Argument::Flag,
+        description: "turn syntax highlighting on or off",
+    },
+    CommandSpec {
+        name: "lint",
+        argument: Argument::Flag,
+        description: "turn warnings about suspicious inputs on or off",
+    },
+];
+
+/// Reads `line` as a meta-command, or gives `None` if it doesn't start with
+/// `:`. Command names are case-insensitive; arguments are kept as written.
+pub fn parse_command(line: &str) -> Option<Result<Command, CommandError>> {
+    let line = line.trim().strip_prefix(':')?;
+    let (name, argument) = match line.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (line, ""),
+    };
+    let name = name.to_lowercase();
+
+    let spec = match COMMANDS.iter().find(|spec| spec.name == name) {
+        Some(spec) => spec,
+        None => {
+            let suggestion = suggest(&name);
+            return Some(Err(CommandError::Unknown { name, suggestion }));
+        }
+    };
+
+    Some(build(spec, argument))
+}
+
+/// One line per command: its usage, then what it does.
+pub fn help_text() -> String {
+    let usages: Vec<String> = COMMANDS.iter().map(CommandSpec::usage).collect();
+    let width = usages.iter().map(String::len).max().unwrap_or(0);
+
+    COMMANDS
+        .iter()
+        .zip(&usages)
+        .map(|(spec, usage)| format!("{usage:<width$}  {}\n", spec.description))
+        .collect()
+}
+
+fn build(spec: &CommandSpec, argument: &str) -> Result<Command, CommandError> {
+    match spec.argument {
+        Argument::None if !argument.is_empty() => {
+            return Err(CommandError::UnexpectedArgument { command: spec.name });
+        }
+        Argument::Path | Argument::Source | Argument::Flag if argument.is_empty() => {
+            return Err(CommandError::MissingArgument {
+                command: spec.name,
+                usage: spec.usage(),
+            });
+        }
+        _ => {}
+    }
+
+    let command = match spec.name {
+        "help" => Command::Help,
+        "quit" => Command::Quit,
+        "reset" => Command::Reset,
+        "env" => Command::Env,
+        "trace" => Command::Trace,
+        "type" => Command::Type(String::from(argument)),
+        "load" => Command::Load(String::from(argument)),
+        "save" => Command::Save(String::from(argument)),
+        "color" => Command::Color(parse_flag(spec.name, argument)?),
+        "lint" => Command::Lint(parse_flag(spec.name, argument)?),
+        _ => unreachable!("`:{}` is listed but never built", spec.name),
+    };
+
+    Ok(command)
+}
+
+fn parse_flag(command: &'static str, value: &str) -> Result<bool, CommandError> {
+    match value.to_lowercase().as_str() {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(CommandError::InvalidFlag {
+            command,
+            value: String::from(value),
+        }),
+    }
+}
+
+// The command sharing the longest prefix with `name`, if any shares one.
+fn suggest(name: &str) -> Option<&'static str> {
+    let shared = |spec: &CommandSpec| {
+        spec.name
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+
+    let mut best = None;
+    let mut best_len = 0;
+
+    for spec in COMMANDS {
+        let len = shared(spec);
+
+        if len > best_len {
+            best = Some(spec.name);
+            best_len = len;
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::commands::{help_text, parse_command, Command, CommandError, COMMANDS};
+
+    fn parse(line: &str) -> Result<Command, CommandError> {
+        parse_command(line).expect("should be read as a command")
+    }
+
+    #[test]
+    fn test_not_a_command() {
+        assert_eq!(None, parse_command("let x = 1;"));
+        assert_eq!(None, parse_command(""));
+        assert_eq!(None, parse_command("x : y"));
+    }
+
+    #[test]
+    fn test_commands_without_arguments() {
+        let tests = vec![
+            (":help", Command::Help),
+            (":quit", Command::Quit),
+            (":reset", Command::Reset),
+            (":env", Command::Env),
+            (":trace", Command::Trace),
+            ("  :help  ", Command::Help),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Ok(expect), parse(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_names_are_case_insensitive() {
+        assert_eq!(Ok(Command::Quit), parse(":QUIT"));
+        assert_eq!(Ok(Command::Help), parse(":Help"));
+        assert_eq!(Ok(Command::Color(true)), parse(":COLOR ON"));
+    }
+
+    #[test]
+    fn test_commands_with_arguments() {
+        let tests = vec![
+            (":type 1 + 2", Command::Type(String::from("1 + 2"))),
+            (
+                ":type   len(\"a b\")  ",
+                Command::Type(String::from("len(\"a b\")")),
+            ),
+            (
+                ":load lib.monkey",
+                Command::Load(String::from("lib.monkey")),
+            ),
+            (
+                ":load My Programs/Lib.monkey",
+                Command::Load(String::from("My Programs/Lib.monkey")),
+            ),
+            (
+                ":save session.monkey",
+                Command::Save(String::from("session.monkey")),
+            ),
+            (":color on", Command::Color(true)),
+            (":color off", Command::Color(false)),
+            (":color Off", Command::Color(false)),
+            (":lint on", Command::Lint(true)),
+            (":lint off", Command::Lint(false)),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Ok(expect), parse(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_argument_errors() {
+        let tests = vec![
+            (":quit now", "`:quit` doesn't take an argument"),
+            (":help me", "`:help` doesn't take an argument"),
+            (":load", "`:load` needs an argument: :load PATH"),
+            (":save   ", "`:save` needs an argument: :save PATH"),
+            (":type", "`:type` needs an argument: :type EXPR"),
+            (":color", "`:color` needs an argument: :color on|off"),
+            (":color yes", "`:color` expects `on` or `off`, got `yes`"),
+            (":lint", "`:lint` needs an argument: :lint on|off"),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, parse(input).unwrap_err().to_string(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_commands() {
+        let tests = vec![
+            (":lod x", "unknown command `:lod`, did you mean `:load`?"),
+            (":q", "unknown command `:q`, did you mean `:quit`?"),
+            (":RES", "unknown command `:res`, did you mean `:reset`?"),
+            (":tr", "unknown command `:tr`, did you mean `:trace`?"),
+            (":xyzzy", "unknown command `:xyzzy`, see `:help`"),
+            (":", "unknown command `:`, see `:help`"),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, parse(input).unwrap_err().to_string(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_every_listed_command_parses() {
+        for spec in COMMANDS {
+            let line = match spec.argument.placeholder() {
+                "" => format!(":{}", spec.name),
+                "on|off" => format!(":{} on", spec.name),
+                _ => format!(":{} x", spec.name),
+            };
+
+            assert!(parse(&line).is_ok(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_help_lists_every_command() {
+        let help = help_text();
+
+        assert_eq!(COMMANDS.len(), help.lines().count());
+        assert!(help.starts_with(":help "));
+        assert!(help.contains(":load PATH     evaluate a file in this session\n"));
+
+        for spec in COMMANDS {
+            assert!(help.contains(spec.description), "{}", spec.name);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::IsTerminal;
+
+use crate::lexer::lexer::Lexer;
+use crate::token::token::Token;
+
+/// The ANSI color used for each kind of token, kept in one place so the
+/// scheme can be changed without touching the highlighter.
+pub mod colors {
+    pub const KEYWORD: &str = "\x1b[1;35m";
+    pub const NUMBER: &str = "\x1b[33m";
+    pub const STRING: &str = "\x1b[32m";
+    pub const OPERATOR: &str = "\x1b[36m";
+    pub const ILLEGAL: &str = "\x1b[31m";
+    pub const RESET: &str = "\x1b[0m";
+}
+
+/// Whether the REPL should color its input: not when `NO_COLOR` is set to
+/// a non-empty value, and not when stdout isn't a terminal.
+pub fn color_enabled() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    !no_color && std::io::stdout().is_terminal()
+}
+
+fn color_of(tok: &Token) -> Option<&'static str> {
+    match tok {
+        Token::Function
+        | Token::Let
+        | Token::Const
+        | Token::If
+        | Token::Else
+        | Token::Return
+        | Token::While
+        | Token::Break
+        | Token::Continue
+        | Token::Bool(_) => Some(colors::KEYWORD),
+        Token::Int(_) | Token::BigInt(_) | Token::Float(_) => Some(colors::NUMBER),
+        Token::Str(_) => Some(colors::STRING),
+        Token::Assign
+        | Token::Plus
+        | Token::Minus
+        | Token::Bang
+        | Token::Asterisk
+        | Token::Slash
+        | Token::Equal
+        | Token::NotEqual
+        | Token::LessThan
+        | Token::LessThanEqual
+        | Token::GreaterThan
+        | Token::GreaterThanEqual => Some(colors::OPERATOR),
+        Token::Illegal(_) | Token::InvalidEscape(_) => Some(colors::ILLEGAL),
+        _ => None,
+    }
+}
+
+/// Wraps every keyword, number, string and operator in `line` in its ANSI
+/// color. Everything else, whitespace included, is copied unchanged.
+pub fn highlight_line(line: &str) -> String {
+    let mut lexer = Lexer::new(line);
+    let mut output = String::with_capacity(line.len());
+    let mut end = 0;
+
+    loop {
+        let (tok, span) = lexer.next_token_with_span();
+
+        if tok == Token::Eof {
+            break;
+        }
+
+        output.push_str(&line[end..span.start]);
+
+        match color_of(&tok) {
+            Some(color) => {
+                output.push_str(color);
+                output.push_str(&line[span.clone()]);
+                output.push_str(colors::RESET);
+            }
+            None => output.push_str(&line[span.clone()]),
+        }
+
+        end = span.end;
+    }
+
+    output.push_str(&line[end..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::highlight::colors::{KEYWORD, NUMBER, OPERATOR, RESET, STRING};
+    use crate::repl::highlight::highlight_line;
+
+    fn strip_colors(line: &str) -> String {
+        let mut output = String::new();
+        let mut chars = line.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                chars.by_ref().find(|&ch| ch == 'm');
+            } else {
+                output.push(ch);
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn test_highlight_line() {
+        assert_eq!(
+            format!(
+                "{KEYWORD}let{RESET} x {OPERATOR}={RESET} {NUMBER}5{RESET} {OPERATOR}*{RESET} y;"
+            ),
+            highlight_line("let x = 5 * y;")
+        );
+        assert_eq!(
+            format!("{KEYWORD}if{RESET} ({KEYWORD}true{RESET}) {{ {STRING}\"hi\"{RESET} }}"),
+            highlight_line("if (true) { \"hi\" }")
+        );
+    }
+
+    #[test]
+    fn test_highlight_line_keeps_the_text() {
+        let tests = vec![
+            "",
+            "  fn(a, b) {\treturn a >= b; }  ",
+            "let 변수 = [1, 2][0];",
+            "\"unterminated @",
+        ];
+
+        for input in tests {
+            assert_eq!(input, strip_colors(&highlight_line(input)));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::evaluator::environment::Environment;
+use crate::evaluator::object::Object;
+
+/// Binds REPL results so later inputs can refer to them: the latest one as
+/// `_` and each one as `_1`, `_2` and so on, in the order they were recorded.
+///
+/// Only names that have been recorded exist, so nothing listing the
+/// environment's bindings sees a `_N` before its result does.
+#[derive(Debug, Default)]
+pub struct SessionBindings {
+    count: usize,
+}
+
+impl SessionBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `object` as `_` and the next `_N`, returning `N`. `null` and
+    /// errors aren't results worth keeping, so they leave every name as it
+    /// was and give `None`. A name the user made a constant in `env` keeps
+    /// its value.
+    pub fn record(&mut self, env: &Rc<RefCell<Environment>>, object: &Object) -> Option<usize> {
+        if let Object::Null | Object::Error(_) = object {
+            return None;
+        }
+
+        self.count += 1;
+
+        let mut env = env.borrow_mut();
+        for name in [String::from("_"), format!("_{}", self.count)] {
+            if !env.is_constant(&name) {
+                env.define(name, object);
+            }
+        }
+
+        Some(self.count)
+    }
+
+    /// How many results have been recorded.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::evaluator::environment::Environment;
+    use crate::evaluator::evaluator::Evaluator;
+    use crate::evaluator::object::Object;
+    use crate::lexer::lexer::Lexer;
+    use crate::parser::parser::Parser;
+    use crate::repl::session::SessionBindings;
+
+    #[test]
+    fn test_record_names() {
+        let env = Rc::new(RefCell::new(Environment::new()));
+        let mut session = SessionBindings::new();
+
+        assert_eq!(Some(1), session.record(&env, &Object::Int(10)));
+        assert_eq!(
+            Some(2),
+            session.record(&env, &Object::Str(String::from("a")))
+        );
+
+        let env = env.borrow();
+        assert_eq!(
+            Some(Object::Str(String::from("a"))),
+            env.get(String::from("_"))
+        );
+        assert_eq!(Some(Object::Int(10)), env.get(String::from("_1")));
+        assert_eq!(
+            Some(Object::Str(String::from("a"))),
+            env.get(String::from("_2"))
+        );
+        assert_eq!(None, env.get(String::from("_3")));
+        assert_eq!(2, session.count());
+    }
+
+    #[test]
+    fn test_record_skips_null_and_errors() {
+        let env = Rc::new(RefCell::new(Environment::new()));
+        let mut session = SessionBindings::new();
+
+        assert_eq!(None, session.record(&env, &Object::Null));
+        assert!(env.borrow().is_empty());
+
+        session.record(&env, &Object::Int(1));
+        assert_eq!(None, session.record(&env, &Object::Null));
+        assert_eq!(
+            None,
+            session.record(&env, &Object::Error(String::from("oops")))
+        );
+
+        let env = env.borrow();
+        assert_eq!(Some(Object::Int(1)), env.get(String::from("_")));
+        assert_eq!(None, env.get(String::from("_2")));
+        assert_eq!(1, session.count());
+    }
+
+    #[test]
+    fn test_record_keeps_constants() {
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let mut session = SessionBindings::new();
+
+        for input in ["const _ = 3;", "const _2 = 4;", "7", "8", "_ + _2"] {
+            let program = Parser::new(Lexer::new(input)).parse_program();
+            if let Some(result) = evaluator.eval(program) {
+                session.record(&evaluator.environment(), &result);
+            }
+        }
+
+        let env = evaluator.environment();
+        let env = env.borrow();
+        assert_eq!(Some(Object::Int(3)), env.get(String::from("_")));
+        assert_eq!(Some(Object::Int(7)), env.get(String::from("_1")));
+        assert_eq!(Some(Object::Int(4)), env.get(String::from("_2")));
+        assert_eq!(Some(Object::Int(7)), env.get(String::from("_3")));
+        assert_eq!(3, session.count());
+    }
+
+    #[test]
+    fn test_results_are_usable_in_later_input() {
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let mut session = SessionBindings::new();
+
+        for (input, expect) in [
+            ("5 + 5", Object::Int(10)),
+            ("_ * 2", Object::Int(20)),
+            ("_1 + _2", Object::Int(30)),
+        ] {
+            let program = Parser::new(Lexer::new(input)).parse_program();
+            let result = evaluator.eval(program).unwrap();
+            assert_eq!(expect, result, "{input}");
+
+            session.record(&evaluator.environment(), &result);
+        }
+    }
+}
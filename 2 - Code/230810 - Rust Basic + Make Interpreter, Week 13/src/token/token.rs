@@ -1,13 +1,19 @@
 #[derive(Clone, Debug, PartialEq)]
 pub enum Token {
-    Illegal,
+    Illegal(char),
+    /// A string literal with a malformed escape, which is named as written.
+    InvalidEscape(String),
     Eof,
 
     // Identifiers + Literals
     Ident(String),
-    Int(i64),
-    Double(f64),
+    Int(u64),
+    /// An integer literal too large for `Int`, as written. The parser
+    /// reports it as out of range.
+    BigInt(String),
+    Float(f64),
     Bool(bool),
+    Str(String),
 
     // Operators
     Assign,
@@ -26,16 +32,63 @@ pub enum Token {
 
     // Delimiters
     Comma,
+    Colon,
     Semicolon,
     Lparen,
     Rparen,
     Lbrace,
     Rbrace,
+    Lbracket,
+    Rbracket,
 
     // Reserved Keywords
     Function,
     Let,
+    Const,
     If,
     Else,
     Return,
+    While,
+    Break,
+    Continue,
+}
+
+/// A location in the source, both 1-based and counted in characters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A half-open byte range of the source, so `&source[span.start..span.end]`
+/// is exactly the text a token or node was parsed from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The span running from the start of `self` to the end of `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+        }
+    }
+
+    pub fn slice(self, source: &str) -> &str {
+        &source[self.start..self.end]
+    }
+
+    /// Where in `source` the span starts, as the lexer would report it.
+    pub fn start_position(self, source: &str) -> Position {
+        let before = &source[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
 }
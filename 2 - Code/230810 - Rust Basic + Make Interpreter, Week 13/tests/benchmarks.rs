@@ -0,0 +1,164 @@
+//! Timing benchmarks for the tree-walking evaluator.
+//!
+//! They are ignored by default, since timings are only comparable in an
+//! optimized build on a quiet machine:
+//!
+//! ```text
+//! cargo test --release --test benchmarks -- --ignored
+//! ```
+//!
+//! A run fails if a benchmark takes more than twice its time in
+//! `tests/benchmarks/baseline.json`. Set `MONKEY_BENCH_UPDATE=1` to
+//! rewrite the baseline with the current timings instead.
+
+#[path = "benchmarks/programs.rs"]
+mod programs;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use monkey::evaluator::environment::Environment;
+use monkey::evaluator::evaluator::Evaluator;
+use monkey::lexer::lexer::Lexer;
+use monkey::parser::parser::Parser;
+use programs::Benchmark;
+
+const RUNS: usize = 5;
+const ALLOWED_SLOWDOWN: f64 = 2.0;
+
+fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/benchmarks/baseline.json")
+}
+
+/// Reads the flat `{"name": milliseconds, ...}` object the baseline is stored as.
+fn read_baseline() -> BTreeMap<String, f64> {
+    let text = fs::read_to_string(baseline_path()).unwrap_or_default();
+
+    text.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|entry| {
+            let (name, millis) = entry.split_once(':')?;
+            let name = name.trim().trim_matches('"');
+            Some((String::from(name), millis.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn write_baseline(baseline: &BTreeMap<String, f64>) {
+    let entries = baseline
+        .iter()
+        .map(|(name, millis)| format!("  \"{name}\": {millis:.3}"))
+        .collect::<Vec<_>>();
+
+    fs::write(
+        baseline_path(),
+        format!("{{\n{}\n}}\n", entries.join(",\n")),
+    )
+    .unwrap();
+}
+
+/// The fastest of `RUNS` runs, which is the least noisy figure to compare.
+fn time(mut run: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn time_evaluator(benchmark: &Benchmark) -> Duration {
+    let source = (benchmark.source)();
+
+    time(|| {
+        let program = Parser::new(Lexer::new(&source)).parse_program();
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+        let result = evaluator.eval(program).map(|object| object.to_string());
+
+        if let Some(expected) = benchmark.expected {
+            assert_eq!(Some(String::from(expected)), result, "{}", benchmark.name);
+        }
+    })
+}
+
+fn time_parser(benchmark: &Benchmark) -> Duration {
+    let source = (benchmark.source)();
+
+    time(|| {
+        let mut parser = Parser::new(Lexer::new(&source));
+        parser.parse_program();
+        assert!(parser.get_errors().is_empty(), "{}", benchmark.name);
+    })
+}
+
+fn check(name: &str, elapsed: Duration) {
+    let millis = elapsed.as_secs_f64() * 1000.0;
+    let mut baseline = read_baseline();
+
+    println!("{name}: {millis:.3} ms");
+
+    if std::env::var_os("MONKEY_BENCH_UPDATE").is_some() {
+        baseline.insert(String::from(name), millis);
+        write_baseline(&baseline);
+        return;
+    }
+
+    match baseline.get(name) {
+        Some(&expected) => assert!(
+            millis <= expected * ALLOWED_SLOWDOWN,
+            "{name} took {millis:.3} ms, more than {ALLOWED_SLOWDOWN}x the {expected:.3} ms baseline"
+        ),
+        None => panic!("{name} has no baseline; rerun with MONKEY_BENCH_UPDATE=1"),
+    }
+}
+
+#[test]
+#[ignore]
+fn bench_fibonacci() {
+    check(
+        programs::FIBONACCI.name,
+        time_evaluator(&programs::FIBONACCI),
+    );
+}
+
+#[test]
+#[ignore]
+fn bench_arithmetic() {
+    check(
+        programs::ARITHMETIC.name,
+        time_evaluator(&programs::ARITHMETIC),
+    );
+}
+
+#[test]
+#[ignore]
+fn bench_parse() {
+    check(programs::PARSE.name, time_parser(&programs::PARSE));
+}
+
+/// Keeps the benchmark programs themselves working in ordinary test runs.
+#[test]
+fn benchmark_programs_are_valid() {
+    for benchmark in programs::ALL {
+        let source = (benchmark.source)();
+        let mut parser = Parser::new(Lexer::new(&source));
+        let program = parser.parse_program();
+
+        assert!(parser.get_errors().is_empty(), "{}", benchmark.name);
+
+        if let Some(expected) = benchmark.expected {
+            let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Environment::new())));
+            let result = evaluator.eval(program).map(|object| object.to_string());
+
+            assert_eq!(Some(String::from(expected)), result, "{}", benchmark.name);
+        }
+    }
+}
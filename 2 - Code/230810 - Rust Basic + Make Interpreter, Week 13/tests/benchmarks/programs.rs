@@ -0,0 +1,63 @@
+//! Programs shared by every engine benchmark, so the evaluator and any
+//! later engine are timed on exactly the same input.
+
+pub struct Benchmark {
+    pub name: &'static str,
+    pub source: fn() -> String,
+    /// The displayed result of evaluating `source`, or `None` for
+    /// benchmarks that only parse.
+    pub expected: Option<&'static str>,
+}
+
+pub const FIBONACCI: Benchmark = Benchmark {
+    name: "fibonacci_20",
+    source: fibonacci_source,
+    expected: Some("6765"),
+};
+
+pub const ARITHMETIC: Benchmark = Benchmark {
+    name: "arithmetic_10k",
+    source: arithmetic_source,
+    expected: Some("5000"),
+};
+
+pub const PARSE: Benchmark = Benchmark {
+    name: "parse_large_source",
+    source: parse_source,
+    expected: None,
+};
+
+pub const ALL: [Benchmark; 3] = [FIBONACCI, ARITHMETIC, PARSE];
+
+fn fibonacci_source() -> String {
+    String::from("let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(20);")
+}
+
+fn arithmetic_source() -> String {
+    String::from(
+        "let a = 0;
+let i = 0;
+while (i < 10000) {
+    a = if (a < 5000) { a + 2 * 3 - 5 } else { a - 1 + 1 };
+    i = i + 1;
+}
+a;
+",
+    )
+}
+
+/// Straight-line code, so the parser sees a couple of hundred thousand
+/// statements rather than one short loop.
+fn parse_source() -> String {
+    let mut source = String::new();
+
+    for _ in 0..20 {
+        source.push_str(&fibonacci_source());
+
+        for _ in 0..10_000 {
+            source.push_str("let a = if (a < 5000) { a + 2 * 3 - 5 } else { a - 1 + 1 };\n");
+        }
+    }
+
+    source
+}
@@ -1,9 +1,37 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Player {
     pub health: u32,
     pub mana: Option<u32>,
     pub level: u32,
 }
 
+/// The layout version written by `Player::to_bytes`.
+pub const PLAYER_FORMAT_VERSION: u8 = 1;
+
+/// Why `Player::from_bytes` rejected its input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlayerDecodeError {
+    Truncated,
+    UnsupportedVersion(u8),
+    InvalidOptionTag(u8),
+    TrailingBytes,
+}
+
+impl fmt::Display for PlayerDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerDecodeError::Truncated => write!(f, "input ends before the player does"),
+            PlayerDecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported player format version {version}")
+            }
+            PlayerDecodeError::InvalidOptionTag(tag) => write!(f, "invalid mana tag {tag}"),
+            PlayerDecodeError::TrailingBytes => write!(f, "input continues after the player"),
+        }
+    }
+}
+
 impl Player {
     pub fn revive(&self) -> Option<Player> {
         if self.health == 0 {
@@ -17,6 +45,53 @@ impl Player {
         None
     }
 
+    /// Encode as the version byte, then health and level as little-endian
+    /// `u32`s, then a tag byte that is 1 if a little-endian `u32` mana
+    /// follows and 0 if the player has no mana pool.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![PLAYER_FORMAT_VERSION];
+        bytes.extend_from_slice(&self.health.to_le_bytes());
+        bytes.extend_from_slice(&self.level.to_le_bytes());
+
+        match self.mana {
+            Some(mana) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&mana.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+
+        bytes
+    }
+
+    /// Decode what `to_bytes` wrote, which must be the whole input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Player, PlayerDecodeError> {
+        let mut rest = bytes;
+
+        let version = take_u8(&mut rest)?;
+        if version != PLAYER_FORMAT_VERSION {
+            return Err(PlayerDecodeError::UnsupportedVersion(version));
+        }
+
+        let health = take_u32(&mut rest)?;
+        let level = take_u32(&mut rest)?;
+        let mana = match take_u8(&mut rest)? {
+            0 => None,
+            1 => Some(take_u32(&mut rest)?),
+            tag => return Err(PlayerDecodeError::InvalidOptionTag(tag)),
+        };
+
+        if !rest.is_empty() {
+            return Err(PlayerDecodeError::TrailingBytes);
+        }
+
+        Ok(Player {
+            health,
+            mana,
+            level,
+        })
+    }
+
     pub fn cast_spell(&mut self, mana_cost: u32) -> u32 {
         match self.mana {
             Some(ref mut mana) => {
@@ -39,6 +114,24 @@ impl Player {
     }
 }
 
+// Split one byte off the front of `rest`.
+fn take_u8(rest: &mut &[u8]) -> Result<u8, PlayerDecodeError> {
+    let (&byte, tail) = rest.split_first().ok_or(PlayerDecodeError::Truncated)?;
+    *rest = tail;
+    Ok(byte)
+}
+
+// Split a little-endian `u32` off the front of `rest`.
+fn take_u32(rest: &mut &[u8]) -> Result<u32, PlayerDecodeError> {
+    if rest.len() < 4 {
+        return Err(PlayerDecodeError::Truncated);
+    }
+
+    let (head, tail) = rest.split_at(4);
+    *rest = tail;
+    Ok(u32::from_le_bytes(head.try_into().unwrap()))
+}
+
 #[test]
 fn test_reviving_dead_player() {
     let dead_player = Player {
@@ -164,3 +257,101 @@ fn test_cast_large_spell_with_no_mana_pool() {
     assert_eq!(underleveled_player.mana, None);
     assert_eq!(underleveled_player.level, 6);
 }
+
+#[test]
+fn test_bytes_round_trip() {
+    let values = [0, 1, 10, 100, u32::MAX - 1, u32::MAX];
+    for &health in &values {
+        for &level in &values {
+            for mana in [None, Some(0), Some(7), Some(u32::MAX)] {
+                let player = Player {
+                    health,
+                    mana,
+                    level,
+                };
+                let bytes = player.to_bytes();
+                assert_eq!(bytes.len(), if mana.is_some() { 14 } else { 10 });
+                assert_eq!(Player::from_bytes(&bytes), Ok(player));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bytes_layout() {
+    let player = Player {
+        health: 0x0102_0304,
+        mana: Some(0xAABB_CCDD),
+        level: 9,
+    };
+    assert_eq!(
+        player.to_bytes(),
+        vec![1, 4, 3, 2, 1, 9, 0, 0, 0, 1, 0xDD, 0xCC, 0xBB, 0xAA]
+    );
+}
+
+#[test]
+fn test_decoding_truncated_input() {
+    let bytes = Player {
+        health: 5,
+        mana: Some(5),
+        level: 5,
+    }
+    .to_bytes();
+    for len in 0..bytes.len() {
+        assert_eq!(
+            Player::from_bytes(&bytes[..len]),
+            Err(PlayerDecodeError::Truncated),
+            "{len} bytes"
+        );
+    }
+}
+
+#[test]
+fn test_decoding_bad_version() {
+    let mut bytes = Player {
+        health: 5,
+        mana: None,
+        level: 5,
+    }
+    .to_bytes();
+    bytes[0] = 2;
+    assert_eq!(
+        Player::from_bytes(&bytes),
+        Err(PlayerDecodeError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        Player::from_bytes(&[0]),
+        Err(PlayerDecodeError::UnsupportedVersion(0))
+    );
+}
+
+#[test]
+fn test_decoding_invalid_option_tag() {
+    let mut bytes = Player {
+        health: 5,
+        mana: Some(5),
+        level: 5,
+    }
+    .to_bytes();
+    bytes[9] = 2;
+    assert_eq!(
+        Player::from_bytes(&bytes),
+        Err(PlayerDecodeError::InvalidOptionTag(2))
+    );
+}
+
+#[test]
+fn test_decoding_trailing_bytes() {
+    let mut bytes = Player {
+        health: 5,
+        mana: None,
+        level: 5,
+    }
+    .to_bytes();
+    bytes.push(0);
+    assert_eq!(
+        Player::from_bytes(&bytes),
+        Err(PlayerDecodeError::TrailingBytes)
+    );
+}
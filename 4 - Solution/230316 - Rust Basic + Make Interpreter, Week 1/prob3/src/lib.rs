@@ -1,40 +1,144 @@
 pub fn annotate(minefield: &[&str]) -> Vec<String> {
-    minefield
-        .iter()
-        .enumerate()
-        .map(|(row_idx, row_content)| {
-            row_content
-                .chars()
-                .enumerate()
-                .map(|(col_idx, col_content)| {
-                    if col_content == '*' {
-                        '*'
-                    } else {
-                        let mut count = 0;
-
-                        for i in row_idx.saturating_sub(1)..=row_idx + 1 {
-                            for j in col_idx.saturating_sub(1)..=col_idx + 1 {
-                                if i < minefield.len()
-                                    && j < row_content.len()
-                                    && minefield[i].chars().nth(j) == Some('*')
-                                {
-                                    count += 1;
-                                }
-                            }
-                        }
+    annotate_iter(minefield).collect()
+}
 
-                        if count == 0 {
-                            ' '
-                        } else {
-                            (count as u8 + b'0') as char
-                        }
-                    }
+/// Annotates one row at a time, looking only at the rows next to it.
+pub fn annotate_iter<'a>(minefield: &'a [&'a str]) -> impl Iterator<Item = String> + 'a {
+    (0..minefield.len()).map(move |row_idx| {
+        let neighbours = &minefield[row_idx.saturating_sub(1)..(row_idx + 2).min(minefield.len())];
+        annotate_row(minefield[row_idx], neighbours)
+    })
+}
+
+/// Hands each annotated row to `sink` together with its index, in order.
+pub fn annotate_into(minefield: &[&str], sink: &mut impl FnMut(usize, String)) {
+    for (row_idx, row) in annotate_iter(minefield).enumerate() {
+        sink(row_idx, row);
+    }
+}
+
+/// Like `annotate`, but draws mines as `✱`, counts as `①` to `⑧` and
+/// squares with no mine around them as `·`. Gives an empty board unless
+/// every row has the same length.
+pub fn annotate_unicode(minefield: &[&str]) -> Vec<String> {
+    let width = minefield.first().map_or(0, |row| row.chars().count());
+
+    if minefield.iter().any(|row| row.chars().count() != width) {
+        return Vec::new();
+    }
+
+    (0..minefield.len())
+        .map(|row_idx| {
+            let neighbours =
+                &minefield[row_idx.saturating_sub(1)..(row_idx + 2).min(minefield.len())];
+            square_counts(minefield[row_idx], neighbours)
+                .map(|count| match count {
+                    None => '✱',
+                    Some(0) => '·',
+                    Some(count) => char::from_u32(0x2460 + count - 1).unwrap(),
                 })
                 .collect()
         })
         .collect()
 }
 
+/// Turns the square at `row`, `col` of a board made by `annotate` into a
+/// mine or back into an empty square, and fixes up the counts around it so
+/// the board reads as if `annotate` had been run again. Gives the squares
+/// that changed in row-major order, which is none if the square is off the
+/// board or already what was asked for.
+pub fn update_cell(
+    board: &mut [String],
+    row: usize,
+    col: usize,
+    make_mine: bool,
+) -> Vec<(usize, usize)> {
+    let is_mine = |board: &[String], row: usize, col: usize| {
+        board.get(row).and_then(|r| r.as_bytes().get(col)) == Some(&b'*')
+    };
+
+    let on_board = board.get(row).is_some_and(|r| col < r.len());
+
+    if !on_board || is_mine(board, row, col) == make_mine {
+        return Vec::new();
+    }
+
+    let flipped = if make_mine { "*" } else { " " };
+    board[row].replace_range(col..col + 1, flipped);
+
+    let mut changed = vec![(row, col)];
+
+    for i in row.saturating_sub(1)..(row + 2).min(board.len()) {
+        for j in col.saturating_sub(1)..(col + 2).min(board[i].len()) {
+            if is_mine(board, i, j) {
+                continue;
+            }
+
+            let mut count = 0;
+            for k in i.saturating_sub(1)..=i + 1 {
+                for l in j.saturating_sub(1)..(j + 2).min(board[i].len()) {
+                    if is_mine(board, k, l) {
+                        count += 1;
+                    }
+                }
+            }
+
+            let square = match count {
+                0 => ' ',
+                count => char::from_digit(count, 10).unwrap(),
+            };
+
+            if board[i].as_bytes()[j] != square as u8 {
+                board[i].replace_range(j..j + 1, square.encode_utf8(&mut [0; 4]));
+
+                if (i, j) != (row, col) {
+                    changed.push((i, j));
+                }
+            }
+        }
+    }
+
+    changed.sort_unstable();
+    changed
+}
+
+fn annotate_row(row_content: &str, neighbours: &[&str]) -> String {
+    square_counts(row_content, neighbours)
+        .map(|count| match count {
+            None => '*',
+            Some(0) => ' ',
+            Some(count) => char::from_digit(count, 10).unwrap(),
+        })
+        .collect()
+}
+
+/// The number of mines around each square of the row, or `None` for a mine.
+fn square_counts<'a>(
+    row_content: &'a str,
+    neighbours: &'a [&'a str],
+) -> impl Iterator<Item = Option<u32>> + 'a {
+    row_content
+        .chars()
+        .enumerate()
+        .map(move |(col_idx, col_content)| {
+            if col_content == '*' {
+                None
+            } else {
+                let mut count = 0;
+
+                for row in neighbours {
+                    for j in col_idx.saturating_sub(1)..=col_idx + 1 {
+                        if j < row_content.len() && row.chars().nth(j) == Some('*') {
+                            count += 1;
+                        }
+                    }
+                }
+
+                Some(count)
+            }
+        })
+}
+
 #[cfg(test)]
 fn remove_annotations(board: &[&str]) -> Vec<String> {
     board.iter().map(|r| remove_annotations_in_row(r)).collect()
@@ -56,6 +160,23 @@ fn run_test(test_case: &[&str]) {
     let cleaned_strs = cleaned.iter().map(|r| &r[..]).collect::<Vec<_>>();
     let expected = test_case.iter().map(|&r| r.to_string()).collect::<Vec<_>>();
     assert_eq!(expected, annotate(&cleaned_strs));
+    assert_eq!(expected, annotate_iter(&cleaned_strs).collect::<Vec<_>>());
+
+    let mut streamed = Vec::new();
+    annotate_into(&cleaned_strs, &mut |row_idx, row| {
+        assert_eq!(row_idx, streamed.len(), "rows must arrive in order");
+        streamed.push(row);
+    });
+    assert_eq!(expected, streamed);
+}
+
+#[test]
+fn annotate_iter_is_lazy() {
+    let minefield = ["*  ", " * ", "  *", "   "];
+    let mut rows = annotate_iter(&minefield);
+    assert_eq!(rows.next(), Some(String::from("*21")));
+    assert_eq!(rows.next(), Some(String::from("2*2")));
+    assert_eq!(rows.size_hint(), (2, Some(2)));
 }
 
 #[test]
@@ -177,3 +298,151 @@ fn large_board() {
         "111111",
     ]);
 }
+
+#[test]
+fn unicode_board() {
+    let minefield = ["*   ", "  * ", "    "];
+    assert_eq!(annotate_unicode(&minefield), ["✱②①①", "①②✱①", "·①①①"]);
+}
+
+#[test]
+fn unicode_board_covers_every_count() {
+    let minefield = ["***", "* *", "***", "   "];
+    assert_eq!(annotate_unicode(&minefield), ["✱✱✱", "✱⑧✱", "✱✱✱", "②③②"]);
+    assert_eq!(annotate_unicode(&["   ", " * "]), ["①①①", "①✱①"]);
+    assert_eq!(annotate_unicode(&["*", " ", " "]), ["✱", "①", "·"]);
+}
+
+#[test]
+fn unicode_board_matches_ascii_layout() {
+    let minefield = [" *  * ", "  *   ", "    * ", "   * *", " *  * ", "      "];
+    let ascii = annotate(&minefield);
+    let unicode = annotate_unicode(&minefield);
+
+    for (ascii, unicode) in ascii.iter().zip(&unicode) {
+        let converted: String = ascii
+            .chars()
+            .map(|ch| match ch {
+                '*' => '✱',
+                ' ' => '·',
+                digit => char::from_u32(0x245f + digit.to_digit(10).unwrap()).unwrap(),
+            })
+            .collect();
+        assert_eq!(&converted, unicode);
+    }
+}
+
+#[test]
+fn unicode_empty_boards() {
+    assert!(annotate_unicode(&[]).is_empty());
+    assert_eq!(annotate_unicode(&[""]), [""]);
+}
+
+#[test]
+fn unicode_ragged_board_is_rejected() {
+    assert!(annotate_unicode(&["   ", " * ", "  "]).is_empty());
+    assert!(annotate_unicode(&["*", "  "]).is_empty());
+}
+
+#[cfg(test)]
+use proptest::prelude::*;
+
+/// A 12 by 17 minefield, about one square in five a mine, and the toggles
+/// to make on it.
+#[cfg(test)]
+fn minefield_and_toggles() -> impl Strategy<Value = (Vec<String>, Vec<(usize, usize, bool)>)> {
+    let (height, width) = (12, 17);
+    let row = prop::collection::vec(prop::bool::weighted(0.2), width).prop_map(|mines| {
+        mines
+            .iter()
+            .map(|&mine| if mine { '*' } else { ' ' })
+            .collect()
+    });
+    let toggles = prop::collection::vec((0..height, 0..width, any::<bool>()), 500);
+
+    (prop::collection::vec(row, height), toggles)
+}
+
+#[cfg(test)]
+proptest! {
+    // A fixed seed, so every run toggles the same cells.
+    #![proptest_config(ProptestConfig {
+        cases: 16,
+        rng_seed: proptest::test_runner::RngSeed::Fixed(650),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn update_cell_matches_annotate((mut minefield, toggles) in minefield_and_toggles()) {
+        let mut board = annotate(&minefield.iter().map(String::as_str).collect::<Vec<_>>());
+
+        for (step, (row, col, make_mine)) in toggles.into_iter().enumerate() {
+            let before = board.clone();
+            let changed = update_cell(&mut board, row, col, make_mine);
+
+            minefield[row].replace_range(col..col + 1, if make_mine { "*" } else { " " });
+            let expected = annotate(&minefield.iter().map(String::as_str).collect::<Vec<_>>());
+            prop_assert_eq!(&expected, &board, "step {}, ({}, {})", step, row, col);
+
+            let mut differing = Vec::new();
+            for (i, (old, new)) in before.iter().zip(&board).enumerate() {
+                for (j, (a, b)) in old.bytes().zip(new.bytes()).enumerate() {
+                    if a != b {
+                        differing.push((i, j));
+                    }
+                }
+            }
+            prop_assert_eq!(differing, changed, "step {}, ({}, {})", step, row, col);
+        }
+    }
+}
+
+#[test]
+fn update_cell_reports_changed_squares() {
+    let mut board = vec![
+        String::from("111"),
+        String::from("1*1"),
+        String::from("111"),
+    ];
+
+    assert_eq!(
+        update_cell(&mut board, 1, 1, false),
+        [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2)
+        ]
+    );
+    assert_eq!(board, ["   ", "   ", "   "]);
+
+    assert_eq!(
+        update_cell(&mut board, 0, 0, true),
+        [(0, 0), (0, 1), (1, 0), (1, 1)]
+    );
+    assert_eq!(board, ["*1 ", "11 ", "   "]);
+}
+
+#[test]
+fn update_cell_to_the_same_state_is_a_no_op() {
+    let mut board = vec![String::from("*1"), String::from("11")];
+
+    assert!(update_cell(&mut board, 0, 0, true).is_empty());
+    assert!(update_cell(&mut board, 1, 1, false).is_empty());
+    assert_eq!(board, ["*1", "11"]);
+}
+
+#[test]
+fn update_cell_off_the_board_changes_nothing() {
+    let mut board = vec![String::from("*1"), String::from("11")];
+
+    assert!(update_cell(&mut board, 2, 0, true).is_empty());
+    assert!(update_cell(&mut board, 0, 2, true).is_empty());
+    assert!(update_cell(&mut [], 0, 0, true).is_empty());
+    assert_eq!(board, ["*1", "11"]);
+}
@@ -1,21 +1,398 @@
-use num_bigint::BigInt;
-use std::ops::{Add, Mul, Sub};
+use num_bigint::{BigInt, Sign};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Mul, Rem, Sub};
+
+/// The longest fractional part `Decimal::try_from` accepts, in digits.
+pub const DEFAULT_MAX_FRACTION_DIGITS: usize = 100_000;
 
 /// Type implementing arbitrary-precision decimal arithmetic
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Decimal {
     number: BigInt,
     decimal_pow: BigInt,
 }
 
+/// Why a string could not be parsed as a `Decimal`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    Invalid,
+    FractionTooLong { digits: usize, max: usize },
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDecimalError::Invalid => write!(f, "invalid decimal literal"),
+            ParseDecimalError::FractionTooLong { digits, max } => write!(
+                f,
+                "fractional part has {digits} digits, more than the maximum of {max}"
+            ),
+        }
+    }
+}
+
 impl Decimal {
     pub fn try_from(input: &str) -> Option<Decimal> {
+        Self::parse(input).ok()
+    }
+
+    /// Parse a decimal, rejecting fractional parts longer than
+    /// `DEFAULT_MAX_FRACTION_DIGITS`.
+    pub fn parse(input: &str) -> Result<Decimal, ParseDecimalError> {
+        Self::parse_with_max_fraction_digits(input, DEFAULT_MAX_FRACTION_DIGITS)
+    }
+
+    /// Parse a decimal with at most `max` fractional digits.
+    ///
+    /// The length is checked before anything is allocated, so an oversized
+    /// input is rejected in time proportional to its length.
+    pub fn parse_with_max_fraction_digits(
+        input: &str,
+        max: usize,
+    ) -> Result<Decimal, ParseDecimalError> {
         let parts: Vec<&str> = input.split(".").collect();
+        let digits = parts.get(1).unwrap_or(&"").len();
+
+        if digits > max {
+            return Err(ParseDecimalError::FractionTooLong { digits, max });
+        }
+
+        // `BigInt::pow` takes a `u32`, which bounds the scale whatever `max` is.
+        let Ok(scale) = u32::try_from(digits) else {
+            return Err(ParseDecimalError::FractionTooLong {
+                digits,
+                max: u32::MAX as usize,
+            });
+        };
+
+        Ok(Self {
+            number: BigInt::parse_bytes(parts.join("").as_bytes(), 10)
+                .ok_or(ParseDecimalError::Invalid)?,
+            decimal_pow: BigInt::from(10).pow(scale),
+        })
+    }
+
+    /// Build `integer + fractional / 10^scale`, with the fractional part
+    /// taking the sign of `integer`, so `(-1, 5, 1)` is `-1.5`.
+    ///
+    /// Since the sign comes from `integer`, values between -1 and 0 can't be
+    /// built this way: `(0, 5, 1)` is `0.5`. Subtract from `Decimal::zero()`
+    /// to get `-0.5`.
+    ///
+    /// Panics unless `fractional < 10^scale`; see `try_from_parts`.
+    pub fn from_parts(integer: i64, fractional: u64, scale: u32) -> Decimal {
+        Self::try_from_parts(integer, fractional, scale)
+            .expect("fractional part must have fewer than `scale` digits")
+    }
+
+    /// Like `from_parts`, but returns `None` unless `fractional < 10^scale`.
+    pub fn try_from_parts(integer: i64, fractional: u64, scale: u32) -> Option<Decimal> {
+        let decimal_pow = BigInt::from(10).pow(scale);
+        let fractional = BigInt::from(fractional);
+
+        if fractional >= decimal_pow {
+            return None;
+        }
+
+        let integer = BigInt::from(integer) * &decimal_pow;
+        let number = if integer.sign() == Sign::Minus {
+            integer - fractional
+        } else {
+            integer + fractional
+        };
+
         Some(Self {
-            number: BigInt::parse_bytes(parts.join("").as_bytes(), 10)?,
-            decimal_pow: BigInt::from(10).pow(parts.get(1).unwrap_or(&"").len() as u32),
+            number,
+            decimal_pow,
         })
     }
+
+    /// Convert a float using its shortest round-trip representation,
+    /// so `0.1` becomes exactly `0.1` rather than its binary expansion.
+    ///
+    /// Returns `None` for `NaN` and infinities.
+    pub fn from_f64(f: f64) -> Option<Decimal> {
+        if !f.is_finite() {
+            return None;
+        }
+
+        Self::try_from(&format!("{f}"))
+    }
+
+    /// Convert a float by printing exactly `digits` fractional digits,
+    /// which exposes the binary approximation stored in the float.
+    ///
+    /// Returns `None` for `NaN` and infinities.
+    pub fn from_f64_with_precision(f: f64, digits: usize) -> Option<Decimal> {
+        if !f.is_finite() {
+            return None;
+        }
+
+        let formatted = format!("{f:.digits$}");
+        let trimmed = if formatted.contains('.') {
+            formatted.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &formatted
+        };
+
+        Self::try_from(trimmed)
+    }
+
+    /// Convert to the nearest float, or `None` if the value is out of range.
+    pub fn to_f64(&self) -> Option<f64> {
+        let value = format!("{}e-{}", self.number, self.scale())
+            .parse::<f64>()
+            .ok()?;
+
+        if value.is_finite() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    pub fn zero() -> Decimal {
+        Self {
+            number: BigInt::from(0),
+            decimal_pow: BigInt::from(1),
+        }
+    }
+
+    /// Restrict the value to `min..=max`.
+    ///
+    /// Panics if `min` is greater than `max`.
+    pub fn clamp(self, min: Decimal, max: Decimal) -> Decimal {
+        assert!(min <= max, "clamp called with min greater than max");
+
+        if self < min {
+            min
+        } else if self > max {
+            max
+        } else {
+            self
+        }
+    }
+
+    /// The larger of the two, or `a` if they are equal.
+    pub fn max(a: Decimal, b: Decimal) -> Decimal {
+        if b > a {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// The smaller of the two, or `a` if they are equal.
+    pub fn min(a: Decimal, b: Decimal) -> Decimal {
+        if b < a {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// Format as `d.ddde±x` with exactly one non-zero digit before the point.
+    pub fn to_scientific_notation(&self) -> String {
+        self.to_exponent_notation(1)
+    }
+
+    /// Format like `to_scientific_notation` but with the exponent always
+    /// a multiple of three, leaving one to three digits before the point.
+    pub fn to_engineering_notation(&self) -> String {
+        self.to_exponent_notation(3)
+    }
+
+    /// Round to at most `max_scale` fractional digits, with ties rounded away
+    /// from zero.
+    ///
+    /// Panics if the value has more than `u32::MAX` fractional digits.
+    pub fn round_to_scale(&self, max_scale: u32) -> Decimal {
+        self.try_round_to_scale(max_scale)
+            .expect("scale must fit in a u32")
+    }
+
+    // `round_to_scale`, or `None` if the current scale doesn't fit in a `u32`.
+    fn try_round_to_scale(&self, max_scale: u32) -> Option<Decimal> {
+        let scale = u32::try_from(self.scale()).ok()?;
+
+        if scale <= max_scale {
+            return Some(self.clone());
+        }
+
+        let divisor = BigInt::from(10).pow(scale - max_scale);
+        let mut number = &self.number / &divisor;
+        let remainder = &self.number % &divisor;
+
+        if remainder.magnitude() * 2u32 >= *divisor.magnitude() {
+            number += match self.number.sign() {
+                Sign::Minus => -1,
+                _ => 1,
+            };
+        }
+
+        Some(Self {
+            number,
+            decimal_pow: BigInt::from(10).pow(max_scale),
+        })
+    }
+
+    /// Add and round the sum to at most `max_scale` fractional digits, so a
+    /// long chain of operations can't grow without bound.
+    ///
+    /// A result too small to show at that scale rounds to zero like any other.
+    ///
+    /// The arithmetic itself can't overflow, so "checked" refers only to the
+    /// scale: the result is `None` if `max_scale` is above
+    /// `DEFAULT_MAX_FRACTION_DIGITS`, since `parse` couldn't read such a
+    /// result back, or if the exact result has more than `u32::MAX`
+    /// fractional digits. Otherwise it is always `Some`.
+    pub fn checked_add(&self, rhs: &Self, max_scale: u32) -> Option<Decimal> {
+        Self::rounded(self.clone() + rhs.clone(), max_scale)
+    }
+
+    /// Subtract like `checked_add`, with `None` in the same cases.
+    pub fn checked_sub(&self, rhs: &Self, max_scale: u32) -> Option<Decimal> {
+        Self::rounded(self.clone() - rhs.clone(), max_scale)
+    }
+
+    /// Multiply like `checked_add`, with `None` in the same cases.
+    pub fn checked_mul(&self, rhs: &Self, max_scale: u32) -> Option<Decimal> {
+        Self::rounded(self.clone() * rhs.clone(), max_scale)
+    }
+
+    /// The square root truncated to `precision` fractional digits, or `None`
+    /// for a negative value.
+    ///
+    /// Newton's method runs on the exact integer `self * 10^(2 * precision)`,
+    /// so no floating point error can creep into the digits.
+    pub fn sqrt(&self, precision: u32) -> Option<Decimal> {
+        if self.number.sign() == Sign::Minus {
+            return None;
+        }
+
+        let target = &self.number * BigInt::from(10).pow(2 * precision) / &self.decimal_pow;
+
+        Some(Self {
+            number: Self::integer_sqrt(&target),
+            decimal_pow: BigInt::from(10).pow(precision),
+        })
+    }
+
+    // The largest `x` with `x * x <= n`, for a non-negative `n`. Starting
+    // above the root, each Newton step moves down towards it until a step
+    // would no longer decrease.
+    fn integer_sqrt(n: &BigInt) -> BigInt {
+        if n.sign() == Sign::NoSign {
+            return BigInt::from(0);
+        }
+
+        let mut x = BigInt::from(1) << n.bits().div_ceil(2);
+
+        loop {
+            let next = (&x + n / &x) >> 1;
+
+            if next >= x {
+                return x;
+            }
+
+            x = next;
+        }
+    }
+
+    fn rounded(exact: Decimal, max_scale: u32) -> Option<Decimal> {
+        if max_scale as usize > DEFAULT_MAX_FRACTION_DIGITS {
+            return None;
+        }
+
+        exact.try_round_to_scale(max_scale)
+    }
+
+    // The value as a fraction in lowest terms, the same for every
+    // representation of it: `0.50` and `0.5` both give `(1, 2)`.
+    fn reduced(&self) -> (BigInt, BigInt) {
+        let (mut a, mut b) = (self.number.clone(), self.decimal_pow.clone());
+        if a.sign() == Sign::Minus {
+            a = -a;
+        }
+
+        while b.sign() != Sign::NoSign {
+            let r = a % &b;
+            a = b;
+            b = r;
+        }
+
+        (&self.number / &a, &self.decimal_pow / &a)
+    }
+
+    // Number of fractional digits, i.e. the exponent of `decimal_pow`.
+    fn scale(&self) -> usize {
+        self.decimal_pow.to_string().len() - 1
+    }
+
+    // Shared implementation of the exponent notations, where the printed
+    // exponent is always a multiple of `step`.
+    fn to_exponent_notation(&self, step: i64) -> String {
+        let sign = if self.number < BigInt::from(0) {
+            "-"
+        } else {
+            ""
+        };
+        let digits = self.number.magnitude().to_string();
+        let significant = digits.trim_end_matches('0');
+
+        if significant.is_empty() {
+            return String::from("0e+0");
+        }
+
+        let trailing_zeros = (digits.len() - significant.len()) as i64;
+        let exponent = significant.len() as i64 - 1 + trailing_zeros - self.scale() as i64;
+        let shown_exponent = exponent - exponent.rem_euclid(step);
+        let integer_len = (exponent - shown_exponent + 1) as usize;
+
+        let mut coefficient = String::from(significant);
+        while coefficient.len() < integer_len {
+            coefficient.push('0');
+        }
+
+        let (integer, fraction) = coefficient.split_at(integer_len);
+        let exponent_sign = if shown_exponent < 0 { '-' } else { '+' };
+
+        if fraction.is_empty() {
+            format!("{sign}{integer}e{exponent_sign}{}", shown_exponent.abs())
+        } else {
+            format!(
+                "{sign}{integer}.{fraction}e{exponent_sign}{}",
+                shown_exponent.abs()
+            )
+        }
+    }
+}
+
+/// Print every digit of the current scale, so trailing zeros such as the
+/// one in `1.50` are kept.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scale = self.scale();
+        let mut digits = self.number.magnitude().to_string();
+
+        while digits.len() <= scale {
+            digits.insert(0, '0');
+        }
+
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        let sign = if self.number.sign() == Sign::Minus {
+            "-"
+        } else {
+            ""
+        };
+
+        if fraction.is_empty() {
+            write!(f, "{sign}{integer}")
+        } else {
+            write!(f, "{sign}{integer}.{fraction}")
+        }
+    }
 }
 
 impl PartialEq for Decimal {
@@ -25,6 +402,16 @@ impl PartialEq for Decimal {
     }
 }
 
+impl Eq for Decimal {}
+
+/// Hashes the value rather than its representation, so decimals that
+/// compare equal hash the same.
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.reduced().hash(state);
+    }
+}
+
 impl PartialOrd for Decimal {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         (self.number.clone() * other.decimal_pow.clone())
@@ -64,6 +451,26 @@ impl Mul for Decimal {
     }
 }
 
+/// Truncated remainder like the integer `%`, so the result takes the sign
+/// of `self`: `-7 % 3 == -1`.
+///
+/// Panics if `rhs` is zero.
+impl Rem for Decimal {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        assert!(
+            rhs.number.sign() != Sign::NoSign,
+            "attempt to calculate the remainder with a divisor of zero"
+        );
+
+        Self {
+            number: (self.number * rhs.decimal_pow.clone())
+                % (rhs.number * self.decimal_pow.clone()),
+            decimal_pow: (self.decimal_pow * rhs.decimal_pow),
+        }
+    }
+}
+
 /// Create a Decimal from a string literal
 ///
 /// Use only when you _know_ that your value is valid.
@@ -348,3 +755,379 @@ fn test_add_away_decimal() {
 fn test_sub_away_decimal() {
     assert_eq!(decimal("1.1") - decimal("0.1"), decimal("1.0"))
 }
+
+// test conversion from and to floats
+#[test]
+fn test_from_f64_round_trip() {
+    let value = Decimal::from_f64(12.34).unwrap();
+    assert_eq!(value, decimal("12.34"));
+    assert!((value.to_f64().unwrap() - 12.34).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_from_f64_negative_and_integral() {
+    assert_eq!(Decimal::from_f64(-2.5), Some(decimal("-2.5")));
+    assert_eq!(Decimal::from_f64(42.0), Some(decimal("42")));
+    assert_eq!(Decimal::from_f64(0.0), Some(decimal("0")));
+}
+
+#[test]
+fn test_from_f64_non_finite() {
+    assert_eq!(Decimal::from_f64(f64::NAN), None);
+    assert_eq!(Decimal::from_f64(f64::INFINITY), None);
+    assert_eq!(Decimal::from_f64(f64::NEG_INFINITY), None);
+    assert_eq!(Decimal::from_f64_with_precision(f64::NAN, 10), None);
+}
+
+#[test]
+fn test_from_f64_add_is_exact() {
+    let sum = Decimal::from_f64(0.1).unwrap() + Decimal::from_f64(0.2).unwrap();
+    assert_eq!(sum, decimal("0.3"));
+}
+
+#[test]
+fn test_from_f64_with_precision() {
+    assert_eq!(
+        Decimal::from_f64_with_precision(0.1, 20),
+        Some(decimal("0.10000000000000000555"))
+    );
+    assert_eq!(
+        Decimal::from_f64_with_precision(1.5, 3),
+        Some(decimal("1.5"))
+    );
+    assert_eq!(Decimal::from_f64_with_precision(2.0, 0), Some(decimal("2")));
+}
+
+// test exponent notations
+#[test]
+fn test_to_scientific_notation() {
+    assert_eq!(
+        decimal("12345.6789").to_scientific_notation(),
+        "1.23456789e+4"
+    );
+    assert_eq!(decimal("0.0042").to_scientific_notation(), "4.2e-3");
+    assert_eq!(
+        decimal("-0.0000000000035").to_scientific_notation(),
+        "-3.5e-12"
+    );
+    assert_eq!(decimal("123400").to_scientific_notation(), "1.234e+5");
+    assert_eq!(decimal("7").to_scientific_notation(), "7e+0");
+    assert_eq!(decimal("1.000").to_scientific_notation(), "1e+0");
+}
+
+#[test]
+fn test_to_scientific_notation_zero() {
+    assert_eq!(Decimal::zero().to_scientific_notation(), "0e+0");
+    assert_eq!(decimal("-0.000").to_scientific_notation(), "0e+0");
+    assert_eq!(Decimal::zero().to_engineering_notation(), "0e+0");
+}
+
+#[test]
+fn test_to_engineering_notation() {
+    assert_eq!(
+        decimal("12345.6789").to_engineering_notation(),
+        "12.3456789e+3"
+    );
+    assert_eq!(decimal("0.0042").to_engineering_notation(), "4.2e-3");
+    assert_eq!(decimal("0.00042").to_engineering_notation(), "420e-6");
+    assert_eq!(decimal("-50000").to_engineering_notation(), "-50e+3");
+    assert_eq!(decimal("999").to_engineering_notation(), "999e+0");
+}
+
+// test input limits and bounded arithmetic
+#[test]
+fn test_huge_fraction_is_rejected() {
+    let input = format!("0.{}", "1".repeat(10_000_000));
+    assert_eq!(
+        Decimal::parse(&input).unwrap_err(),
+        ParseDecimalError::FractionTooLong {
+            digits: 10_000_000,
+            max: DEFAULT_MAX_FRACTION_DIGITS
+        }
+    );
+    assert!(Decimal::try_from(&input).is_none());
+}
+
+#[test]
+fn test_fraction_limit_is_configurable() {
+    assert_eq!(
+        Decimal::parse_with_max_fraction_digits("1.234", 2).unwrap_err(),
+        ParseDecimalError::FractionTooLong { digits: 3, max: 2 }
+    );
+    assert_eq!(
+        Decimal::parse_with_max_fraction_digits("1.23", 2),
+        Ok(decimal("1.23"))
+    );
+    assert_eq!(Decimal::parse("1.x"), Err(ParseDecimalError::Invalid));
+}
+
+#[test]
+fn test_round_to_scale() {
+    assert_eq!(decimal("1.2345").round_to_scale(2), decimal("1.23"));
+    assert_eq!(decimal("1.235").round_to_scale(2), decimal("1.24"));
+    assert_eq!(decimal("-1.235").round_to_scale(2), decimal("-1.24"));
+    assert_eq!(decimal("-1.234").round_to_scale(2), decimal("-1.23"));
+    assert_eq!(decimal("9.99").round_to_scale(1), decimal("10"));
+    assert_eq!(decimal("2.5").round_to_scale(0), decimal("3"));
+    assert_eq!(decimal("1.5").round_to_scale(3), decimal("1.5"));
+}
+
+#[test]
+fn test_checked_ops_round_to_max_scale() {
+    assert_eq!(
+        decimal("0.125").checked_add(&decimal("0.0001"), 2),
+        Some(decimal("0.13"))
+    );
+    assert_eq!(
+        decimal("1").checked_sub(&decimal("0.333"), 1),
+        Some(decimal("0.7"))
+    );
+    assert_eq!(
+        decimal("1.05").checked_mul(&decimal("1.05"), 2),
+        Some(decimal("1.10"))
+    );
+    assert_eq!(
+        decimal("0.0001").checked_mul(&decimal("0.0001"), 4),
+        Some(decimal("0.0000"))
+    );
+    assert_eq!(
+        decimal("-0.00004").checked_add(&decimal("0"), 4),
+        Some(Decimal::zero())
+    );
+    assert_eq!(
+        decimal("0.5").checked_sub(&decimal("0.5"), 0),
+        Some(Decimal::zero())
+    );
+}
+
+#[test]
+fn test_checked_ops_reject_scale_beyond_parse_limit() {
+    let max_scale = DEFAULT_MAX_FRACTION_DIGITS as u32;
+    assert_eq!(
+        decimal("1").checked_add(&decimal("0.5"), max_scale),
+        Some(decimal("1.5"))
+    );
+    assert_eq!(
+        decimal("1").checked_add(&decimal("0.5"), max_scale + 1),
+        None
+    );
+    assert_eq!(decimal("1").checked_mul(&decimal("0.5"), u32::MAX), None);
+}
+
+#[test]
+fn test_checked_chain_does_not_grow() {
+    let rate = decimal("1.0123");
+    let mut value = decimal("1000");
+    for _ in 0..50 {
+        value = value.checked_mul(&rate, 4).unwrap();
+        assert!(value.scale() <= 4);
+    }
+    assert_eq!(value, decimal("1842.7305"));
+}
+
+#[test]
+fn test_clamp() {
+    assert_eq!(
+        decimal("5").clamp(decimal("1"), decimal("10")),
+        decimal("5")
+    );
+    assert_eq!(
+        decimal("0").clamp(decimal("1"), decimal("10")),
+        decimal("1")
+    );
+    assert_eq!(
+        decimal("15").clamp(decimal("1"), decimal("10")),
+        decimal("10")
+    );
+    assert_eq!(
+        decimal("-0.5").clamp(decimal("-0.25"), decimal("0.25")),
+        decimal("-0.25")
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_clamp_with_inverted_bounds() {
+    decimal("5").clamp(decimal("10"), decimal("1"));
+}
+
+#[test]
+fn test_max_and_min() {
+    assert_eq!(
+        Decimal::max(decimal("1.5"), decimal("1.25")),
+        decimal("1.5")
+    );
+    assert_eq!(
+        Decimal::min(decimal("1.5"), decimal("1.25")),
+        decimal("1.25")
+    );
+    assert_eq!(
+        Decimal::max(decimal(BIGS[0]), decimal(BIGS[1])),
+        decimal(BIGS[1])
+    );
+    assert_eq!(
+        Decimal::min(decimal(BIGS[0]), decimal(BIGS[1])),
+        decimal(BIGS[0])
+    );
+}
+
+#[test]
+fn test_from_parts() {
+    assert_eq!(Decimal::from_parts(3, 14, 2), decimal("3.14"));
+    assert_eq!(Decimal::from_parts(-1, 5, 1), decimal("-1.5"));
+    assert_eq!(Decimal::from_parts(0, 1, 3), decimal("0.001"));
+    assert_eq!(Decimal::from_parts(7, 0, 0), decimal("7"));
+    assert_eq!(
+        Decimal::from_parts(i64::MIN, 99, 2),
+        decimal("-9223372036854775808.99")
+    );
+}
+
+#[test]
+fn test_from_parts_between_minus_one_and_zero() {
+    assert_eq!(Decimal::from_parts(0, 5, 1), decimal("0.5"));
+    assert_eq!(
+        Decimal::zero() - Decimal::from_parts(0, 5, 1),
+        decimal("-0.5")
+    );
+}
+
+#[test]
+fn test_try_from_parts_rejects_long_fractions() {
+    assert_eq!(Decimal::try_from_parts(1, 10, 1), None);
+    assert_eq!(Decimal::try_from_parts(1, 1, 0), None);
+    assert_eq!(Decimal::try_from_parts(1, 9, 1), Some(decimal("1.9")));
+}
+
+#[test]
+#[should_panic]
+fn test_from_parts_panics_on_long_fraction() {
+    Decimal::from_parts(0, 100, 2);
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(decimal("1.50").to_string(), "1.50");
+    assert_eq!(decimal("-0.001").to_string(), "-0.001");
+    assert_eq!(decimal("42").to_string(), "42");
+    assert_eq!(decimal("-0.0").to_string(), "0.0");
+    assert_eq!((decimal("1.5") + decimal("1.5")).to_string(), "3.00");
+    assert_eq!(Decimal::zero().to_string(), "0");
+}
+
+#[test]
+fn test_rem() {
+    assert_eq!(decimal("10") % decimal("3"), decimal("1"));
+    assert_eq!(decimal("1.5") % decimal("0.4"), decimal("0.3"));
+    assert_eq!(decimal("-7") % decimal("3"), decimal("-1"));
+    assert_eq!(decimal("7") % decimal("-3"), decimal("1"));
+    assert_eq!(decimal("12.34") % decimal("1"), decimal("0.34"));
+    assert_eq!(decimal("0.3") % decimal("1.5"), decimal("0.3"));
+}
+
+#[test]
+#[should_panic]
+fn test_rem_by_zero() {
+    let _ = decimal("1") % decimal("0.00");
+}
+
+#[cfg(test)]
+fn hash_of(value: &Decimal) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_hash_agrees_with_eq() {
+    let half = [
+        decimal("0.5"),
+        decimal("0.50000"),
+        decimal("1.0") - decimal("0.5"),
+        decimal("0.25") + decimal("0.25"),
+        decimal("1.0") * decimal("0.5"),
+    ];
+
+    for value in &half {
+        assert_eq!(&half[0], value);
+        assert_eq!(hash_of(&half[0]), hash_of(value), "{value}");
+    }
+
+    assert_eq!(hash_of(&decimal("0")), hash_of(&decimal("-0.000")));
+    assert_eq!(hash_of(&decimal("-2.50")), hash_of(&decimal("-2.5")));
+    assert_eq!(
+        hash_of(&decimal(BIGS[2])),
+        hash_of(&(decimal(BIGS[0]) + decimal(BIGS[1])))
+    );
+}
+
+#[test]
+fn test_hash_distinguishes_values() {
+    let values = [
+        "0", "0.5", "-0.5", "5", "0.05", "1", "1.01", BIGS[0], BIGS[1],
+    ];
+
+    for (i, a) in values.iter().enumerate() {
+        for b in &values[i + 1..] {
+            assert_ne!(hash_of(&decimal(a)), hash_of(&decimal(b)), "{a} and {b}");
+        }
+    }
+}
+
+#[test]
+fn test_decimal_as_hash_key() {
+    use std::collections::{HashMap, HashSet};
+
+    let set: HashSet<Decimal> = ["1.0", "1", "1.00", "2", "0.10", "0.1"]
+        .iter()
+        .map(|value| decimal(value))
+        .collect();
+    assert_eq!(3, set.len());
+    assert!(set.contains(&(decimal("0.5") + decimal("0.5"))));
+
+    let mut prices = HashMap::new();
+    prices.insert(decimal("9.90"), "apple");
+    assert_eq!(Some(&"apple"), prices.get(&decimal("9.9")));
+    assert_eq!(None, prices.get(&decimal("9.09")));
+}
+
+#[test]
+fn test_sqrt() {
+    assert_eq!(decimal("4").sqrt(10), Some(decimal("2")));
+    assert_eq!(decimal("0").sqrt(5), Some(decimal("0")));
+    assert_eq!(decimal("-1").sqrt(5), None);
+    assert_eq!(decimal("-0.00").sqrt(5), Some(decimal("0")));
+    assert_eq!(decimal("0.25").sqrt(1), Some(decimal("0.5")));
+    assert_eq!(decimal("1.44").sqrt(4), Some(decimal("1.2")));
+    assert_eq!(decimal("10").sqrt(0), Some(decimal("3")));
+    assert_eq!(decimal("0.0001").sqrt(3), Some(decimal("0.010")));
+}
+
+#[test]
+fn test_sqrt_of_two() {
+    let root = decimal("2").sqrt(20).unwrap();
+    let known = decimal("1.41421356237309504880");
+    let error = if root > known {
+        root.clone() - known
+    } else {
+        known - root.clone()
+    };
+    assert!(error <= decimal("0.00000000000000000001"), "{root}");
+    assert_eq!(root.to_string(), "1.41421356237309504880");
+}
+
+#[test]
+fn test_sqrt_is_truncated_at_precision() {
+    for (input, precision) in [("2", 30), ("3", 15), ("0.5", 12), (BIGS[0], 40)] {
+        let value = decimal(input);
+        let root = value.sqrt(precision).unwrap();
+        let ulp = Decimal::from_parts(0, 1, precision);
+        assert!(root.clone() * root.clone() <= value, "{input}");
+        assert!(
+            (root.clone() + ulp.clone()) * (root + ulp) > value,
+            "{input}"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use prob2::Decimal;
+use proptest::prelude::*;
+use proptest::test_runner::RngSeed;
+
+/// An exact value `number / 10^scale`, computed without `Decimal` so the
+/// two can be checked against each other.
+#[derive(Clone)]
+struct Reference {
+    number: BigInt,
+    scale: u32,
+}
+
+impl Reference {
+    fn parse(input: &str) -> Self {
+        let (negative, unsigned) = match input.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, input),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let digits = format!("{integer}{fraction}");
+        let number = BigInt::parse_bytes(digits.as_bytes(), 10).unwrap();
+
+        Self {
+            number: if negative { -number } else { number },
+            scale: fraction.len() as u32,
+        }
+    }
+
+    fn rescaled(&self, scale: u32) -> BigInt {
+        &self.number * BigInt::from(10).pow(scale - self.scale)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let scale = self.scale.max(other.scale);
+
+        Self {
+            number: self.rescaled(scale) + other.rescaled(scale),
+            scale,
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let scale = self.scale.max(other.scale);
+
+        Self {
+            number: self.rescaled(scale) - other.rescaled(scale),
+            scale,
+        }
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Self {
+            number: &self.number * &other.number,
+            scale: self.scale + other.scale,
+        }
+    }
+
+    fn rem(&self, other: &Self) -> Self {
+        let scale = self.scale.max(other.scale);
+
+        Self {
+            number: self.rescaled(scale) % other.rescaled(scale),
+            scale,
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+
+        self.rescaled(scale).cmp(&other.rescaled(scale))
+    }
+
+    fn to_decimal(&self) -> Decimal {
+        let negative = self.number < BigInt::from(0);
+        let digits = format!(
+            "{:0>width$}",
+            self.number.magnitude().to_string(),
+            width = self.scale as usize + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        let sign = if negative { "-" } else { "" };
+
+        Decimal::try_from(&format!("{sign}{integer}.{fraction}")).unwrap()
+    }
+}
+
+fn decimal(input: &str) -> Decimal {
+    Decimal::try_from(input).unwrap_or_else(|| panic!("{input} should parse"))
+}
+
+/// A decimal literal with up to 12 digits on each side of the point,
+/// which may be negative, have leading zeros or have no point at all.
+fn literal() -> impl Strategy<Value = String> {
+    "-?[0-9]{1,12}(\\.[0-9]{1,12})?"
+}
+
+proptest! {
+    // A fixed seed, so a failure shows up on every run and not just once.
+    #![proptest_config(ProptestConfig {
+        cases: 2_000,
+        rng_seed: RngSeed::Fixed(631),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn parse_display_parse_is_stable(input in literal()) {
+        let parsed = decimal(&input);
+        let shown = parsed.to_string();
+        let reparsed = decimal(&shown);
+
+        prop_assert_eq!(&parsed, &reparsed, "{} shown as {}", input, shown);
+        prop_assert_eq!(shown, reparsed.to_string());
+    }
+
+    #[test]
+    fn add_and_mul_commute(a in literal(), b in literal()) {
+        let (a, b) = (decimal(&a), decimal(&b));
+
+        prop_assert_eq!(a.clone() + b.clone(), b.clone() + a.clone());
+        prop_assert_eq!(a.clone() * b.clone(), b * a);
+    }
+
+    #[test]
+    fn add_and_mul_associate(a in literal(), b in literal(), c in literal()) {
+        let (a, b, c) = (decimal(&a), decimal(&b), decimal(&c));
+
+        prop_assert_eq!(
+            (a.clone() + b.clone()) + c.clone(),
+            a.clone() + (b.clone() + c.clone())
+        );
+        prop_assert_eq!((a.clone() * b.clone()) * c.clone(), a * (b * c));
+    }
+
+    #[test]
+    fn subtracting_itself_gives_zero(a in literal()) {
+        let a = decimal(&a);
+
+        prop_assert_eq!(a.clone() - a, Decimal::zero());
+    }
+
+    #[test]
+    fn ordering_is_consistent(x in literal(), y in literal()) {
+        let (a, b) = (decimal(&x), decimal(&y));
+        let expected = Reference::parse(&x).cmp(&Reference::parse(&y));
+
+        prop_assert_eq!(a < b, b > a);
+        prop_assert!(!(a < b && a > b));
+        prop_assert_eq!(a.partial_cmp(&b), Some(expected));
+        prop_assert_eq!(a == b, expected == Ordering::Equal);
+    }
+
+    #[test]
+    fn arithmetic_matches_the_reference(x in literal(), y in literal()) {
+        let (a, b) = (decimal(&x), decimal(&y));
+        let (x, y) = (Reference::parse(&x), Reference::parse(&y));
+
+        prop_assert_eq!(a.clone() + b.clone(), x.add(&y).to_decimal(), "sum");
+        prop_assert_eq!(a.clone() - b.clone(), x.sub(&y).to_decimal(), "difference");
+        prop_assert_eq!(a.clone() * b.clone(), x.mul(&y).to_decimal(), "product");
+
+        if b != Decimal::zero() {
+            prop_assert_eq!(a % b, x.rem(&y).to_decimal(), "remainder");
+        }
+    }
+}
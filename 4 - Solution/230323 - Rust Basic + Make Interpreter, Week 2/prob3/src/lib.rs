@@ -1,10 +1,22 @@
+use std::mem::{self, MaybeUninit};
+use std::{ptr, slice};
+
+#[cfg(test)]
+use std::collections::VecDeque;
 #[cfg(test)]
 use std::rc::Rc;
 
+mod spsc;
+
+pub use spsc::{Consumer, Producer, SpscBuffer};
+
 pub struct CircularBuffer<T> {
-    data: Vec<Option<T>>,
+    // Exactly the `len` slots starting at `read_index`, wrapping around the
+    // end, are initialized.
+    data: Vec<MaybeUninit<T>>,
     read_index: usize,
     write_index: usize,
+    len: usize,
 }
 
 #[derive(Debug, PartialEq)]
@@ -16,12 +28,35 @@ pub enum Error {
 impl<T> CircularBuffer<T> {
     pub fn new(capacity: usize) -> Self {
         Self {
-            data: (0..capacity).map(|_| None).collect(),
+            data: (0..capacity).map(|_| MaybeUninit::uninit()).collect(),
+            read_index: 0,
+            write_index: 0,
+            len: 0,
+        }
+    }
+
+    /// A full buffer holding the elements of `v`, oldest first, with exactly
+    /// room for them. The vector's allocation becomes the buffer's storage.
+    pub fn from_vec(v: Vec<T>) -> Self {
+        let mut v = mem::ManuallyDrop::new(v);
+        let (pointer, len, capacity) = (v.as_mut_ptr(), v.len(), v.capacity());
+
+        // `MaybeUninit<T>` has the same layout as `T`, and every slot is
+        // initialized.
+        let data = unsafe { Vec::from_raw_parts(pointer.cast(), len, capacity) };
+
+        Self {
+            data,
             read_index: 0,
             write_index: 0,
+            len,
         }
     }
 
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
     pub fn write(&mut self, element: T) -> Result<(), Error> {
         if self.is_full() {
             return Err(Error::FullBuffer);
@@ -31,42 +66,346 @@ impl<T> CircularBuffer<T> {
         Ok(())
     }
 
+    /// Write as many of `items` as there is room for, in order, and return how
+    /// many were written. Fails with `FullBuffer` only if a non-empty slice
+    /// could not be written at all.
+    pub fn write_slice(&mut self, items: &[T]) -> Result<usize, Error>
+    where
+        T: Clone,
+    {
+        self.write_slice_with(items, Self::fill_from)
+    }
+
+    /// Write all of `items` with `overwrite` semantics, so at most the last
+    /// `capacity` of them remain afterwards.
+    pub fn overwrite_slice(&mut self, items: &[T])
+    where
+        T: Clone,
+    {
+        self.overwrite_slice_with(items, Self::fill_from);
+    }
+
+    /// `write_slice` for `Copy` elements, which are copied into the storage
+    /// a contiguous run at a time instead of cloned one by one.
+    pub fn write_slice_copied(&mut self, items: &[T]) -> Result<usize, Error>
+    where
+        T: Copy,
+    {
+        self.write_slice_with(items, Self::copy_from)
+    }
+
+    /// `overwrite_slice` for `Copy` elements, copied like `write_slice_copied`.
+    pub fn overwrite_slice_copied(&mut self, items: &[T])
+    where
+        T: Copy,
+    {
+        self.overwrite_slice_with(items, Self::copy_from);
+    }
+
     pub fn read(&mut self) -> Result<T, Error> {
-        self.data[self.read_index]
-            .take()
-            .ok_or(Error::EmptyBuffer)
-            .map(|value| {
-                self.read_index = self.increase_index(self.read_index);
-                value
-            })
+        self.take_oldest().ok_or(Error::EmptyBuffer)
     }
 
     pub fn clear(&mut self) {
-        self.data = (0..self.data.len()).map(|_| None).collect();
+        self.drop_elements();
         self.read_index = 0;
         self.write_index = 0;
     }
 
     pub fn overwrite(&mut self, element: T) {
-        let is_overwriting = self.is_full();
+        self.overwrite_returning(element);
+    }
+
+    /// Like `overwrite`, but hand back the oldest element if it had to make
+    /// room for `element`. A buffer with no capacity hands back `element`
+    /// itself.
+    pub fn overwrite_returning(&mut self, element: T) -> Option<T> {
+        if self.data.is_empty() {
+            return Some(element);
+        }
+
+        let displaced = if self.is_full() {
+            self.take_oldest()
+        } else {
+            None
+        };
+
         self.write_without_check(element);
-        if is_overwriting {
-            self.read_index = self.increase_index(self.read_index);
+        displaced
+    }
+
+    /// `overwrite_returning` if `allow_evict` is set, `write` otherwise.
+    pub fn try_write_or_overwrite(
+        &mut self,
+        element: T,
+        allow_evict: bool,
+    ) -> Result<Option<T>, Error> {
+        if allow_evict {
+            Ok(self.overwrite_returning(element))
+        } else {
+            self.write(element).map(|()| None)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Peek at the element `index_from_oldest` positions after the next one to be read.
+    pub fn get(&self, index_from_oldest: usize) -> Option<&T> {
+        if index_from_oldest >= self.len {
+            return None;
         }
+
+        let slot = &self.data[(self.read_index + index_from_oldest) % self.data.len()];
+        Some(unsafe { slot.assume_init_ref() })
+    }
+
+    /// Peek at the element `index` positions before the most recently written one.
+    pub fn get_from_newest(&self, index: usize) -> Option<&T> {
+        let len = self.len();
+
+        if index >= len {
+            return None;
+        }
+
+        self.get(len - 1 - index)
+    }
+
+    /// Fold over the stored elements from the oldest to the newest.
+    pub fn fold<B>(&self, init: B, f: impl FnMut(B, &T) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    pub fn min_item(&self) -> Option<&T>
+    where
+        T: PartialOrd,
+    {
+        self.iter()
+            .reduce(|min, item| if item < min { item } else { min })
+    }
+
+    pub fn max_item(&self) -> Option<&T>
+    where
+        T: PartialOrd,
+    {
+        self.iter()
+            .reduce(|max, item| if item > max { item } else { max })
+    }
+
+    /// Return the stored elements in FIFO order as at most two contiguous
+    /// slices, like `VecDeque::as_slices`. The second slice is only non-empty
+    /// when the stored elements wrap around the end of the backing storage.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let end = self.read_index + self.len;
+
+        let (front, back) = if self.len == 0 {
+            (&self.data[..0], &self.data[..0])
+        } else if end <= self.data.len() {
+            (&self.data[self.read_index..end], &self.data[..0])
+        } else {
+            (
+                &self.data[self.read_index..],
+                &self.data[..end - self.data.len()],
+            )
+        };
+
+        // Both ranges lie within the `len` initialized slots.
+        unsafe { (assume_init_slice(front), assume_init_slice(back)) }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).filter_map(move |index| self.get(index))
     }
 
     fn is_full(&self) -> bool {
-        self.data[self.write_index].is_some()
+        self.len == self.data.len()
     }
 
     fn increase_index(&self, index: usize) -> usize {
         (index + 1) % self.data.len()
     }
 
+    // Move the oldest element out, leaving its slot uninitialized.
+    fn take_oldest(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let element = unsafe { ptr::read(self.data[self.read_index].as_ptr()) };
+        self.read_index = self.increase_index(self.read_index);
+        self.len -= 1;
+
+        Some(element)
+    }
+
+    // Drop every stored element in place. `len` is reset first so that a
+    // panicking `drop` leaks the rest instead of dropping them twice.
+    fn drop_elements(&mut self) {
+        let len = mem::replace(&mut self.len, 0);
+
+        for offset in 0..len {
+            let index = (self.read_index + offset) % self.data.len();
+            unsafe { ptr::drop_in_place(self.data[index].as_mut_ptr()) };
+        }
+    }
+
+    fn write_slice_with(&mut self, items: &[T], fill: fn(&mut Self, &[T])) -> Result<usize, Error> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let count = items.len().min(self.data.len() - self.len);
+        if count == 0 {
+            return Err(Error::FullBuffer);
+        }
+
+        fill(self, &items[..count]);
+        Ok(count)
+    }
+
+    fn overwrite_slice_with(&mut self, items: &[T], fill: fn(&mut Self, &[T])) {
+        let items = &items[items.len().saturating_sub(self.data.len())..];
+        let overwritten = (self.len + items.len()).saturating_sub(self.data.len());
+
+        for _ in 0..overwritten {
+            self.take_oldest();
+        }
+
+        fill(self, items);
+    }
+
+    // Copy `items` into the slots starting at the write index without
+    // checking for room, as one run up to the end of the storage and one
+    // from its start. Copying can't panic, so nothing is left half-written.
+    fn copy_from(&mut self, items: &[T])
+    where
+        T: Copy,
+    {
+        if items.is_empty() {
+            return;
+        }
+
+        let first = items.len().min(self.data.len() - self.write_index);
+        let (head, tail) = items.split_at(first);
+
+        unsafe {
+            let storage = self.data.as_mut_ptr().cast::<T>();
+            ptr::copy_nonoverlapping(head.as_ptr(), storage.add(self.write_index), head.len());
+            ptr::copy_nonoverlapping(tail.as_ptr(), storage, tail.len());
+        }
+
+        self.write_index = (self.write_index + items.len()) % self.data.len();
+        self.len += items.len();
+    }
+
+    // Clone `items` into the slots starting at the write index without
+    // checking for room. `write_index` and `len` move together after every
+    // element, so a panicking `clone` leaves the buffer holding exactly the
+    // elements written so far.
+    fn fill_from(&mut self, items: &[T])
+    where
+        T: Clone,
+    {
+        for item in items {
+            self.write_without_check(item.clone());
+        }
+    }
+
     fn write_without_check(&mut self, element: T) {
-        self.data[self.write_index] = Some(element);
+        unsafe { ptr::write(self.data[self.write_index].as_mut_ptr(), element) };
         self.write_index = self.increase_index(self.write_index);
+        self.len += 1;
+    }
+}
+
+impl<T> Drop for CircularBuffer<T> {
+    fn drop(&mut self) {
+        self.drop_elements();
+    }
+}
+
+impl<T> FromIterator<T> for CircularBuffer<T> {
+    /// Collect into a full buffer exactly as large as the number of items.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+// The caller guarantees every slot in `slots` is initialized.
+unsafe fn assume_init_slice<T>(slots: &[MaybeUninit<T>]) -> &[T] {
+    slice::from_raw_parts(slots.as_ptr().cast(), slots.len())
+}
+
+#[test]
+fn collect_sizes_the_buffer_to_fit() {
+    let buffer = (0..5).collect::<CircularBuffer<_>>();
+    assert_eq!(5, buffer.capacity());
+    assert_eq!(5, buffer.len());
+}
+
+#[test]
+fn collected_buffer_reads_in_iteration_order() {
+    let mut buffer: CircularBuffer<_> = "abc".chars().collect();
+    assert_eq!(Err(Error::FullBuffer), buffer.write('d'));
+    assert_eq!(Ok('a'), buffer.read());
+    assert!(buffer.write('d').is_ok());
+    for expected in ['b', 'c', 'd'] {
+        assert_eq!(Ok(expected), buffer.read());
     }
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn from_vec_reuses_the_allocation() {
+    let mut v = Vec::with_capacity(8);
+    v.extend([1, 2, 3]);
+    let pointer = v.as_ptr();
+
+    let mut buffer = CircularBuffer::from_vec(v);
+    assert_eq!(3, buffer.capacity());
+    assert_eq!((&[1, 2, 3][..], &[][..]), buffer.as_slices());
+    assert_eq!(pointer, buffer.as_slices().0.as_ptr());
+
+    buffer.overwrite(4);
+    assert_eq!(Ok(2), buffer.read());
+}
+
+#[test]
+fn from_empty_vec_has_no_capacity() {
+    let mut buffer = CircularBuffer::<i32>::from_vec(Vec::new());
+    assert_eq!(0, buffer.capacity());
+    assert!(buffer.is_empty());
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+    assert_eq!(Err(Error::FullBuffer), buffer.write(1));
+    buffer.overwrite(2);
+    assert_eq!(Some(3), buffer.overwrite_returning(3));
+    assert_eq!(Ok(Some(4)), buffer.try_write_or_overwrite(4, true));
+    assert_eq!(
+        Err(Error::FullBuffer),
+        buffer.try_write_or_overwrite(5, false)
+    );
+    buffer.overwrite_slice(&[6, 7]);
+    assert!(buffer.is_empty());
+
+    let mut buffer: CircularBuffer<i32> = std::iter::empty().collect();
+    assert_eq!(Some(1), buffer.overwrite_returning(1));
+    let mut buffer = CircularBuffer::new(0);
+    assert_eq!(Some(1), buffer.overwrite_returning(1));
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn from_vec_drops_its_elements() {
+    let element = Rc::new(());
+    let buffer = CircularBuffer::from_vec(vec![Rc::clone(&element), Rc::clone(&element)]);
+    assert_eq!(Rc::strong_count(&element), 3);
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&element), 1);
 }
 
 #[test]
@@ -162,6 +501,49 @@ fn clear_actually_frees_up_its_elements() {
     assert_eq!(Rc::strong_count(&element), 1);
 }
 
+#[test]
+fn only_stored_elements_are_dropped() {
+    let element = Rc::new(());
+    let mut buffer = CircularBuffer::new(3);
+    for _ in 0..3 {
+        assert!(buffer.write(Rc::clone(&element)).is_ok());
+    }
+    assert_eq!(Rc::strong_count(&element), 4);
+
+    drop(buffer.read());
+    assert_eq!(Rc::strong_count(&element), 3);
+
+    // Wrap around, then evict one element to make room for another.
+    assert!(buffer.write(Rc::clone(&element)).is_ok());
+    buffer.overwrite(Rc::clone(&element));
+    assert_eq!(Rc::strong_count(&element), 4);
+
+    buffer.overwrite_slice(&[Rc::clone(&element), Rc::clone(&element)]);
+    assert_eq!(Rc::strong_count(&element), 4);
+
+    let kept = buffer.read().unwrap();
+    assert_eq!(Rc::strong_count(&element), 4);
+    drop(kept);
+
+    // Dropping the buffer releases the two elements still in it, straddling
+    // the end of the storage, and nothing else.
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&element), 1);
+}
+
+#[test]
+fn dropping_an_empty_or_unused_buffer_drops_nothing() {
+    let element = Rc::new(());
+    let mut buffer = CircularBuffer::new(2);
+    assert!(buffer.write(Rc::clone(&element)).is_ok());
+    drop(buffer.read());
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&element), 1);
+
+    drop(CircularBuffer::<Rc<()>>::new(4));
+    drop(CircularBuffer::<Rc<()>>::new(0));
+}
+
 #[test]
 fn overwrite_acts_like_write_on_non_full_buffer() {
     let mut buffer = CircularBuffer::new(2);
@@ -196,6 +578,85 @@ fn overwrite_replaces_the_oldest_item_remaining_in_buffer_following_a_read() {
     assert_eq!(Ok('5'), buffer.read());
 }
 
+#[test]
+fn overwrite_returning_gives_back_nothing_on_non_full_buffer() {
+    let mut buffer = CircularBuffer::new(2);
+    assert_eq!(None, buffer.overwrite_returning('1'));
+    assert_eq!(None, buffer.overwrite_returning('2'));
+    assert_eq!(Ok('1'), buffer.read());
+    assert_eq!(Ok('2'), buffer.read());
+}
+
+#[test]
+fn overwrite_returning_gives_back_the_oldest_item_remaining_in_buffer_following_a_read() {
+    let mut buffer = CircularBuffer::new(3);
+    assert!(buffer.write('1').is_ok());
+    assert!(buffer.write('2').is_ok());
+    assert!(buffer.write('3').is_ok());
+    assert_eq!(Ok('1'), buffer.read());
+    assert!(buffer.write('4').is_ok());
+    assert_eq!(Some('2'), buffer.overwrite_returning('5'));
+    assert_eq!(Ok('3'), buffer.read());
+    assert_eq!(Ok('4'), buffer.read());
+    assert_eq!(Ok('5'), buffer.read());
+}
+
+#[test]
+fn overwrite_returning_evicts_in_write_order_across_wraparound() {
+    let mut buffer = CircularBuffer::new(3);
+    let evicted: Vec<Option<i32>> = (1..=8)
+        .map(|item| buffer.overwrite_returning(item))
+        .collect();
+    assert_eq!(
+        vec![
+            None,
+            None,
+            None,
+            Some(1),
+            Some(2),
+            Some(3),
+            Some(4),
+            Some(5)
+        ],
+        evicted
+    );
+
+    assert_eq!(Ok(6), buffer.read());
+    assert_eq!(None, buffer.overwrite_returning(9));
+    assert_eq!(Some(7), buffer.overwrite_returning(10));
+    assert_eq!(Some(8), buffer.overwrite_returning(11));
+    for expected in [9, 10, 11] {
+        assert_eq!(Ok(expected), buffer.read());
+    }
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn overwrite_returning_releases_the_evicted_element() {
+    let mut buffer = CircularBuffer::new(1);
+    let element = Rc::new(());
+    buffer.overwrite(Rc::clone(&element));
+    let evicted = buffer.overwrite_returning(Rc::new(()));
+    assert!(evicted.is_some_and(|evicted| Rc::ptr_eq(&evicted, &element)));
+    assert_eq!(Rc::strong_count(&element), 1);
+}
+
+#[test]
+fn try_write_or_overwrite_follows_allow_evict() {
+    let mut buffer = CircularBuffer::new(2);
+    assert_eq!(Ok(None), buffer.try_write_or_overwrite(1, false));
+    assert_eq!(Ok(None), buffer.try_write_or_overwrite(2, true));
+    assert_eq!(
+        Err(Error::FullBuffer),
+        buffer.try_write_or_overwrite(3, false)
+    );
+    assert_eq!(Ok(Some(1)), buffer.try_write_or_overwrite(4, true));
+    assert_eq!(Ok(2), buffer.read());
+    assert_eq!(Ok(None), buffer.try_write_or_overwrite(5, false));
+    assert_eq!(Ok(4), buffer.read());
+    assert_eq!(Ok(5), buffer.read());
+}
+
 #[test]
 fn integer_buffer() {
     let mut buffer = CircularBuffer::new(2);
@@ -216,3 +677,317 @@ fn string_buffer() {
     assert_eq!(0, buffer.read().unwrap().len());
     assert_eq!(Ok("Testing".to_string()), buffer.read());
 }
+
+#[test]
+fn len_tracks_reads_and_writes() {
+    let mut buffer = CircularBuffer::new(3);
+    assert!(buffer.is_empty());
+    assert!(buffer.write(1).is_ok());
+    assert!(buffer.write(2).is_ok());
+    assert_eq!(2, buffer.len());
+    assert!(buffer.write(3).is_ok());
+    assert_eq!(3, buffer.len());
+    assert_eq!(Ok(1), buffer.read());
+    assert_eq!(2, buffer.len());
+    buffer.overwrite(4);
+    buffer.overwrite(5);
+    assert_eq!(3, buffer.len());
+    buffer.clear();
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn get_reads_across_the_wrap_boundary() {
+    let mut buffer = CircularBuffer::new(4);
+    for item in 1..=10 {
+        buffer.overwrite(item);
+    }
+
+    assert_eq!(Some(&7), buffer.get(0));
+    assert_eq!(Some(&10), buffer.get(3));
+    assert_eq!(None, buffer.get(4));
+    assert_eq!(Some(&10), buffer.get_from_newest(0));
+    assert_eq!(Some(&7), buffer.get_from_newest(3));
+    assert_eq!(None, buffer.get_from_newest(4));
+
+    // peeking must not disturb the read position
+    assert_eq!(Ok(7), buffer.read());
+    assert_eq!(Some(&8), buffer.get(0));
+    assert_eq!(Some(&10), buffer.get_from_newest(0));
+}
+
+#[test]
+fn get_on_empty_buffer() {
+    let buffer = CircularBuffer::<i32>::new(2);
+    assert_eq!(None, buffer.get(0));
+    assert_eq!(None, buffer.get_from_newest(0));
+    assert_eq!(None, buffer.min_item());
+    assert_eq!(None, buffer.max_item());
+    assert_eq!(0, buffer.fold(0, |acc, item| acc + item));
+}
+
+#[test]
+fn aggregates_match_a_reference_window() {
+    let mut buffer = CircularBuffer::new(5);
+    let mut window = VecDeque::new();
+    let readings = [3, -1, 4, 1, -5, 9, 2, 6, -5, 3, 5, 8, -9, 7, 9];
+
+    for (step, &reading) in readings.iter().enumerate() {
+        buffer.overwrite(reading);
+        window.push_back(reading);
+        if window.len() > 5 {
+            window.pop_front();
+        }
+
+        if step % 4 == 3 {
+            assert_eq!(window.pop_front().ok_or(Error::EmptyBuffer), buffer.read());
+        }
+
+        for (index, item) in window.iter().enumerate() {
+            assert_eq!(Some(item), buffer.get(index));
+            assert_eq!(Some(item), buffer.get_from_newest(window.len() - 1 - index));
+        }
+
+        assert_eq!(window.len(), buffer.len());
+        assert_eq!(
+            window.iter().sum::<i32>(),
+            buffer.fold(0, |acc, item| acc + item)
+        );
+        assert_eq!(window.iter().min(), buffer.min_item());
+        assert_eq!(window.iter().max(), buffer.max_item());
+    }
+}
+
+#[test]
+fn as_slices_on_empty_buffer() {
+    let buffer = CircularBuffer::<i32>::new(3);
+    let (front, back) = buffer.as_slices();
+    assert!(front.is_empty());
+    assert!(back.is_empty());
+}
+
+#[test]
+fn as_slices_without_wraparound() {
+    let mut buffer = CircularBuffer::new(4);
+    assert!(buffer.write(1).is_ok());
+    assert!(buffer.write(2).is_ok());
+    assert!(buffer.write(3).is_ok());
+    assert_eq!(Ok(1), buffer.read());
+
+    let (front, back) = buffer.as_slices();
+    assert_eq!(&[2, 3], front);
+    assert!(back.is_empty());
+}
+
+#[test]
+fn as_slices_with_wraparound() {
+    let mut buffer = CircularBuffer::new(4);
+    for item in 1..=6 {
+        buffer.overwrite(item);
+    }
+
+    let (front, back) = buffer.as_slices();
+    assert_eq!(&[3, 4], front);
+    assert_eq!(&[5, 6], back);
+    assert_eq!(buffer.len(), front.len() + back.len());
+}
+
+#[test]
+fn as_slices_of_full_buffer_starting_at_zero() {
+    let mut buffer = CircularBuffer::new(3);
+    for item in 1..=3 {
+        assert!(buffer.write(item).is_ok());
+    }
+
+    let (front, back) = buffer.as_slices();
+    assert_eq!(&[1, 2, 3], front);
+    assert!(back.is_empty());
+}
+
+#[test]
+fn write_slice_stops_when_the_buffer_is_full() {
+    let mut buffer = CircularBuffer::new(4);
+    assert!(buffer.write(1).is_ok());
+    assert!(buffer.write(2).is_ok());
+    assert_eq!(Ok(1), buffer.read());
+    assert_eq!(Ok(3), buffer.write_slice(&[3, 4, 5, 6, 7]));
+    assert_eq!(4, buffer.len());
+    assert_eq!(Err(Error::FullBuffer), buffer.write_slice(&[8]));
+    for expected in [2, 3, 4, 5] {
+        assert_eq!(Ok(expected), buffer.read());
+    }
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn write_slice_wraps_around() {
+    let mut buffer = CircularBuffer::new(3);
+    assert_eq!(Ok(2), buffer.write_slice(&['a', 'b']));
+    assert_eq!(Ok('a'), buffer.read());
+    assert_eq!(Ok('b'), buffer.read());
+    assert_eq!(Ok(3), buffer.write_slice(&['c', 'd', 'e']));
+    assert_eq!((&['c'][..], &['d', 'e'][..]), buffer.as_slices());
+}
+
+#[test]
+fn write_empty_slice() {
+    let mut buffer = CircularBuffer::<i32>::new(1);
+    assert_eq!(Ok(0), buffer.write_slice(&[]));
+    assert!(buffer.write(1).is_ok());
+    assert_eq!(Ok(0), buffer.write_slice(&[]));
+    assert_eq!(1, buffer.len());
+}
+
+#[test]
+fn overwrite_slice_on_a_full_buffer() {
+    let mut buffer = CircularBuffer::new(3);
+    assert_eq!(Ok(3), buffer.write_slice(&[1, 2, 3]));
+    buffer.overwrite_slice(&[4, 5]);
+    assert_eq!(3, buffer.len());
+    for expected in [3, 4, 5] {
+        assert_eq!(Ok(expected), buffer.read());
+    }
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn overwrite_slice_longer_than_capacity_keeps_the_newest() {
+    let mut buffer = CircularBuffer::new(3);
+    assert!(buffer.write(0).is_ok());
+    buffer.overwrite_slice(&[1, 2, 3, 4, 5]);
+    for expected in [3, 4, 5] {
+        assert_eq!(Ok(expected), buffer.read());
+    }
+    assert_eq!(Err(Error::EmptyBuffer), buffer.read());
+}
+
+#[test]
+fn slices_on_a_zero_capacity_buffer() {
+    let mut buffer = CircularBuffer::new(0);
+    assert_eq!(Err(Error::FullBuffer), buffer.write_slice(&[1]));
+    buffer.overwrite_slice(&[1, 2]);
+    assert_eq!(0, buffer.len());
+}
+
+#[test]
+fn overwrite_slice_matches_repeated_overwrite() {
+    for capacity in 1..5 {
+        for written in 0..=capacity {
+            for length in 0..8 {
+                let items: Vec<usize> = (100..100 + length).collect();
+                let mut batch = CircularBuffer::new(capacity);
+                let mut single = CircularBuffer::new(capacity);
+                for value in 0..written {
+                    assert!(batch.write(value).is_ok());
+                    assert!(single.write(value).is_ok());
+                }
+                batch.overwrite_slice(&items);
+                for &item in &items {
+                    single.overwrite(item);
+                }
+                let contents = |buffer: &CircularBuffer<usize>| {
+                    let (front, back) = buffer.as_slices();
+                    [front, back].concat()
+                };
+                assert_eq!(contents(&single), contents(&batch));
+            }
+        }
+    }
+}
+
+#[test]
+fn write_slice_keeps_what_it_wrote_when_clone_panics() {
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    // Panics once `clones_left` runs out.
+    struct Fused {
+        element: Rc<()>,
+        clones_left: Rc<Cell<usize>>,
+    }
+
+    impl Clone for Fused {
+        fn clone(&self) -> Self {
+            let left = self.clones_left.get();
+            assert!(left > 0, "clone panicked on purpose");
+            self.clones_left.set(left - 1);
+            Self {
+                element: Rc::clone(&self.element),
+                clones_left: Rc::clone(&self.clones_left),
+            }
+        }
+    }
+
+    let element = Rc::new(());
+    let clones_left = Rc::new(Cell::new(usize::MAX));
+    let item = Fused {
+        element: Rc::clone(&element),
+        clones_left: Rc::clone(&clones_left),
+    };
+    let items = [item.clone(), item.clone(), item.clone()];
+
+    // Start near the end of the storage so the failed write wraps around.
+    let mut buffer = CircularBuffer::new(4);
+    for _ in 0..3 {
+        assert!(buffer.write(item.clone()).is_ok());
+    }
+    drop(buffer.read());
+    drop(buffer.read());
+    assert_eq!(Rc::strong_count(&element), 1 + 1 + 3 + 1);
+
+    clones_left.set(2);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| buffer.write_slice(&items)));
+    assert!(result.is_err());
+    assert_eq!(3, buffer.len());
+    assert_eq!(Rc::strong_count(&element), 1 + 1 + 3 + 3);
+
+    // The next write goes into the slot after the last one written.
+    clones_left.set(usize::MAX);
+    assert!(buffer.write(item.clone()).is_ok());
+    assert_eq!(Err(Error::FullBuffer), buffer.write(item.clone()));
+    assert_eq!(Rc::strong_count(&element), 1 + 1 + 3 + 4);
+
+    drop(buffer);
+    drop(items);
+    assert_eq!(Rc::strong_count(&element), 2);
+}
+
+#[test]
+fn copied_slices_match_cloned_slices() {
+    for capacity in 1..5 {
+        for written in 0..=capacity {
+            for length in 0..8 {
+                let items: Vec<usize> = (100..100 + length).collect();
+                let mut copied = CircularBuffer::new(capacity);
+                let mut cloned = CircularBuffer::new(capacity);
+                for value in 0..written {
+                    assert!(copied.write(value).is_ok());
+                    assert!(cloned.write(value).is_ok());
+                }
+                // Move the read index so the copies start mid-storage.
+                drop(copied.read());
+                drop(cloned.read());
+                assert_eq!(
+                    cloned.write_slice(&items),
+                    copied.write_slice_copied(&items)
+                );
+                assert_eq!(cloned.as_slices(), copied.as_slices());
+
+                copied.overwrite_slice_copied(&items);
+                cloned.overwrite_slice(&items);
+                assert_eq!(cloned.as_slices(), copied.as_slices());
+                assert_eq!(cloned.write(0), copied.write(0));
+                assert_eq!(cloned.as_slices(), copied.as_slices());
+            }
+        }
+    }
+}
+
+#[test]
+fn copied_slice_on_a_zero_capacity_buffer() {
+    let mut buffer = CircularBuffer::new(0);
+    assert_eq!(Ok(0), buffer.write_slice_copied(&[]));
+    assert_eq!(Err(Error::FullBuffer), buffer.write_slice_copied(&[1]));
+    buffer.overwrite_slice_copied(&[1, 2]);
+    assert_eq!(0, buffer.len());
+}
@@ -0,0 +1,215 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::Error;
+
+/// The slab shared by the two halves of a single-producer single-consumer
+/// buffer. Only the `Producer` writes and only the `Consumer` reads, so each
+/// slot is touched by one thread at a time without locking.
+pub struct SpscBuffer<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // Both count every element ever read or written, so the buffer is empty
+    // when they're equal and full when they're `capacity` apart.
+    read_count: AtomicUsize,
+    write_count: AtomicUsize,
+}
+
+// The halves hand each element from one thread to the other, and the counts
+// keep them from touching the same slot at once.
+unsafe impl<T: Send> Sync for SpscBuffer<T> {}
+
+/// The writing half of an `SpscBuffer`.
+pub struct Producer<T> {
+    buffer: Arc<SpscBuffer<T>>,
+}
+
+/// The reading half of an `SpscBuffer`.
+pub struct Consumer<T> {
+    buffer: Arc<SpscBuffer<T>>,
+}
+
+impl<T> SpscBuffer<T> {
+    /// Split a buffer that holds up to `capacity` elements into its two halves,
+    /// which may be moved to different threads.
+    pub fn with_capacity(capacity: usize) -> (Producer<T>, Consumer<T>) {
+        let buffer = Arc::new(Self {
+            slots: (0..capacity)
+                .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+                .collect(),
+            read_count: AtomicUsize::new(0),
+            write_count: AtomicUsize::new(0),
+        });
+
+        (
+            Producer {
+                buffer: Arc::clone(&buffer),
+            },
+            Consumer { buffer },
+        )
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn slot(&self, count: usize) -> *mut MaybeUninit<T> {
+        self.slots[count % self.capacity()].get()
+    }
+}
+
+impl<T> Drop for SpscBuffer<T> {
+    fn drop(&mut self) {
+        let read_count = *self.read_count.get_mut();
+        let write_count = *self.write_count.get_mut();
+
+        for count in read_count..write_count {
+            unsafe { (*self.slot(count)).assume_init_drop() }
+        }
+    }
+}
+
+impl<T> Producer<T> {
+    pub fn write(&mut self, element: T) -> Result<(), Error> {
+        let buffer = &self.buffer;
+        let write_count = buffer.write_count.load(Ordering::Relaxed);
+        let read_count = buffer.read_count.load(Ordering::Acquire);
+
+        if write_count - read_count == buffer.capacity() {
+            return Err(Error::FullBuffer);
+        }
+
+        // The consumer is done with this slot: it was read before `read_count`
+        // moved past it, or it was never written.
+        unsafe { (*buffer.slot(write_count)).write(element) };
+        buffer.write_count.store(write_count + 1, Ordering::Release);
+
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+}
+
+impl<T> Consumer<T> {
+    pub fn read(&mut self) -> Result<T, Error> {
+        let buffer = &self.buffer;
+        let read_count = buffer.read_count.load(Ordering::Relaxed);
+        let write_count = buffer.write_count.load(Ordering::Acquire);
+
+        if read_count == write_count {
+            return Err(Error::EmptyBuffer);
+        }
+
+        // The producer finished writing this slot before `write_count` moved
+        // past it, and won't reuse it until `read_count` does.
+        let element = unsafe { (*buffer.slot(read_count)).assume_init_read() };
+        buffer.read_count.store(read_count + 1, Ordering::Release);
+
+        Ok(element)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+}
+
+#[cfg(test)]
+use std::thread;
+
+#[test]
+fn spsc_reads_in_write_order() {
+    let (mut producer, mut consumer) = SpscBuffer::with_capacity(2);
+    assert_eq!(Err(Error::EmptyBuffer), consumer.read());
+    assert!(producer.write('1').is_ok());
+    assert!(producer.write('2').is_ok());
+    assert_eq!(Ok('1'), consumer.read());
+    assert_eq!(Ok('2'), consumer.read());
+    assert_eq!(Err(Error::EmptyBuffer), consumer.read());
+}
+
+#[test]
+fn spsc_full_when_write_catches_read() {
+    let (mut producer, mut consumer) = SpscBuffer::with_capacity(3);
+    assert_eq!(3, producer.capacity());
+    for item in 1..=3 {
+        assert!(producer.write(item).is_ok());
+    }
+    assert_eq!(Err(Error::FullBuffer), producer.write(4));
+    assert_eq!(Ok(1), consumer.read());
+    assert!(producer.write(4).is_ok());
+    assert_eq!(Err(Error::FullBuffer), producer.write(5));
+    for item in 2..=4 {
+        assert_eq!(Ok(item), consumer.read());
+    }
+}
+
+#[test]
+fn spsc_zero_capacity_is_always_full_and_empty() {
+    let (mut producer, mut consumer) = SpscBuffer::with_capacity(0);
+    assert_eq!(Err(Error::FullBuffer), producer.write(1));
+    assert_eq!(Err(Error::EmptyBuffer), consumer.read());
+}
+
+#[test]
+fn spsc_transfers_across_threads() {
+    const COUNT: u64 = 200_000;
+    let (mut producer, mut consumer) = SpscBuffer::with_capacity(16);
+
+    let writer = thread::spawn(move || {
+        for item in 0..COUNT {
+            while producer.write(item).is_err() {
+                thread::yield_now();
+            }
+        }
+    });
+    let reader = thread::spawn(move || {
+        let mut received = Vec::new();
+        while received.len() < COUNT as usize {
+            match consumer.read() {
+                Ok(item) => received.push(item),
+                Err(_) => thread::yield_now(),
+            }
+        }
+        received
+    });
+
+    writer.join().unwrap();
+    let received = reader.join().unwrap();
+    assert!((0..COUNT).eq(received));
+}
+
+#[test]
+fn spsc_drops_unread_elements_once() {
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (mut producer, mut consumer) = SpscBuffer::with_capacity(4);
+
+    // Wrap around once so the unread elements straddle the end of the slab.
+    for _ in 0..3 {
+        assert!(producer.write(DropCounter(Arc::clone(&drops))).is_ok());
+    }
+    for _ in 0..3 {
+        drop(consumer.read().unwrap());
+    }
+    for _ in 0..3 {
+        assert!(producer.write(DropCounter(Arc::clone(&drops))).is_ok());
+    }
+    drop(consumer.read().unwrap());
+    assert_eq!(4, drops.load(Ordering::SeqCst));
+
+    let consumer = thread::spawn(move || consumer);
+    drop(producer);
+    assert_eq!(4, drops.load(Ordering::SeqCst));
+    drop(consumer.join().unwrap());
+    assert_eq!(6, drops.load(Ordering::SeqCst));
+}
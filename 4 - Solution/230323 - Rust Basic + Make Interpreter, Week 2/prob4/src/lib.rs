@@ -1,3 +1,4 @@
+use std::fmt;
 use std::iter::FromIterator;
 
 struct Node<T> {
@@ -15,6 +16,11 @@ pub struct SimpleLinkedList<T> {
     head: Option<Box<Node<T>>>,
 }
 
+/// Borrows the items from the top of the stack to the bottom.
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
 impl<T> SimpleLinkedList<T> {
     pub fn new() -> Self {
         Self { head: None }
@@ -55,6 +61,16 @@ impl<T> SimpleLinkedList<T> {
         self.head.as_ref().map(|head| &(head.data))
     }
 
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().map(|head| &mut (head.data))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
     pub fn rev(mut self) -> SimpleLinkedList<T> {
         let mut ret = SimpleLinkedList::new();
 
@@ -66,6 +82,62 @@ impl<T> SimpleLinkedList<T> {
     }
 }
 
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.data
+        })
+    }
+}
+
+/// Drops the nodes one at a time. The default drop would recurse through
+/// every `next` box and overflow the stack on a long list.
+impl<T> Drop for SimpleLinkedList<T> {
+    fn drop(&mut self) {
+        let mut cur_node = self.head.take();
+
+        while let Some(mut node) = cur_node {
+            cur_node = node.next.take();
+        }
+    }
+}
+
+/// Copies the nodes in a single walk down the list, in the same order.
+impl<T: Clone> Clone for SimpleLinkedList<T> {
+    fn clone(&self) -> Self {
+        let mut list = SimpleLinkedList::new();
+        let mut tail = &mut list.head;
+
+        for item in self.iter() {
+            let node = tail.insert(Box::new(Node::new(item.clone(), None)));
+            tail = &mut node.next;
+        }
+
+        list
+    }
+}
+
+/// Lists are equal when they would pop the same items in the same order.
+impl<T: PartialEq> PartialEq for SimpleLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SimpleLinkedList<T> {}
+
+/// Formats like a slice in pop order, top first: pushing 1, 2 and 3 shows
+/// `[3, 2, 1]`.
+impl<T: fmt::Debug> fmt::Debug for SimpleLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Pushes the items in iteration order, so the last one ends up on top.
 impl<T> FromIterator<T> for SimpleLinkedList<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut list = SimpleLinkedList::new();
@@ -172,6 +244,43 @@ fn test_peek_returns_reference_to_head_element_but_does_not_remove_it() {
     assert_eq!(list.peek(), None, "No element should be contained in list");
 }
 
+#[test]
+fn test_peek_mut_updates_head_element_in_place() {
+    let mut list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    assert_eq!(
+        list.peek_mut(),
+        None,
+        "No element should be contained in list"
+    );
+    list.push(1);
+    list.push(21);
+    if let Some(head) = list.peek_mut() {
+        *head *= 2;
+    }
+    assert_eq!(list.peek(), Some(&42), "Head element must be doubled");
+    assert_eq!(list.len(), 2, "peek_mut must not change the length");
+    assert_eq!(list.pop(), Some(42), "Element must be 42");
+    assert_eq!(list.pop(), Some(1), "Element below the head is untouched");
+}
+
+#[test]
+fn test_iter_walks_from_top_without_consuming() {
+    let mut list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    for i in 1..=5 {
+        list.push(i);
+    }
+    let items: Vec<&u32> = list.iter().collect();
+    assert_eq!(items, vec![&5, &4, &3, &2, &1], "Items must come top first");
+    assert_eq!(list.len(), 5, "iter must not consume the list");
+    assert_eq!(list.pop(), Some(5), "Head element is still 5");
+}
+
+#[test]
+fn test_iter_on_empty_list() {
+    let list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    assert_eq!(list.iter().next(), None, "Empty list yields nothing");
+}
+
 #[test]
 fn test_from_slice() {
     let mut array = vec!["1", "2", "3", "4"];
@@ -206,3 +315,77 @@ fn test_into_vector() {
     let s_as_vec: Vec<i32> = s.into();
     assert_eq!(v, s_as_vec);
 }
+
+#[test]
+fn test_dropping_a_long_list_does_not_overflow() {
+    let list: SimpleLinkedList<u32> = (0..2_000_000).collect();
+    assert_eq!(list.peek(), Some(&1_999_999));
+    let cloned = list.clone();
+    drop(list);
+    assert_eq!(cloned.len(), 2_000_000);
+    drop(cloned.rev());
+}
+
+#[test]
+fn test_clone_is_independent() {
+    let mut list: SimpleLinkedList<String> =
+        ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let mut cloned = list.clone();
+    assert_eq!(list, cloned);
+    cloned.push("d".to_string());
+    if let Some(head) = list.peek_mut() {
+        head.push('!');
+    }
+    assert_eq!(list.pop(), Some("c!".to_string()));
+    assert_eq!(cloned.pop(), Some("d".to_string()));
+    assert_eq!(cloned.pop(), Some("c".to_string()));
+    let rest: Vec<String> = list.into();
+    let cloned_rest: Vec<String> = cloned.into();
+    assert_eq!(rest, cloned_rest);
+}
+
+#[test]
+fn test_clone_of_empty_list() {
+    let list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    assert!(list.clone().is_empty());
+}
+
+#[test]
+fn test_equality_ignores_how_the_list_was_built() {
+    let mut pushed = SimpleLinkedList::new();
+    for i in 1..=3 {
+        pushed.push(i);
+    }
+
+    let collected: SimpleLinkedList<u32> = vec![1, 2, 3].into_iter().collect();
+    let reversed: SimpleLinkedList<u32> = vec![3, 2, 1]
+        .into_iter()
+        .collect::<SimpleLinkedList<_>>()
+        .rev();
+
+    let mut popped_and_pushed: SimpleLinkedList<u32> = (1..=5).collect();
+    popped_and_pushed.pop();
+    popped_and_pushed.pop();
+
+    assert_eq!(pushed, collected);
+    assert_eq!(pushed, reversed);
+    assert_eq!(pushed, popped_and_pushed);
+    assert_eq!(SimpleLinkedList::<u32>::new(), (0..0).collect());
+
+    let shorter: SimpleLinkedList<u32> = (1..=2).collect();
+    let reordered: SimpleLinkedList<u32> = vec![2, 1, 3].into_iter().collect();
+    assert_ne!(pushed, shorter);
+    assert_ne!(shorter, pushed);
+    assert_ne!(pushed, reordered);
+}
+
+#[test]
+fn test_debug_shows_pop_order() {
+    let mut list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    assert_eq!(format!("{list:?}"), "[]");
+    list.push(1);
+    list.push(2);
+    list.push(3);
+    assert_eq!(format!("{list:?}"), "[3, 2, 1]");
+    assert_eq!(format!("{:?}", list.rev()), "[1, 2, 3]");
+}
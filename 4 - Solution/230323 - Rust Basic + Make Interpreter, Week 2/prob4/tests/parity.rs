@@ -0,0 +1,96 @@
+use prob4::SimpleLinkedList;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::{RngSeed, TestRunner};
+
+/// Every stack operation the parity harness knows about.
+///
+/// New list features need a variant here, an entry in `Op::any` and an
+/// arm in `Op::apply`.
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Push(u32),
+    Pop,
+    Peek,
+    PeekMut(u32),
+    Len,
+    Iter,
+}
+
+impl Op {
+    /// Every operation, each as likely as the others.
+    fn any() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (0..1000_u32).prop_map(Op::Push),
+            Just(Op::Pop),
+            Just(Op::Peek),
+            (0..1000_u32).prop_map(Op::PeekMut),
+            Just(Op::Len),
+            Just(Op::Iter),
+        ]
+    }
+
+    /// Apply the operation to the list and to a `Vec` used as a stack
+    /// and compare what each of them can observe.
+    fn apply(self, list: &mut SimpleLinkedList<u32>, stack: &mut Vec<u32>) {
+        match self {
+            Op::Push(value) => {
+                list.push(value);
+                stack.push(value);
+            }
+            Op::Pop => assert_eq!(list.pop(), stack.pop()),
+            Op::Peek => assert_eq!(list.peek(), stack.last()),
+            Op::PeekMut(value) => {
+                if let Some(head) = list.peek_mut() {
+                    *head += value;
+                }
+                if let Some(top) = stack.last_mut() {
+                    *top += value;
+                }
+                assert_eq!(list.peek(), stack.last());
+            }
+            Op::Len => {
+                assert_eq!(list.len(), stack.len());
+                assert_eq!(list.is_empty(), stack.is_empty());
+            }
+            Op::Iter => assert!(list.iter().eq(stack.iter().rev())),
+        }
+    }
+}
+
+fn run_parity(ops: impl IntoIterator<Item = Op>) {
+    let mut list = SimpleLinkedList::new();
+    let mut stack = Vec::new();
+
+    for op in ops {
+        op.apply(&mut list, &mut stack);
+    }
+
+    let list: Vec<u32> = list.into();
+    assert_eq!(list, stack);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 20,
+        rng_seed: RngSeed::Fixed(601),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn parity_with_vec_stack(ops in prop::collection::vec(Op::any(), 500)) {
+        run_parity(ops);
+    }
+}
+
+#[test]
+#[ignore]
+fn parity_with_vec_stack_stress() {
+    // Drawn one at a time, since a strategy for millions of operations
+    // would keep all of them around for shrinking.
+    let mut runner = TestRunner::deterministic();
+    let op = Op::any();
+    let ops = std::iter::repeat_with(|| op.new_tree(&mut runner).unwrap().current());
+
+    run_parity(ops.take(2_000_000));
+}
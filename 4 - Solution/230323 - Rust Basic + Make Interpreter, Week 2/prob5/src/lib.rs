@@ -1,4 +1,13 @@
-use std::{marker::PhantomData, ptr};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// The test harness always links std, so the tests below may keep using it.
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+use alloc::boxed::Box;
+use core::{cmp::Ordering, marker::PhantomData, mem, ptr};
 
 mod linked_list;
 
@@ -24,6 +33,12 @@ pub struct Iter<'a, T> {
     _marker: PhantomData<&'a Node<T>>,
 }
 
+/// Removes a range of elements, see `LinkedList::drain`
+pub struct Drain<'a, T> {
+    cursor: Cursor<'a, T>,
+    remaining: usize,
+}
+
 impl<T> LinkedList<T> {
     pub fn new() -> Self {
         Self {
@@ -66,6 +81,222 @@ impl<T> LinkedList<T> {
             _marker: PhantomData,
         }
     }
+
+    /// Move the first `n % len` elements from the front to the back
+    ///
+    /// Only the links are rewired, so no element is moved or reallocated.
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        let n = n % self.len;
+        if n == 0 {
+            return;
+        }
+
+        unsafe {
+            // Walk from whichever end is closer to the node that becomes the head.
+            let mut new_head = self.head;
+            if n <= self.len / 2 {
+                for _ in 0..n {
+                    new_head = (*new_head).next;
+                }
+            } else {
+                new_head = self.tail;
+                for _ in 0..self.len - n - 1 {
+                    new_head = (*new_head).prev;
+                }
+            }
+
+            let new_tail = (*new_head).prev;
+
+            (*self.tail).next = self.head;
+            (*self.head).prev = self.tail;
+            (*new_tail).next = ptr::null_mut();
+            (*new_head).prev = ptr::null_mut();
+
+            self.head = new_head;
+            self.tail = new_tail;
+        }
+    }
+
+    /// Move the last `n % len` elements from the back to the front
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.rotate_left(self.len - n % self.len);
+    }
+
+    /// Sort the list with a stable bottom-up merge sort
+    ///
+    /// Only the links are rewired, and the sort needs no recursion, so even
+    /// very long lists can't overflow the stack. If `cmp` panics, the list
+    /// is left empty and its elements are leaked.
+    pub fn sort_by(&mut self, mut cmp: impl FnMut(&T, &T) -> Ordering) {
+        if self.len < 2 {
+            return;
+        }
+
+        let (mut curr, len) = self.unlink_all();
+
+        // `bins[i]` is either empty or a sorted run of `2^i` nodes that all
+        // came before the nodes in the lower bins.
+        let mut bins: [*mut Node<T>; usize::BITS as usize] =
+            [ptr::null_mut(); usize::BITS as usize];
+
+        unsafe {
+            while !curr.is_null() {
+                let next = (*curr).next;
+                (*curr).next = ptr::null_mut();
+
+                let mut carry = curr;
+                let mut i = 0;
+                while !bins[i].is_null() {
+                    carry = Self::merge_runs(bins[i], carry, &mut cmp);
+                    bins[i] = ptr::null_mut();
+                    i += 1;
+                }
+                bins[i] = carry;
+
+                curr = next;
+            }
+
+            let mut sorted = ptr::null_mut();
+            for bin in bins {
+                if !bin.is_null() {
+                    sorted = Self::merge_runs(bin, sorted, &mut cmp);
+                }
+            }
+
+            self.relink(sorted, len);
+        }
+    }
+
+    /// Merge the sorted `other` into this sorted list by splicing its nodes
+    ///
+    /// Equal elements from `self` stay ahead of those from `other`. If `cmp`
+    /// panics, both lists' elements are leaked.
+    pub fn merge(&mut self, mut other: LinkedList<T>, mut cmp: impl FnMut(&T, &T) -> Ordering) {
+        let (ours, our_len) = self.unlink_all();
+        let (theirs, their_len) = other.unlink_all();
+
+        unsafe {
+            let merged = Self::merge_runs(ours, theirs, &mut cmp);
+            self.relink(merged, our_len + their_len);
+        }
+    }
+
+    /// Merge two sorted lists into one by relinking their nodes, in `O(n + m)`
+    /// and without allocating
+    pub fn merge_sorted(mut self, other: LinkedList<T>) -> LinkedList<T>
+    where
+        T: Ord,
+    {
+        self.merge(other, T::cmp);
+        self
+    }
+
+    /// Remove every element for which `same(current, previous)` is true,
+    /// where `previous` is the last element kept before it; the same order
+    /// as `Vec::dedup_by`
+    pub fn dedup_by(&mut self, mut same: impl FnMut(&T, &T) -> bool) {
+        if self.head.is_null() {
+            return;
+        }
+
+        unsafe {
+            let mut kept = self.head;
+
+            while !(*kept).next.is_null() {
+                let curr = (*kept).next;
+
+                if !same(&(*curr).data, &(*kept).data) {
+                    kept = curr;
+                    continue;
+                }
+
+                let next = (*curr).next;
+                (*kept).next = next;
+                if next.is_null() {
+                    self.tail = kept;
+                } else {
+                    (*next).prev = kept;
+                }
+                self.len -= 1;
+
+                drop(Box::from_raw(curr));
+            }
+        }
+    }
+
+    // Detach every node, returning the head of the `next` chain and its length.
+    fn unlink_all(&mut self) -> (*mut Node<T>, usize) {
+        let chain = (self.head, self.len);
+
+        self.head = ptr::null_mut();
+        self.tail = ptr::null_mut();
+        self.len = 0;
+
+        chain
+    }
+
+    // Take ownership of a `next` chain of `len` nodes, restoring the `prev`
+    // links and the tail.
+    unsafe fn relink(&mut self, head: *mut Node<T>, len: usize) {
+        let mut prev = ptr::null_mut();
+        let mut curr = head;
+
+        while !curr.is_null() {
+            (*curr).prev = prev;
+            prev = curr;
+            curr = (*curr).next;
+        }
+
+        self.head = head;
+        self.tail = prev;
+        self.len = len;
+    }
+
+    // Merge two sorted `next` chains, preferring `a` on ties so the merge is stable.
+    unsafe fn merge_runs(
+        mut a: *mut Node<T>,
+        mut b: *mut Node<T>,
+        cmp: &mut impl FnMut(&T, &T) -> Ordering,
+    ) -> *mut Node<T> {
+        let mut head = ptr::null_mut();
+        let mut tail: *mut Node<T> = ptr::null_mut();
+
+        while !a.is_null() && !b.is_null() {
+            let taken = if cmp(&(*b).data, &(*a).data) == Ordering::Less {
+                let taken = b;
+                b = (*b).next;
+                taken
+            } else {
+                let taken = a;
+                a = (*a).next;
+                taken
+            };
+
+            if tail.is_null() {
+                head = taken;
+            } else {
+                (*tail).next = taken;
+            }
+            tail = taken;
+        }
+
+        let rest = if a.is_null() { b } else { a };
+
+        if tail.is_null() {
+            rest
+        } else {
+            (*tail).next = rest;
+            head
+        }
+    }
 }
 
 // the cursor is expected to act as if it is at the position of an element
@@ -80,6 +311,62 @@ impl<T> Cursor<'_, T> {
         unsafe { Some(&mut (*self.curr).data) }
     }
 
+    /// Return the 0-based index of the current element counted from the front,
+    /// or 0 for a cursor on an empty list
+    pub fn position_from_front(&self) -> usize {
+        Self::hops(self.curr, |node| unsafe { (*node).prev })
+    }
+
+    /// Return the 0-based index of the current element counted from the back,
+    /// or 0 for a cursor on an empty list
+    pub fn position_from_back(&self) -> usize {
+        Self::hops(self.curr, |node| unsafe { (*node).next })
+    }
+
+    // Count how many times `step` can be followed from `node` before reaching the end.
+    fn hops(mut node: *mut Node<T>, step: impl Fn(*mut Node<T>) -> *mut Node<T>) -> usize {
+        let mut hops = 0;
+
+        if node.is_null() {
+            return hops;
+        }
+
+        while !step(node).is_null() {
+            node = step(node);
+            hops += 1;
+        }
+
+        hops
+    }
+
+    /// Swap the current element with the front one and follow it there
+    ///
+    /// Only the two values are exchanged, in O(1), so the cursor ends up on
+    /// the front node, which now holds the element it was on.
+    pub fn swap_with_front(&mut self) {
+        let head = self.list.head;
+        self.swap_with(head);
+    }
+
+    /// Swap the current element with the back one and follow it there
+    pub fn swap_with_back(&mut self) {
+        let tail = self.list.tail;
+        self.swap_with(tail);
+    }
+
+    // Exchange the current value with the one in `other` and move onto `other`.
+    fn swap_with(&mut self, other: *mut Node<T>) {
+        if self.curr.is_null() || self.curr == other {
+            return;
+        }
+
+        unsafe {
+            mem::swap(&mut (*self.curr).data, &mut (*other).data);
+        }
+
+        self.curr = other;
+    }
+
     /// Move one position forward (towards the back) and
     /// return a reference to the new position
     #[allow(clippy::should_implement_trait)]
@@ -145,10 +432,10 @@ impl<T> Cursor<'_, T> {
 
             self.list.len -= 1;
 
-            let data = std::ptr::read(&(*curr_ptr).data);
-            drop(Box::from_raw(curr_ptr));
+            // Moving the data out of the box frees the node without dropping the data twice.
+            let node = Box::from_raw(curr_ptr);
 
-            Some(data)
+            Some(node.data)
         }
     }
 
@@ -475,6 +762,422 @@ fn cursor_take() {
     }
 }
 
+#[test]
+fn cursor_position() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+
+    let mut cursor = list.cursor_front();
+    assert_eq!(cursor.position_from_front(), 0);
+    assert_eq!(cursor.position_from_back(), 9);
+
+    cursor.next();
+    cursor.next();
+    assert_eq!(cursor.position_from_front(), 2);
+    assert_eq!(cursor.position_from_back(), 7);
+
+    // Taking moves the cursor to the next element, which slides into the same index.
+    assert_eq!(cursor.take(), Some(2));
+    assert_eq!(cursor.peek_mut(), Some(&mut 3));
+    assert_eq!(cursor.position_from_front(), 2);
+    assert_eq!(cursor.position_from_back(), 6);
+
+    let mut cursor = list.cursor_back();
+    assert_eq!(cursor.position_from_front(), 8);
+    assert_eq!(cursor.position_from_back(), 0);
+
+    // Taking the back moves the cursor to the new back.
+    assert_eq!(cursor.take(), Some(9));
+    assert_eq!(cursor.position_from_front(), 7);
+    assert_eq!(cursor.position_from_back(), 0);
+
+    let mut list = (0..10).collect::<LinkedList<_>>();
+    assert_eq!(list.cursor_back().position_from_front(), 9);
+}
+
+#[test]
+fn cursor_position_on_empty_list() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    assert_eq!(list.cursor_front().position_from_front(), 0);
+    assert_eq!(list.cursor_back().position_from_back(), 0);
+}
+
+#[test]
+fn cursor_swap_with_front_from_middle() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    cursor.seek_forward(2);
+    cursor.swap_with_front();
+
+    assert_eq!(cursor.peek_mut(), Some(&mut 2));
+    assert_eq!(cursor.position_from_front(), 0);
+    assert!([2, 1, 0, 3, 4].iter().eq(list.iter()));
+}
+
+#[test]
+fn cursor_swap_with_front_from_back() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_back();
+    cursor.swap_with_front();
+
+    assert_eq!(cursor.next(), Some(&mut 1));
+    assert!([4, 1, 2, 3, 0].iter().eq(list.iter()));
+}
+
+#[test]
+fn cursor_swap_with_back() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    cursor.next();
+    cursor.swap_with_back();
+
+    assert_eq!(cursor.peek_mut(), Some(&mut 1));
+    assert_eq!(cursor.position_from_back(), 0);
+    assert!([0, 4, 2, 3, 1].iter().eq(list.iter()));
+}
+
+#[test]
+fn cursor_swap_in_place_is_a_no_op() {
+    let mut list = (0..3)
+        .map(|i| std::format!("{i}"))
+        .collect::<LinkedList<_>>();
+    list.cursor_front().swap_with_front();
+    list.cursor_back().swap_with_back();
+    assert!(["0", "1", "2"].iter().eq(list.iter()));
+
+    let mut empty: LinkedList<i32> = LinkedList::new();
+    let mut cursor = empty.cursor_front();
+    cursor.swap_with_front();
+    cursor.swap_with_back();
+    assert_eq!(cursor.peek_mut(), None);
+}
+
+// ———————————————————————————————————————————————————————————
+// Tests for rotation
+// ———————————————————————————————————————————————————————————
+
+#[test]
+fn rotate_left() {
+    let mut list = (1..=5).collect::<LinkedList<_>>();
+    list.rotate_left(2);
+    assert!([3, 4, 5, 1, 2].iter().eq(list.iter()));
+    assert_eq!(list.front(), Some(&3));
+    assert_eq!(list.back(), Some(&2));
+    assert_eq!(list.len(), 5);
+}
+
+#[test]
+fn rotate_right() {
+    let mut list = (1..=5).collect::<LinkedList<_>>();
+    list.rotate_right(1);
+    assert!([5, 1, 2, 3, 4].iter().eq(list.iter()));
+    assert_eq!(list.pop_back(), Some(4));
+    assert_eq!(list.pop_front(), Some(5));
+}
+
+#[test]
+fn rotate_by_len_is_a_no_op() {
+    let mut list = (1..=5).collect::<LinkedList<_>>();
+    list.rotate_left(5);
+    list.rotate_right(5);
+    list.rotate_left(0);
+    assert!((1..=5).eq(list.iter().copied()));
+}
+
+#[test]
+fn rotate_empty_and_single_element_lists() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    list.rotate_left(3);
+    list.rotate_right(3);
+    assert!(list.is_empty());
+
+    list.push_back(1);
+    list.rotate_left(3);
+    list.rotate_right(7);
+    assert_eq!(list.front(), Some(&1));
+    assert_eq!(list.back(), Some(&1));
+}
+
+#[test]
+fn rotate_uses_modulo() {
+    let mut list = (1..=5).collect::<LinkedList<_>>();
+    list.rotate_left(12);
+    assert!([3, 4, 5, 1, 2].iter().eq(list.iter()));
+    list.rotate_right(12);
+    assert!((1..=5).eq(list.iter().copied()));
+}
+
+#[test]
+fn rotate_keeps_links_consistent_in_both_directions() {
+    for n in 0..7 {
+        let mut list = (0..6).collect::<LinkedList<_>>();
+        list.rotate_left(n);
+
+        let mut cursor = list.cursor_back();
+        let mut backwards = std::vec![*cursor.peek_mut().unwrap()];
+        while let Some(&mut value) = cursor.prev() {
+            backwards.push(value);
+        }
+        backwards.reverse();
+
+        let expected = (0..6).map(|i| (i + n) % 6).collect::<std::vec::Vec<_>>();
+        assert_eq!(backwards, expected);
+        assert!(expected.iter().eq(list.iter()));
+    }
+}
+
+// ———————————————————————————————————————————————————————————
+// Tests for drain
+// ———————————————————————————————————————————————————————————
+
+#[test]
+fn drain_middle() {
+    let mut list = (1..=7).collect::<LinkedList<_>>();
+    let drained = list.drain(2..5).collect::<std::vec::Vec<_>>();
+
+    assert_eq!(drained, [3, 4, 5]);
+    assert_eq!(list.len(), 4);
+    assert!([1, 2, 6, 7].iter().eq(list.iter()));
+    assert_eq!(list.pop_back(), Some(7));
+    assert_eq!(list.pop_front(), Some(1));
+}
+
+#[test]
+fn drain_range_forms() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+
+    assert!((7..10).eq(list.drain(7..)));
+    assert!((0..2).eq(list.drain(..2)));
+    assert!((2..4).eq(list.drain(..=1)));
+    assert_eq!(list.drain(1..1).next(), None);
+    assert!([4, 5, 6].iter().eq(list.iter()));
+
+    assert!((4..7).eq(list.drain(..)));
+    assert!(list.is_empty());
+    assert_eq!(list.back(), None);
+
+    list.push_back(1);
+    list.push_front(0);
+    assert!([0, 1].iter().eq(list.iter()));
+}
+
+#[test]
+fn drain_size_hint() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut drain = list.drain(1..4);
+
+    assert_eq!(drain.size_hint(), (3, Some(3)));
+    drain.next();
+    assert_eq!(drain.len(), 2);
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let mut list = (0..3).collect::<LinkedList<_>>();
+    list.drain(1..4);
+}
+
+#[test]
+fn drain_dropped_mid_iteration() {
+    use std::cell::Cell;
+    struct DropCounter<'a>(&'a Cell<usize>, usize);
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let counter = Cell::new(0);
+    let mut list = (0..7)
+        .map(|i| DropCounter(&counter, i))
+        .collect::<LinkedList<_>>();
+
+    {
+        let mut drain = list.drain(1..6);
+        assert_eq!(drain.next().map(|counter| counter.1), Some(1));
+        assert_eq!(counter.get(), 1);
+    }
+
+    // The four elements the drain never yielded are dropped along with it.
+    assert_eq!(counter.get(), 5);
+    assert_eq!(list.len(), 2);
+    assert!([0, 6].iter().eq(list.iter().map(|counter| &counter.1)));
+
+    drop(list);
+    assert_eq!(counter.get(), 7);
+}
+
+// ———————————————————————————————————————————————————————————
+// Tests for sort, merge and dedup
+// ———————————————————————————————————————————————————————————
+
+// Walk the list both ways, checking that the links agree with each other and
+// with `len`, then return the elements from front to back.
+#[cfg(test)]
+fn checked_elements<T: Clone>(list: &LinkedList<T>) -> std::vec::Vec<T> {
+    let mut forward = std::vec::Vec::new();
+    let mut prev = ptr::null_mut();
+    let mut curr = list.head;
+
+    unsafe {
+        while !curr.is_null() {
+            assert_eq!((*curr).prev, prev, "prev link out of sync");
+            forward.push((*curr).data.clone());
+            prev = curr;
+            curr = (*curr).next;
+        }
+    }
+
+    assert_eq!(list.tail, prev, "tail is not the last node");
+    assert_eq!(list.len(), forward.len(), "len is out of sync");
+
+    forward
+}
+
+#[test]
+fn sort_reverse_sorted() {
+    let mut list = (0..100).rev().collect::<LinkedList<_>>();
+    list.sort_by(|a, b| a.cmp(b));
+
+    assert_eq!(
+        checked_elements(&list),
+        (0..100).collect::<std::vec::Vec<_>>()
+    );
+    assert_eq!(list.front(), Some(&0));
+    assert_eq!(list.back(), Some(&99));
+}
+
+#[test]
+fn sort_empty_and_single_element_lists() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    list.sort_by(|a, b| a.cmp(b));
+    assert!(checked_elements(&list).is_empty());
+
+    list.push_back(1);
+    list.sort_by(|a, b| a.cmp(b));
+    assert_eq!(checked_elements(&list), [1]);
+}
+
+#[test]
+fn sort_is_stable() {
+    // Sort by the key only; the payload records the original order.
+    let mut list = [3, 1, 2, 3, 1, 2, 1, 3]
+        .into_iter()
+        .enumerate()
+        .map(|(payload, key)| (key, payload))
+        .collect::<LinkedList<_>>();
+    list.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(
+        checked_elements(&list),
+        [
+            (1, 1),
+            (1, 4),
+            (1, 6),
+            (2, 2),
+            (2, 5),
+            (3, 0),
+            (3, 3),
+            (3, 7)
+        ]
+    );
+}
+
+#[test]
+fn sort_large_list() {
+    let mut list = (0..2_000_000).rev().collect::<LinkedList<i32>>();
+    list.sort_by(|a, b| a.cmp(b));
+
+    assert_eq!(list.len(), 2_000_000);
+    assert!((0..2_000_000).eq(list.iter().copied()));
+}
+
+#[test]
+fn merge_overlapping_ranges() {
+    let mut list = (0..10).step_by(2).collect::<LinkedList<_>>();
+    list.merge((5..10).collect(), |a, b| a.cmp(b));
+
+    assert_eq!(checked_elements(&list), [0, 2, 4, 5, 6, 6, 7, 8, 8, 9]);
+}
+
+#[test]
+fn merge_keeps_own_elements_first_on_ties() {
+    let mut list = [(1, 'a'), (2, 'a')].into_iter().collect::<LinkedList<_>>();
+    let other = [(1, 'b'), (2, 'b'), (3, 'b')].into_iter().collect();
+    list.merge(other, |a, b| a.0.cmp(&b.0));
+
+    assert_eq!(
+        checked_elements(&list),
+        [(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b'), (3, 'b')]
+    );
+}
+
+#[test]
+fn merge_with_empty_lists() {
+    let mut list = LinkedList::new();
+    list.merge((0..3).collect(), |a, b| a.cmp(b));
+    assert_eq!(checked_elements(&list), [0, 1, 2]);
+
+    list.merge(LinkedList::new(), |a, b| a.cmp(b));
+    assert_eq!(checked_elements(&list), [0, 1, 2]);
+}
+
+#[test]
+fn merge_sorted_matches_sorted_vec() {
+    let cases: [(&[i32], &[i32]); 5] = [
+        (&[1, 2, 3], &[7, 8, 9]),
+        (&[7, 8, 9], &[1, 2, 3]),
+        (&[1, 4, 6, 9], &[2, 4, 5, 10, 11]),
+        (&[], &[3, 5]),
+        (&[4], &[2]),
+    ];
+
+    for (left, right) in cases {
+        let merged = left
+            .iter()
+            .copied()
+            .collect::<LinkedList<_>>()
+            .merge_sorted(right.iter().copied().collect());
+
+        let mut expected = [left, right].concat();
+        expected.sort();
+        assert_eq!(checked_elements(&merged), expected);
+    }
+}
+
+#[test]
+fn dedup_runs_at_head_and_tail() {
+    let mut list = [1, 1, 1, 2, 3, 3, 2, 4, 4, 4]
+        .into_iter()
+        .collect::<LinkedList<_>>();
+    list.dedup_by(|a, b| a == b);
+
+    assert_eq!(checked_elements(&list), [1, 2, 3, 2, 4]);
+    assert_eq!(list.back(), Some(&4));
+}
+
+#[test]
+fn dedup_compares_against_the_kept_element() {
+    let mut list = [1, 2, 3, 10, 11, 20].into_iter().collect::<LinkedList<_>>();
+    list.dedup_by(|curr, kept| curr - kept < 5);
+
+    let mut values = vec![1, 2, 3, 10, 11, 20];
+    values.dedup_by(|curr, kept| *curr - *kept < 5);
+
+    assert_eq!(checked_elements(&list), [1, 10, 20]);
+    assert_eq!(checked_elements(&list), values);
+}
+
+#[test]
+fn dedup_down_to_one_element() {
+    let mut list = [7; 5].into_iter().collect::<LinkedList<_>>();
+    list.dedup_by(|a, b| a == b);
+
+    assert_eq!(checked_elements(&list), [7]);
+    assert_eq!(list.front(), list.back());
+}
+
 // ———————————————————————————————————————————————————————————
 // Tests for Step 4: clean-up via `Drop`
 // ———————————————————————————————————————————————————————————
@@ -515,6 +1218,63 @@ fn drop_large_list() {
     drop((0..2_000_000).collect::<LinkedList<i32>>());
 }
 
+#[test]
+fn from_vec_into_vec_round_trip() {
+    assert_eq!(
+        LinkedList::from_vec(std::vec![1, 2, 3]).into_vec(),
+        std::vec![1, 2, 3]
+    );
+
+    let v: std::vec::Vec<i32> = (0..100).rev().collect();
+    assert_eq!(LinkedList::from_vec(v.clone()).into_vec(), v);
+}
+
+#[test]
+fn from_empty_vec() {
+    let list = LinkedList::<i32>::from_vec(std::vec![]);
+    assert!(list.is_empty());
+    assert_eq!(list.front(), None);
+    assert_eq!(list.into_vec(), std::vec::Vec::<i32>::new());
+}
+
+#[test]
+fn from_vec_keeps_order() {
+    let list = LinkedList::from_vec(std::vec!["a", "b", "c"]);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.front(), Some(&"a"));
+    assert_eq!(list.back(), Some(&"c"));
+    assert!(list.iter().eq(["a", "b", "c"].iter()));
+}
+
+#[test]
+fn from_vec_then_modify_into_vec() {
+    let mut list = LinkedList::from_vec(std::vec![2, 3, 4]);
+    list.push_front(1);
+    list.push_back(5);
+    assert_eq!(list.pop_back(), Some(5));
+    list.push_back(6);
+    {
+        let mut cursor = list.cursor_front();
+        cursor.seek_forward(2);
+        assert_eq!(cursor.take(), Some(3));
+    }
+    assert_eq!(list.into_vec(), std::vec![1, 2, 4, 6]);
+}
+
+#[test]
+fn into_vec_moves_every_element_once() {
+    use std::rc::Rc;
+
+    let value = Rc::new(());
+    let list = LinkedList::from_vec(std::vec![Rc::clone(&value), Rc::clone(&value)]);
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    let v = list.into_vec();
+    assert_eq!(Rc::strong_count(&value), 3);
+    drop(v);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
 // ———————————————————————————————————————————————————————————
 // Tests for Step 5 (advanced): covariance and Send/Sync
 // ———————————————————————————————————————————————————————————
@@ -544,3 +1304,23 @@ fn advanced_is_covariant() {
         i
     }
 }
+
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_smoke {
+    use alloc::vec::Vec;
+
+    use crate::LinkedList;
+
+    #[test]
+    fn push_pop_and_iter_through_alloc() {
+        let mut list = LinkedList::new();
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.len(), 1);
+    }
+}
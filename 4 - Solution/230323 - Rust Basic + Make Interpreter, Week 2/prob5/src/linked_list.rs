@@ -1,4 +1,7 @@
-use crate::{Cursor, LinkedList};
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+
+use crate::{Cursor, Drain, LinkedList};
 
 impl<T> LinkedList<T> {
     pub fn push_back(&mut self, element: T) {
@@ -24,9 +27,69 @@ impl<T> LinkedList<T> {
     pub fn back(&self) -> Option<&T> {
         self.iter().last()
     }
+
+    /// Remove the elements in `range` and return them from front to back.
+    ///
+    /// Elements that the returned iterator doesn't yield are removed when it's dropped.
+    ///
+    /// Panics if the range is out of bounds or its start is past its end.
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> Drain<'_, T> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+
+        assert!(start <= end, "drain start {start} is past its end {end}");
+        assert!(
+            end <= self.len(),
+            "drain end {end} is out of bounds for a list of length {}",
+            self.len()
+        );
+
+        let mut cursor = self.cursor_front();
+        cursor.seek_forward(start);
+
+        Drain {
+            cursor,
+            remaining: end - start,
+        }
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // Each element after the first drained one moves up to the cursor once
+        // its predecessor is taken; the tail is only ever the last one taken.
+        self.remaining -= 1;
+        self.cursor.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
-impl<T> std::iter::FromIterator<T> for LinkedList<T> {
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<T> core::iter::FromIterator<T> for LinkedList<T> {
     fn from_iter<I>(iter: I) -> Self
     where
         I: IntoIterator<Item = T>,
@@ -39,6 +102,23 @@ impl<T> std::iter::FromIterator<T> for LinkedList<T> {
     }
 }
 
+impl<T> LinkedList<T> {
+    /// Build a list holding the elements of `v` in the same order. The only
+    /// allocations are the nodes; `v`'s buffer is freed once it's emptied.
+    pub fn from_vec(v: Vec<T>) -> Self {
+        v.into_iter().collect()
+    }
+
+    /// Move the elements into a `Vec`, front first.
+    pub fn into_vec(mut self) -> Vec<T> {
+        let mut v = Vec::with_capacity(self.len());
+        while let Some(elem) = self.pop_front() {
+            v.push(elem);
+        }
+        v
+    }
+}
+
 // seek methods, return false if end of list is reached prematurely
 impl<T> Cursor<'_, T> {
     pub fn seek_forward(&mut self, n: usize) -> bool {
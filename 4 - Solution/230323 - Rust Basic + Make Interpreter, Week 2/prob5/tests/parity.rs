@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use prob5::LinkedList;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::{RngSeed, TestRunner};
+
+/// Every operation the parity harness knows about.
+///
+/// New list features need a variant here, an entry in `Op::any` and an
+/// arm in `Op::apply`.
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack,
+    Front,
+    Back,
+    Len,
+    Iter,
+    RotateLeft(usize),
+    RotateRight(usize),
+    Sort,
+    Dedup,
+}
+
+impl Op {
+    /// Every operation, each as likely as the others.
+    fn any() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (0..1000).prop_map(Op::PushFront),
+            (0..1000).prop_map(Op::PushBack),
+            Just(Op::PopFront),
+            Just(Op::PopBack),
+            Just(Op::Front),
+            Just(Op::Back),
+            Just(Op::Len),
+            Just(Op::Iter),
+            (0..1000_usize).prop_map(Op::RotateLeft),
+            (0..1000_usize).prop_map(Op::RotateRight),
+            Just(Op::Sort),
+            Just(Op::Dedup),
+        ]
+    }
+
+    /// Apply the operation to both collections and compare what
+    /// each of them can observe.
+    fn apply(self, list: &mut LinkedList<i32>, deque: &mut VecDeque<i32>) {
+        match self {
+            Op::PushFront(value) => {
+                list.push_front(value);
+                deque.push_front(value);
+            }
+            Op::PushBack(value) => {
+                list.push_back(value);
+                deque.push_back(value);
+            }
+            Op::PopFront => assert_eq!(list.pop_front(), deque.pop_front()),
+            Op::PopBack => assert_eq!(list.pop_back(), deque.pop_back()),
+            Op::Front => assert_eq!(list.front(), deque.front()),
+            Op::Back => assert_eq!(list.back(), deque.back()),
+            Op::Len => {
+                assert_eq!(list.len(), deque.len());
+                assert_eq!(list.is_empty(), deque.is_empty());
+            }
+            Op::Iter => assert!(list.iter().eq(deque.iter())),
+            Op::RotateLeft(n) => {
+                list.rotate_left(n);
+                if !deque.is_empty() {
+                    deque.rotate_left(n % deque.len());
+                }
+            }
+            Op::RotateRight(n) => {
+                list.rotate_right(n);
+                if !deque.is_empty() {
+                    deque.rotate_right(n % deque.len());
+                }
+            }
+            Op::Sort => {
+                list.sort_by(|a, b| a.cmp(b));
+                deque.make_contiguous().sort();
+            }
+            Op::Dedup => {
+                list.dedup_by(|a, b| a == b);
+                let mut values = deque.drain(..).collect::<Vec<_>>();
+                values.dedup();
+                deque.extend(values);
+            }
+        }
+    }
+}
+
+fn run_parity(ops: impl IntoIterator<Item = Op>, check_every: usize) {
+    let mut list = LinkedList::new();
+    let mut deque = VecDeque::new();
+
+    for (step, op) in ops.into_iter().enumerate() {
+        op.apply(&mut list, &mut deque);
+
+        if step % check_every == 0 {
+            assert_eq!(list.len(), deque.len(), "step {step}, op {op:?}");
+            assert!(list.iter().eq(deque.iter()), "step {step}, op {op:?}");
+        }
+    }
+
+    assert_eq!(list.len(), deque.len());
+    assert!(list.iter().eq(deque.iter()));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 20,
+        rng_seed: RngSeed::Fixed(601),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn parity_with_vec_deque(ops in prop::collection::vec(Op::any(), 2_000)) {
+        run_parity(ops, 1);
+    }
+}
+
+#[test]
+fn parity_with_vec_deque_grows_and_shrinks() {
+    let mut list = LinkedList::new();
+    let mut deque = VecDeque::new();
+
+    for value in 0..100 {
+        Op::PushBack(value).apply(&mut list, &mut deque);
+        Op::PushFront(-value).apply(&mut list, &mut deque);
+    }
+
+    Op::Iter.apply(&mut list, &mut deque);
+
+    for _ in 0..250 {
+        Op::PopFront.apply(&mut list, &mut deque);
+        Op::PopBack.apply(&mut list, &mut deque);
+        Op::Len.apply(&mut list, &mut deque);
+    }
+}
+
+#[test]
+#[ignore]
+fn parity_with_vec_deque_stress() {
+    // Drawn one at a time, since a strategy for millions of operations
+    // would keep all of them around for shrinking.
+    let mut runner = TestRunner::deterministic();
+    let op = Op::any();
+    let ops = std::iter::repeat_with(|| op.new_tree(&mut runner).unwrap().current());
+
+    run_parity(ops.take(5_000_000), 10_000);
+}
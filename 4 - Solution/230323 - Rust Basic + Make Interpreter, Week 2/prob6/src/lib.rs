@@ -1,10 +1,20 @@
 use std::{
     cmp::{Ord, Ordering, PartialOrd},
     collections::HashMap,
+    fmt,
 };
 
+#[cfg(test)]
+use std::{cell::Cell, collections::HashSet};
+
+#[cfg(test)]
+thread_local! {
+    /// How many times `Hand::get_category` ran on this thread.
+    static CATEGORY_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
-enum Rank {
+pub enum Rank {
     Two,
     Three,
     Four,
@@ -20,14 +30,96 @@ enum Rank {
     Ace,
 }
 
+impl Rank {
+    fn plural(&self) -> &'static str {
+        match self {
+            Rank::Two => "Twos",
+            Rank::Three => "Threes",
+            Rank::Four => "Fours",
+            Rank::Five => "Fives",
+            Rank::Six => "Sixes",
+            Rank::Seven => "Sevens",
+            Rank::Eight => "Eights",
+            Rank::Nine => "Nines",
+            Rank::Ten => "Tens",
+            Rank::Jack => "Jacks",
+            Rank::Queen => "Queens",
+            Rank::King => "Kings",
+            Rank::Ace => "Aces",
+        }
+    }
+
+    /// The rank as written in a card, such as `T` for a ten.
+    pub fn symbol(&self) -> char {
+        match self {
+            Rank::Two => '2',
+            Rank::Three => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+        }
+    }
+
+    fn with_article(&self) -> String {
+        match self {
+            Rank::Eight | Rank::Ace => format!("an {self}"),
+            _ => format!("a {self}"),
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum Suit {
+pub enum Suit {
     Diamond,
     Club,
     Heart,
     Spade,
 }
 
+/// The suit's letter, as written in a card.
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match self {
+            Suit::Diamond => "D",
+            Suit::Club => "C",
+            Suit::Heart => "H",
+            Suit::Spade => "S",
+        };
+
+        write!(f, "{letter}")
+    }
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum Category {
@@ -42,12 +134,160 @@ enum Category {
     StraightFlush(Rank),
 }
 
+impl Category {
+    fn order(&self) -> u8 {
+        match self {
+            Category::HighCard(_) => 1,
+            Category::OnePair(_, _, _, _) => 2,
+            Category::TwoPair(_, _, _) => 3,
+            Category::ThreeOfAKind(_, _, _) => 4,
+            Category::Straight(_) => 5,
+            Category::Flush(_) => 6,
+            Category::FullHouse(_, _) => 7,
+            Category::FourOfAKind(_, _) => 8,
+            Category::StraightFlush(_) => 9,
+        }
+    }
+
+    /// Packs the category and its tie-breakers into one number, so that
+    /// comparing the scores of two hands compares the hands.
+    fn score(&self) -> u32 {
+        // Ranks take 4 bits each and no category has more than 5 tie-breakers.
+        let tie_breakers = self.tie_breakers();
+        let padding = 4 * (5 - tie_breakers.len() as u32);
+        let ranks = tie_breakers
+            .into_iter()
+            .fold(0, |score, (_, rank)| score << 4 | (rank as u32 + 1));
+
+        (self.order() as u32) << 20 | ranks << padding
+    }
+
+    /// The ranks compared to break a tie between two hands of this category,
+    /// most significant first, each with a name for explanations.
+    fn tie_breakers(&self) -> Vec<(&'static str, Rank)> {
+        match self {
+            Category::HighCard(hand) | Category::Flush(hand) => {
+                let labels = [
+                    "highest card",
+                    "second card",
+                    "third card",
+                    "fourth card",
+                    "fifth card",
+                ];
+                labels.into_iter().zip(hand.ranks_descending()).collect()
+            }
+            Category::OnePair(pair, kicker1, kicker2, kicker3) => vec![
+                ("pair", *pair),
+                ("first kicker", *kicker1),
+                ("second kicker", *kicker2),
+                ("third kicker", *kicker3),
+            ],
+            Category::TwoPair(high, low, kicker) => {
+                vec![
+                    ("high pair", *high),
+                    ("low pair", *low),
+                    ("kicker", *kicker),
+                ]
+            }
+            Category::ThreeOfAKind(three, kicker1, kicker2) => vec![
+                ("three of a kind", *three),
+                ("first kicker", *kicker1),
+                ("second kicker", *kicker2),
+            ],
+            Category::Straight(high) | Category::StraightFlush(high) => vec![("high card", *high)],
+            Category::FullHouse(three, pair) => vec![("three of a kind", *three), ("pair", *pair)],
+            Category::FourOfAKind(four, kicker) => {
+                vec![("four of a kind", *four), ("kicker", *kicker)]
+            }
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Category::HighCard(hand) => write!(f, "High Card, {} high", hand.ranks_descending()[0]),
+            Category::OnePair(pair, kicker, _, _) => write!(
+                f,
+                "One Pair, {} with {} kicker",
+                pair.plural(),
+                kicker.with_article()
+            ),
+            Category::TwoPair(high, low, kicker) => write!(
+                f,
+                "Two Pair, {} and {} with {} kicker",
+                high.plural(),
+                low.plural(),
+                kicker.with_article()
+            ),
+            Category::ThreeOfAKind(three, kicker, _) => write!(
+                f,
+                "Three of a Kind, {} with {} kicker",
+                three.plural(),
+                kicker.with_article()
+            ),
+            Category::Straight(high) => write!(f, "Straight, {high} high"),
+            Category::Flush(hand) => write!(f, "Flush, {} high", hand.ranks_descending()[0]),
+            Category::FullHouse(three, pair) => {
+                write!(f, "Full House, {} over {}", three.plural(), pair.plural())
+            }
+            Category::FourOfAKind(four, kicker) => write!(
+                f,
+                "Four of a Kind, {} with {} kicker",
+                four.plural(),
+                kicker.with_article()
+            ),
+            Category::StraightFlush(high) => write!(f, "Straight Flush, {high} high"),
+        }
+    }
+}
+
+/// Why a hand string could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandParseError {
+    InvalidCard(String),
+    WrongCardCount(usize),
+}
+
+impl fmt::Display for HandParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandParseError::InvalidCard(card) => write!(f, "invalid card \"{card}\""),
+            HandParseError::WrongCardCount(count) => {
+                write!(f, "a hand needs 5 cards, but {count} were given")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct Card {
+pub struct Card {
     rank: Rank,
     suit: Suit,
 }
 
+impl Card {
+    pub fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    pub fn suit(&self) -> &Suit {
+        &self.suit
+    }
+}
+
+/// The card as `Hand::parse` reads it, such as `TH`. The alternate form,
+/// `{:#}`, writes a ten as `10H` instead.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() && self.rank == Rank::Ten {
+            write!(f, "10{}", self.suit)
+        } else {
+            write!(f, "{}{}", self.rank.symbol(), self.suit)
+        }
+    }
+}
+
 impl Ord for Card {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank.cmp(&other.rank)
@@ -60,31 +300,99 @@ impl PartialOrd for Card {
     }
 }
 
+/// Which game the hands are compared for. Both variants rank hands the
+/// same way apart from `ace_low_straights`; `low_hand_wins` then decides
+/// which end of that ranking takes the pot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Whether A-2-3-4-5 is a straight, five high. Without it the ace only
+    /// ever plays high, so that hand is just ace high.
+    pub ace_low_straights: bool,
+    /// Whether the lowest hand wins instead of the highest, which makes
+    /// straights and flushes count against the hand.
+    pub low_hand_wins: bool,
+}
+
+impl Rules {
+    pub const STANDARD: Rules = Rules {
+        ace_low_straights: true,
+        low_hand_wins: false,
+    };
+
+    /// 2-7 lowball, where 7-5-4-3-2 of mixed suits is the best hand.
+    pub const DEUCE_TO_SEVEN_LOWBALL: Rules = Rules {
+        ace_low_straights: false,
+        low_hand_wins: true,
+    };
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::STANDARD
+    }
+}
+
+/// A parsed poker hand. How its cards are stored is an implementation
+/// detail, so they can only be read through `iter_cards`:
+///
+/// ```compile_fail
+/// fn cards(hand: &prob6::Hand) {
+///     let _ = &hand.cards;
+/// }
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Hand {
     cards: Vec<Card>,
 }
 
+/// The cards from the lowest rank to the highest, separated by spaces.
+/// `{:#}` writes tens as `10` like `Card` does.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            if f.alternate() {
+                write!(f, "{card:#}")?;
+            } else {
+                write!(f, "{card}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Hand {
     fn new(hand: &str) -> Self {
+        match Self::parse(hand) {
+            Ok(hand) => hand,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn parse(hand: &str) -> Result<Self, HandParseError> {
         let mut cards = Vec::new();
 
         for card in hand.split_whitespace() {
-            let mut card = String::from(card);
-
-            if card.starts_with("10") {
-                card = card.replace("10", "T");
-            }
+            let invalid = || HandParseError::InvalidCard(String::from(card));
+            let (rank, suit) = match card.strip_prefix("10") {
+                Some(suit) => ("T", suit),
+                None if card.is_char_boundary(1) => card.split_at(1),
+                None => return Err(invalid()),
+            };
 
-            let suit = match &card[1..2] {
+            let suit = match suit {
                 "D" => Suit::Diamond,
                 "C" => Suit::Club,
                 "H" => Suit::Heart,
                 "S" => Suit::Spade,
-                _ => panic!("Invalid suit"),
+                _ => return Err(invalid()),
             };
 
-            let rank = match &card[0..1] {
+            let rank = match rank {
                 "2" => Rank::Two,
                 "3" => Rank::Three,
                 "4" => Rank::Four,
@@ -98,18 +406,46 @@ impl Hand {
                 "Q" => Rank::Queen,
                 "K" => Rank::King,
                 "A" => Rank::Ace,
-                _ => panic!("Invalid rank"),
+                _ => return Err(invalid()),
             };
 
             cards.push(Card { rank, suit });
         }
 
+        if cards.len() != 5 {
+            return Err(HandParseError::WrongCardCount(cards.len()));
+        }
+
         cards.sort_by(|a, b| a.rank.cmp(&b.rank));
 
-        Self { cards }
+        Ok(Self { cards })
+    }
+
+    /// The cards from the lowest rank to the highest.
+    pub fn iter_cards(&self) -> impl Iterator<Item = &Card> + '_ {
+        self.cards.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    fn ranks_descending(&self) -> Vec<Rank> {
+        self.cards.iter().rev().map(|card| card.rank).collect()
     }
 
     fn get_category(&self) -> Category {
+        self.get_category_with(&Rules::STANDARD)
+    }
+
+    fn get_category_with(&self, rules: &Rules) -> Category {
+        #[cfg(test)]
+        CATEGORY_CALLS.with(|calls| calls.set(calls.get() + 1));
+
         let is_suit_all_same = self
             .cards
             .iter()
@@ -118,7 +454,8 @@ impl Hand {
             .cards
             .windows(2)
             .all(|window| window[0].rank as i64 + 1 == window[1].rank as i64);
-        let is_straight_baby = self.cards[0].rank == Rank::Two
+        let is_straight_baby = rules.ace_low_straights
+            && self.cards[0].rank == Rank::Two
             && self.cards[1].rank == Rank::Three
             && self.cards[2].rank == Rank::Four
             && self.cards[3].rank == Rank::Five
@@ -190,80 +527,11 @@ impl Hand {
 
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Hand) -> Option<Ordering> {
-        let convert = |category: Category| -> u8 {
-            match category {
-                Category::HighCard(_) => 1,
-                Category::OnePair(_, _, _, _) => 2,
-                Category::TwoPair(_, _, _) => 3,
-                Category::ThreeOfAKind(_, _, _) => 4,
-                Category::Straight(_) => 5,
-                Category::Flush(_) => 6,
-                Category::FullHouse(_, _) => 7,
-                Category::FourOfAKind(_, _) => 8,
-                Category::StraightFlush(_) => 9,
-            }
-        };
-
-        let category = self.get_category();
-        let other_category = other.get_category();
-        let ret_compare = convert(category.clone()).cmp(&convert(other_category.clone()));
-
-        match ret_compare {
-            Ordering::Less | Ordering::Greater => Some(ret_compare),
-            Ordering::Equal => {
-                let ret = match (category, other_category) {
-                    (Category::HighCard(hand), Category::HighCard(other_hand)) => {
-                        Some(hand.cards.cmp(&other_hand.cards))
-                    }
-                    (
-                        Category::OnePair(rank1, rank2, rank3, rank4),
-                        Category::OnePair(other_rank1, other_rank2, other_rank3, other_rank4),
-                    ) => Some(
-                        rank1.cmp(&other_rank1).then(
-                            rank2
-                                .cmp(&other_rank2)
-                                .then(rank3.cmp(&other_rank3).then(rank4.cmp(&other_rank4))),
-                        ),
-                    ),
-                    (
-                        Category::TwoPair(rank1, rank2, rank3),
-                        Category::TwoPair(other_rank1, other_rank2, other_rank3),
-                    ) => Some(
-                        rank1
-                            .cmp(&other_rank1)
-                            .then(rank2.cmp(&other_rank2).then(rank3.cmp(&other_rank3))),
-                    ),
-                    (
-                        Category::ThreeOfAKind(rank1, rank2, rank3),
-                        Category::ThreeOfAKind(other_rank1, other_rank2, other_rank3),
-                    ) => Some(
-                        rank1
-                            .cmp(&other_rank1)
-                            .then(rank2.cmp(&other_rank2).then(rank3.cmp(&other_rank3))),
-                    ),
-                    (Category::Straight(rank), Category::Straight(other_rank)) => {
-                        Some(rank.cmp(&other_rank))
-                    }
-                    (Category::Flush(hand), Category::Flush(other_hand)) => {
-                        Some(hand.cards.cmp(&other_hand.cards))
-                    }
-                    (
-                        Category::FullHouse(rank1, rank2),
-                        Category::FullHouse(other_rank1, other_rank2),
-                    ) => Some(rank1.cmp(&other_rank1).then(rank2.cmp(&other_rank2))),
-                    (
-                        Category::FourOfAKind(rank1, rank2),
-                        Category::FourOfAKind(other_rank1, other_rank2),
-                    ) => Some(rank1.cmp(&other_rank1).then(rank2.cmp(&other_rank2))),
-                    (Category::StraightFlush(rank), Category::StraightFlush(other_rank)) => {
-                        Some(rank.cmp(&other_rank))
-                    }
-                    _ => None,
-                };
-
-                ret.filter(|compare| matches!(compare, Ordering::Less | Ordering::Greater))
-            }
-        }
+        Some(
+            self.get_category()
+                .score()
+                .cmp(&other.get_category().score()),
+        )
     }
 }
 
@@ -272,30 +540,30 @@ impl PartialOrd for Hand {
 /// Note the type signature: this function should return _the same_ reference to
 /// the winning hand(s) as were passed in, not reconstructed strings which happen to be equal.
 pub fn winning_hands<'a>(hands: &[&'a str]) -> Vec<&'a str> {
+    winning_hands_with_rules(hands, &Rules::STANDARD)
+}
+
+/// Like `winning_hands`, but for the game described by `rules`.
+pub fn winning_hands_with_rules<'a>(hands: &[&'a str], rules: &Rules) -> Vec<&'a str> {
     let mut ret = Vec::new();
-    let mut win_hand = None;
+    let mut win_score = None;
 
+    // Each hand is parsed and scored once; only the scores are compared.
     for hand_str in hands.iter().copied() {
-        let hand = Hand::new(hand_str);
-
-        match &win_hand {
-            Some(cur_win_hand) => match hand.partial_cmp(cur_win_hand) {
-                Some(order) => match order {
-                    Ordering::Less => {}
-                    Ordering::Equal => {
-                        ret.push(hand_str);
-                    }
-                    Ordering::Greater => {
-                        win_hand = Some(hand);
-                        ret = vec![hand_str];
-                    }
-                },
-                None => {
-                    ret.push(hand_str);
-                }
-            },
-            None => {
-                win_hand = Some(hand);
+        let score = Hand::new(hand_str).get_category_with(rules).score();
+        let beats = |win_score: u32| {
+            if rules.low_hand_wins {
+                win_score.cmp(&score)
+            } else {
+                score.cmp(&win_score)
+            }
+        };
+
+        match win_score.map(beats) {
+            Some(Ordering::Less) => {}
+            Some(Ordering::Equal) => ret.push(hand_str),
+            Some(Ordering::Greater) | None => {
+                win_score = Some(score);
                 ret = vec![hand_str];
             }
         }
@@ -304,6 +572,64 @@ pub fn winning_hands<'a>(hands: &[&'a str]) -> Vec<&'a str> {
     ret
 }
 
+/// Describe a poker hand, e.g. "Full House, Kings over Fours".
+pub fn explain_hand(hand: &str) -> Result<String, HandParseError> {
+    Ok(Hand::parse(hand)?.get_category().to_string())
+}
+
+/// Name the winning hand(s) and, when the winner only beats the next best
+/// hand on a tie-break, the first comparison point that decided it.
+pub fn explain_winner(hands: &[&str]) -> String {
+    let mut parsed = Vec::new();
+
+    for hand in hands.iter().copied() {
+        match Hand::parse(hand) {
+            Ok(parsed_hand) => parsed.push((hand, parsed_hand.get_category())),
+            Err(err) => return format!("{hand} is not a valid hand: {err}"),
+        }
+    }
+
+    let winners = winning_hands(hands);
+    let (winner, category) = match parsed.iter().find(|(hand, _)| *hand == winners[0]) {
+        Some(winner) => winner,
+        None => return String::from("There are no hands to compare"),
+    };
+
+    if winners.len() > 1 {
+        return format!("{} tie with {category}", winners.join(" and "));
+    }
+
+    let runner_up = parsed
+        .iter()
+        .filter(|(hand, _)| hand != winner)
+        .map(|(hand, _)| Hand::new(hand))
+        .reduce(|best, hand| if hand > best { hand } else { best })
+        .map(|hand| hand.get_category());
+
+    let runner_up = match runner_up {
+        Some(runner_up) => runner_up,
+        None => return format!("{winner} wins with {category}"),
+    };
+
+    if category.order() != runner_up.order() {
+        return format!("{winner} wins with {category}, which beats {runner_up}");
+    }
+
+    let decider = category
+        .tie_breakers()
+        .into_iter()
+        .zip(runner_up.tie_breakers())
+        .find(|((_, rank), (_, other_rank))| rank != other_rank);
+
+    match decider {
+        Some(((label, rank), (_, other_rank))) => format!(
+            "{winner} wins with {category} over {runner_up}: the {label} decides it, {rank} beats {other_rank}"
+        ),
+        None => format!("{winner} wins with {category}"),
+    }
+}
+
+#[cfg(test)]
 fn hs_from<'a>(input: &[&'a str]) -> HashSet<&'a str> {
     let mut hs = HashSet::new();
     for item in input.iter() {
@@ -317,10 +643,23 @@ fn hs_from<'a>(input: &[&'a str]) -> HashSet<&'a str> {
 ///
 /// Note that the output can be in any order. Here, we use a HashSet to
 /// abstract away the order of outputs.
+#[cfg(test)]
 fn test<'a, 'b>(input: &[&'a str], expected: &[&'b str]) {
     assert_eq!(hs_from(&winning_hands(input)), hs_from(expected))
 }
 
+/// Like `test`, but playing 2-7 lowball.
+#[cfg(test)]
+fn test_lowball(input: &[&str], expected: &[&str]) {
+    assert_eq!(
+        hs_from(&winning_hands_with_rules(
+            input,
+            &Rules::DEUCE_TO_SEVEN_LOWBALL
+        )),
+        hs_from(expected)
+    )
+}
+
 #[test]
 fn test_single_hand_always_wins() {
     test(&["4S 5S 7H 8D JC"], &["4S 5S 7H 8D JC"])
@@ -502,3 +841,233 @@ fn test_straight_flush_ranks() {
     // both hands have straight flush, tie goes to highest-ranked card
     test(&["4H 6H 7H 8H 5H", "5S 7S 8S 9S 6S"], &["5S 7S 8S 9S 6S"])
 }
+
+#[test]
+fn test_explain_hand() {
+    let tests = [
+        ("KS KH KC 4D 4S", "Full House, Kings over Fours"),
+        ("5H 6S 7D 8C 9H", "Straight, Nine high"),
+        (
+            "QS QH JC JD 9S",
+            "Two Pair, Queens and Jacks with a Nine kicker",
+        ),
+        ("6S 6H AC 9D 4S", "One Pair, Sixes with an Ace kicker"),
+        (
+            "7S 7H 7C 8D 2S",
+            "Three of a Kind, Sevens with an Eight kicker",
+        ),
+        ("2S 4C 7S 9H 10H", "High Card, Ten high"),
+        ("AH 2S 3D 4C 5H", "Straight, Five high"),
+        ("10S JS QS KS AS", "Straight Flush, Ace high"),
+        ("2H 7H 8H 9H KH", "Flush, King high"),
+        (
+            "3S 3H 3C 3D AS",
+            "Four of a Kind, Threes with an Ace kicker",
+        ),
+        (
+            "10D 10H 2C 2D 8S",
+            "Two Pair, Tens and Twos with an Eight kicker",
+        ),
+        ("4H 5H 6H 7H 8H", "Straight Flush, Eight high"),
+    ];
+
+    for (hand, expected) in tests {
+        assert_eq!(explain_hand(hand), Ok(String::from(expected)), "{hand}");
+    }
+}
+
+#[test]
+fn test_explain_hand_rejects_invalid_input() {
+    assert_eq!(
+        explain_hand("KS KH KX 4D 4S"),
+        Err(HandParseError::InvalidCard(String::from("KX")))
+    );
+    assert_eq!(
+        explain_hand("KS KH 4D 4S"),
+        Err(HandParseError::WrongCardCount(4))
+    );
+    assert_eq!(
+        explain_hand("KS KH ♠ 4D 4S"),
+        Err(HandParseError::InvalidCard(String::from("♠")))
+    );
+}
+
+#[test]
+fn test_explain_winner_by_category() {
+    assert_eq!(
+        explain_winner(&["4S 5H 6C 8D KH", "2S 4H 6S 4D JH"]),
+        "2S 4H 6S 4D JH wins with One Pair, Fours with a Jack kicker, which beats High Card, King high"
+    );
+}
+
+#[test]
+fn test_explain_winner_by_tie_break() {
+    assert_eq!(
+        explain_winner(&["JD QH JH 2D QS", "JS QD JC 9D QC"]),
+        "JS QD JC 9D QC wins with Two Pair, Queens and Jacks with a Nine kicker over \
+         Two Pair, Queens and Jacks with a Two kicker: the kicker decides it, Nine beats Two"
+    );
+    assert_eq!(
+        explain_winner(&["3S 5H 6S 8D 7H", "2S 5D 6D 8C 7S"]),
+        "3S 5H 6S 8D 7H wins with High Card, Eight high over High Card, Eight high: \
+         the fifth card decides it, Three beats Two"
+    );
+}
+
+#[test]
+fn test_explain_winner_ties_and_single_hands() {
+    assert_eq!(
+        explain_winner(&["4S 5S 7H 8D JC", "4D 5H 7C 8S JD"]),
+        "4S 5S 7H 8D JC and 4D 5H 7C 8S JD tie with High Card, Jack high"
+    );
+    assert_eq!(
+        explain_winner(&["4S 5S 7H 8D JC"]),
+        "4S 5S 7H 8D JC wins with High Card, Jack high"
+    );
+    assert_eq!(explain_winner(&[]), "There are no hands to compare");
+}
+
+#[test]
+fn test_iter_cards() {
+    let hand = Hand::new("KS 4D 10H 2C 4S");
+    assert_eq!(hand.iter_cards().count(), 5);
+    assert_eq!(hand.len(), 5);
+    assert!(!hand.is_empty());
+    let first = hand.iter_cards().next().unwrap();
+    assert_eq!(first.rank(), Rank::Two);
+    assert_eq!(first.suit(), &Suit::Club);
+    let ranks: Vec<Rank> = hand.iter_cards().map(Card::rank).collect();
+    assert_eq!(
+        ranks,
+        vec![Rank::Two, Rank::Four, Rank::Four, Rank::Ten, Rank::King]
+    );
+}
+
+#[test]
+fn test_winning_hands_categorizes_each_hand_once() {
+    let hands = [
+        "4S 5S 7H 8D JC",
+        "2S 4C 7S 9H 10H",
+        "3S 4S 5D 6H JH",
+        "3H 4H 5C 6C JD",
+        "2S 8H 6S 8D JH",
+        "4S 5H 4C 8C 5C",
+        "4D AH 4S 8S AD",
+        "2S 2H 2C 8D JH",
+        "4S 5H 4C 8S 4H",
+        "10D JH QS KD AC",
+    ];
+
+    CATEGORY_CALLS.with(|calls| calls.set(0));
+    assert_eq!(winning_hands(&hands), vec!["10D JH QS KD AC"]);
+    assert_eq!(CATEGORY_CALLS.with(Cell::get), hands.len());
+}
+
+#[test]
+fn test_score_orders_like_tie_breakers() {
+    let hands = [
+        "2S 3C 4D 5H 7S",
+        "2S 3C 4D 5H 8S",
+        "2S 2C 4D 5H 7S",
+        "2S 2C 3D 3H 4S",
+        "2S 2C 2D 3H 4S",
+        "AS 2C 3D 4H 5S",
+        "6S 2C 3D 4H 5S",
+        "2S 3S 4S 5S 7S",
+        "2S 2C 2D 3H 3S",
+        "2S 2C 2D 2H 3S",
+        "AS 2S 3S 4S 5S",
+        "10S JS QS KS AS",
+    ];
+
+    let scores = hands
+        .iter()
+        .map(|hand| Hand::new(hand).get_category().score())
+        .collect::<Vec<_>>();
+    assert!(
+        scores.windows(2).all(|pair| pair[0] < pair[1]),
+        "{scores:?}"
+    );
+}
+
+#[test]
+fn test_display_hand_in_rank_order() {
+    let hand = Hand::new("JH 10H 9H KH QH");
+    assert_eq!(hand.to_string(), "9H TH JH QH KH");
+    assert_eq!(format!("{hand:#}"), "9H 10H JH QH KH");
+
+    let hand = Hand::new("AS 2D 5H KD 10C");
+    assert_eq!(hand.to_string(), "2D 5H TC KD AS");
+}
+
+#[test]
+fn test_display_card_and_suit() {
+    let card = Hand::new("2S 5H KD AH 10C")
+        .iter_cards()
+        .nth(2)
+        .cloned()
+        .unwrap();
+    assert_eq!(card.to_string(), "TC");
+    assert_eq!(format!("{card:#}"), "10C");
+    assert_eq!(Suit::Diamond.to_string(), "D");
+    assert_eq!(Rank::Ten.symbol(), 'T');
+    assert_eq!(Rank::Ten.to_string(), "Ten");
+}
+
+#[test]
+fn test_display_round_trips() {
+    for input in ["2S 5H KD AH 10C", "4S 4H 4C 8D 8S", "AS 2S 3S 4S 5S"] {
+        let hand = Hand::new(input);
+
+        assert_eq!(Hand::parse(&hand.to_string()), Ok(hand.clone()));
+        assert_eq!(Hand::parse(&format!("{hand:#}")), Ok(hand));
+    }
+}
+
+#[test]
+fn test_standard_rules_are_the_default() {
+    let input = &["4S 5S 7H 8D JC", "2S 4C 7S 9H 10H", "AS 2D 3C 4H 5S"];
+
+    assert_eq!(Rules::default(), Rules::STANDARD);
+    assert_eq!(
+        winning_hands_with_rules(input, &Rules::default()),
+        winning_hands(input)
+    );
+}
+
+#[test]
+fn test_lowball_seven_five_beats_everything() {
+    test_lowball(
+        &[
+            "7S 5H 4D 3C 2S",
+            "7H 6D 4C 3S 2H",
+            "8S 5D 4H 3C 2D",
+            "AS 2D 3C 4H 5S",
+            "6S 5H 4D 3C 2H",
+            "7S 5S 4S 3S 2S",
+            "2S 2H 3D 4C 5S",
+        ],
+        &["7S 5H 4D 3C 2S"],
+    )
+}
+
+#[test]
+fn test_lowball_pair_loses_to_any_no_pair_hand() {
+    test_lowball(&["2S 2H 3D 4C 5S", "KS QH JD 9C 8S"], &["KS QH JD 9C 8S"]);
+    test_lowball(&["2S 2H 3D 4C 5S", "3S 3H 2D 4C 5D"], &["2S 2H 3D 4C 5S"]);
+}
+
+#[test]
+fn test_lowball_straights_and_flushes_count_against_the_hand() {
+    test_lowball(&["6S 5H 4D 3C 2H", "KS QH JD 9C 8S"], &["KS QH JD 9C 8S"]);
+    test_lowball(&["8S 5S 4S 3S 2S", "KS QH JD 9C 8S"], &["KS QH JD 9C 8S"]);
+}
+
+#[test]
+fn test_lowball_aces_are_always_high() {
+    // In standard play the wheel is a straight that loses to the six-high
+    // one; in 2-7 lowball it counts as ace-high with no pair, so it wins.
+    test(&["AS 2D 3C 4H 5S", "2H 3S 4D 5C 6H"], &["2H 3S 4D 5C 6H"]);
+    test_lowball(&["AS 2D 3C 4H 5S", "2H 3S 4D 5C 6H"], &["AS 2D 3C 4H 5S"]);
+    test_lowball(&["AS 2D 3C 4H 5S", "KS QH JD 9C 8S"], &["KS QH JD 9C 8S"]);
+}
@@ -1,67 +1,443 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
 use std::io::{Read, Result, Write};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+/// A source of the current time, so timed wrappers can be tested without a real clock.
+///
+/// Clocks are `Send + Sync` so a timed wrapper can move between threads like its inner
+/// reader or writer.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+/// The clock `timed` wrappers use by default.
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+/// Durations of the calls a timed wrapper has made, in call order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CallTimes {
+    durations: Vec<Duration>,
+}
+impl CallTimes {
+    pub fn durations(&self) -> &[Duration] {
+        &self.durations
+    }
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+    pub fn max(&self) -> Option<Duration> {
+        self.durations.iter().max().copied()
+    }
+    pub fn mean(&self) -> Option<Duration> {
+        if self.durations.is_empty() {
+            None
+        } else {
+            let total: u128 = self.durations.iter().map(Duration::as_nanos).sum();
+            Some(duration_from_nanos(total / self.durations.len() as u128))
+        }
+    }
+    /// Count the calls per `bucket`-wide range, starting from zero, in order. Only ranges
+    /// holding at least one call are listed, so one slow call doesn't cost a range for every
+    /// `bucket` below it.
+    ///
+    /// Panics if `bucket` is zero.
+    pub fn histogram(&self, bucket: Duration) -> Vec<(Range<Duration>, usize)> {
+        assert!(!bucket.is_zero(), "histogram bucket width must be non-zero");
+        let width = bucket.as_nanos();
+        let mut counts = BTreeMap::new();
+        for duration in &self.durations {
+            *counts.entry(duration.as_nanos() / width).or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(i, count)| {
+                let start = duration_from_nanos(i * width);
+                (start..start + bucket, count)
+            })
+            .collect()
+    }
+}
+fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+/// How many of the latest calls `Throughput` keeps for `window_bytes_per_sec` unless told
+/// otherwise.
+pub const DEFAULT_SAMPLE_CAPACITY: usize = 1024;
+/// Bytes moved over time by the calls a timed wrapper has made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Throughput {
+    // When each of the latest calls ended and how many bytes it moved, oldest first.
+    samples: VecDeque<(Instant, usize)>,
+    capacity: usize,
+    // From the start of the first call to the end of the last, over every call.
+    span: Option<Range<Instant>>,
+    bytes: usize,
+}
+impl Default for Throughput {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_SAMPLE_CAPACITY)
+    }
+}
+impl Throughput {
+    /// Keeps only the latest `capacity` calls for `window_bytes_per_sec`. The overall rate
+    /// still counts every call.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(DEFAULT_SAMPLE_CAPACITY)),
+            capacity,
+            span: None,
+            bytes: 0,
+        }
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// The bytes of every call over the wall time from the start of the first call to the
+    /// end of the last, idle time included. `None` before any call, or if no time passed.
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        let span = self.span.as_ref()?;
+        rate(self.bytes, span.end - span.start)
+    }
+    /// The bytes of the kept calls that ended less than `window` before the last call did,
+    /// over `window`. `None` before any call, or if `window` is zero.
+    pub fn window_bytes_per_sec(&self, window: Duration) -> Option<f64> {
+        let &(last, _) = self.samples.back()?;
+        let bytes = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|(end, _)| last - *end < window)
+            .map(|(_, bytes)| bytes)
+            .sum();
+        rate(bytes, window)
+    }
+    fn record(&mut self, call: Range<Instant>, bytes: usize) {
+        self.span = match self.span.take() {
+            Some(span) => Some(span.start..call.end),
+            None => Some(call.clone()),
+        };
+        self.bytes += bytes;
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((call.end, bytes));
+    }
+}
+fn rate(bytes: usize, elapsed: Duration) -> Option<f64> {
+    if elapsed.is_zero() {
+        None
+    } else {
+        Some(bytes as f64 / elapsed.as_secs_f64())
+    }
+}
+// Runs `call`, and with a clock times it into `times` if it succeeds.
+// Without a clock the time is never read.
+fn time_call<T>(
+    clock: &Option<Box<dyn Clock>>,
+    times: &mut CallTimes,
+    call: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    time_span(clock, times, call).map(|(result, _)| result)
+}
+// Like `time_call`, but also gives when the call started and ended.
+fn time_span<T>(
+    clock: &Option<Box<dyn Clock>>,
+    times: &mut CallTimes,
+    call: impl FnOnce() -> Result<T>,
+) -> Result<(T, Option<Range<Instant>>)> {
+    match clock {
+        Some(clock) => {
+            let start = clock.now();
+            let result = call()?;
+            let end = clock.now();
+            times.durations.push(end - start);
+            Ok((result, Some(start..end)))
+        }
+        None => Ok((call()?, None)),
+    }
+}
+// Runs a read or a write through `time_span`, then counts the bytes it moved.
+fn transfer(
+    clock: &Option<Box<dyn Clock>>,
+    times: &mut CallTimes,
+    counter: &mut Counter,
+    throughput: &mut Throughput,
+    call: impl FnOnce() -> Result<usize>,
+) -> Result<usize> {
+    let (bytes, span) = time_span(clock, times, call)?;
+    counter.record(bytes);
+    if let Some(span) = span {
+        throughput.record(span, bytes);
+    }
+    Ok(bytes)
+}
+// Calls and bytes passed through a wrapper, shared so every wrapper counts the same way.
+#[derive(Debug, Default, Clone, Copy)]
+struct Counter {
+    calls: usize,
+    bytes: usize,
+}
+impl Counter {
+    fn record(&mut self, bytes: usize) {
+        self.calls += 1;
+        self.bytes += bytes;
+    }
+}
 pub struct ReadStats<R> {
-    read_calls: usize,
-    read_bytes: usize,
+    reads: Counter,
     wrapped: R,
+    clock: Option<Box<dyn Clock>>,
+    read_times: CallTimes,
+    read_throughput: Throughput,
 }
 impl<R: Read> ReadStats<R> {
     pub fn new(wrapped: R) -> ReadStats<R> {
         Self {
-            read_calls: 0,
-            read_bytes: 0,
+            reads: Counter::default(),
             wrapped,
+            clock: None,
+            read_times: CallTimes::default(),
+            read_throughput: Throughput::default(),
+        }
+    }
+    /// Like `new`, but also records how long each read takes.
+    pub fn timed(wrapped: R) -> ReadStats<R> {
+        Self::timed_with_clock(wrapped, SystemClock)
+    }
+    pub fn timed_with_clock(wrapped: R, clock: impl Clock + 'static) -> ReadStats<R> {
+        Self {
+            clock: Some(Box::new(clock)),
+            ..Self::new(wrapped)
         }
     }
     pub fn get_ref(&self) -> &R {
         &self.wrapped
     }
+    pub fn into_inner(self) -> R {
+        self.wrapped
+    }
     pub fn bytes_through(&self) -> usize {
-        self.read_bytes
+        self.reads.bytes
     }
     pub fn reads(&self) -> usize {
-        self.read_calls
+        self.reads.calls
+    }
+    /// The timings of each successful read; always empty unless created with `timed`.
+    pub fn read_times(&self) -> &CallTimes {
+        &self.read_times
+    }
+    pub fn total_time(&self) -> Duration {
+        self.read_times.total()
+    }
+    pub fn max_call_time(&self) -> Option<Duration> {
+        self.read_times.max()
+    }
+    pub fn mean_call_time(&self) -> Option<Duration> {
+        self.read_times.mean()
+    }
+    pub fn time_histogram(&self, bucket: Duration) -> Vec<(Range<Duration>, usize)> {
+        self.read_times.histogram(bucket)
+    }
+    /// Keeps only the latest `capacity` reads for `window_throughput`.
+    pub fn with_sample_capacity(mut self, capacity: usize) -> ReadStats<R> {
+        self.read_throughput = Throughput::with_capacity(capacity);
+        self
+    }
+    /// The throughput of every successful read; always empty unless created with `timed`.
+    pub fn read_throughput(&self) -> &Throughput {
+        &self.read_throughput
+    }
+    pub fn throughput_bytes_per_sec(&self) -> Option<f64> {
+        self.read_throughput.bytes_per_sec()
+    }
+    pub fn window_throughput(&self, window: Duration) -> Option<f64> {
+        self.read_throughput.window_bytes_per_sec(window)
     }
 }
 impl<R: Read> Read for ReadStats<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        let bytes = self.wrapped.read(buf)?;
-        self.read_calls += 1;
-        self.read_bytes += bytes;
-        Ok(bytes)
+        let wrapped = &mut self.wrapped;
+        transfer(
+            &self.clock,
+            &mut self.read_times,
+            &mut self.reads,
+            &mut self.read_throughput,
+            || wrapped.read(buf),
+        )
     }
 }
 pub struct WriteStats<W> {
-    write_calls: usize,
-    write_bytes: usize,
+    writes: Counter,
+    flush_calls: usize,
+    flushed_bytes: usize,
     wrapped: W,
+    clock: Option<Box<dyn Clock>>,
+    write_times: CallTimes,
+    flush_times: CallTimes,
+    write_throughput: Throughput,
 }
 impl<W: Write> WriteStats<W> {
     pub fn new(wrapped: W) -> WriteStats<W> {
         Self {
-            write_calls: 0,
-            write_bytes: 0,
+            writes: Counter::default(),
+            flush_calls: 0,
+            flushed_bytes: 0,
             wrapped,
+            clock: None,
+            write_times: CallTimes::default(),
+            flush_times: CallTimes::default(),
+            write_throughput: Throughput::default(),
+        }
+    }
+    /// Like `new`, but also records how long each write and each flush takes.
+    pub fn timed(wrapped: W) -> WriteStats<W> {
+        Self::timed_with_clock(wrapped, SystemClock)
+    }
+    pub fn timed_with_clock(wrapped: W, clock: impl Clock + 'static) -> WriteStats<W> {
+        Self {
+            clock: Some(Box::new(clock)),
+            ..Self::new(wrapped)
         }
     }
     pub fn get_ref(&self) -> &W {
         &self.wrapped
     }
+    pub fn into_inner(self) -> W {
+        self.wrapped
+    }
     pub fn bytes_through(&self) -> usize {
-        self.write_bytes
+        self.writes.bytes
     }
     pub fn writes(&self) -> usize {
-        self.write_calls
+        self.writes.calls
+    }
+    /// The number of successful flushes.
+    pub fn flush_count(&self) -> usize {
+        self.flush_calls
+    }
+    /// What `bytes_through` was at the last successful flush, or 0 if there was none.
+    pub fn last_flush_byte_offset(&self) -> usize {
+        self.flushed_bytes
+    }
+    /// The timings of each successful write; always empty unless created with `timed`.
+    pub fn write_times(&self) -> &CallTimes {
+        &self.write_times
+    }
+    /// The timings of each successful flush, kept apart from the writes.
+    pub fn flush_times(&self) -> &CallTimes {
+        &self.flush_times
+    }
+    pub fn total_time(&self) -> Duration {
+        self.write_times.total()
+    }
+    pub fn max_call_time(&self) -> Option<Duration> {
+        self.write_times.max()
+    }
+    pub fn mean_call_time(&self) -> Option<Duration> {
+        self.write_times.mean()
+    }
+    pub fn time_histogram(&self, bucket: Duration) -> Vec<(Range<Duration>, usize)> {
+        self.write_times.histogram(bucket)
+    }
+    /// Keeps only the latest `capacity` writes for `window_throughput`.
+    pub fn with_sample_capacity(mut self, capacity: usize) -> WriteStats<W> {
+        self.write_throughput = Throughput::with_capacity(capacity);
+        self
+    }
+    /// The throughput of every successful write, not counting flushes; always empty unless
+    /// created with `timed`.
+    pub fn write_throughput(&self) -> &Throughput {
+        &self.write_throughput
+    }
+    pub fn throughput_bytes_per_sec(&self) -> Option<f64> {
+        self.write_throughput.bytes_per_sec()
+    }
+    pub fn window_throughput(&self, window: Duration) -> Option<f64> {
+        self.write_throughput.window_bytes_per_sec(window)
     }
 }
 impl<W: Write> Write for WriteStats<W> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        let bytes = self.wrapped.write(buf)?;
-        self.write_calls += 1;
-        self.write_bytes += bytes;
-        Ok(bytes)
+        let wrapped = &mut self.wrapped;
+        transfer(
+            &self.clock,
+            &mut self.write_times,
+            &mut self.writes,
+            &mut self.write_throughput,
+            || wrapped.write(buf),
+        )
     }
     fn flush(&mut self) -> Result<()> {
-        self.wrapped.flush()
+        let wrapped = &mut self.wrapped;
+        time_call(&self.clock, &mut self.flush_times, || wrapped.flush())?;
+        self.flush_calls += 1;
+        self.flushed_bytes = self.writes.bytes;
+        Ok(())
+    }
+}
+/// Counts what passes through a `fmt::Write`, such as the target of `write!`. Strings and
+/// single characters are counted as separate kinds of call.
+pub struct FmtWriteStats<W> {
+    str_writes: Counter,
+    char_writes: Counter,
+    chars: usize,
+    wrapped: W,
+}
+impl<W: fmt::Write> FmtWriteStats<W> {
+    pub fn new(wrapped: W) -> FmtWriteStats<W> {
+        Self {
+            str_writes: Counter::default(),
+            char_writes: Counter::default(),
+            chars: 0,
+            wrapped,
+        }
+    }
+    pub fn get_ref(&self) -> &W {
+        &self.wrapped
+    }
+    pub fn into_inner(self) -> W {
+        self.wrapped
+    }
+    /// The number of characters written, by either kind of call.
+    pub fn chars_through(&self) -> usize {
+        self.chars
+    }
+    /// The UTF-8 length of everything written, by either kind of call.
+    pub fn bytes_through(&self) -> usize {
+        self.str_writes.bytes + self.char_writes.bytes
+    }
+    /// The number of successful `write_str` calls.
+    pub fn writes(&self) -> usize {
+        self.str_writes.calls
+    }
+    /// The number of successful `write_char` calls.
+    pub fn char_writes(&self) -> usize {
+        self.char_writes.calls
+    }
+}
+impl<W: fmt::Write> fmt::Write for FmtWriteStats<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.wrapped.write_str(s)?;
+        self.str_writes.record(s.len());
+        self.chars += s.chars().count();
+        Ok(())
+    }
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.wrapped.write_char(c)?;
+        self.char_writes.record(c.len_utf8());
+        self.chars += 1;
+        Ok(())
     }
 }
 
@@ -257,3 +633,360 @@ fn read_stats_by_ref_returns_wrapped_reader() {
     let reader = ReadStats::new(input);
     assert_eq!(reader.get_ref(), &input);
 }
+
+/// A clock that moves forward by a scripted step each time it is read.
+#[cfg(test)]
+struct MockClock {
+    state: std::sync::Mutex<(Instant, VecDeque<Duration>)>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    /// A clock under which the timed calls take `millis` milliseconds each, in order.
+    fn per_call(millis: &[u64]) -> Self {
+        let calls: Vec<(u64, u64)> = millis.iter().map(|&ms| (ms, 0)).collect();
+        Self::schedule(&calls)
+    }
+
+    /// Like `per_call`, but each `(millis, idle)` call is followed by `idle` milliseconds
+    /// in which the wrapper isn't called.
+    fn schedule(calls: &[(u64, u64)]) -> Self {
+        let steps = calls
+            .iter()
+            .flat_map(|&(ms, idle)| [Duration::from_millis(ms), Duration::from_millis(idle)])
+            .collect();
+        Self {
+            state: std::sync::Mutex::new((Instant::now(), steps)),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        let (current, steps) = &mut *self.state.lock().unwrap();
+        let now = *current;
+        if let Some(step) = steps.pop_front() {
+            *current = now + step;
+        }
+        now
+    }
+}
+
+#[cfg(test)]
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn untimed_stats_record_no_times() {
+    let mut reader = ReadStats::new(&b"abc"[..]);
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).unwrap();
+    assert_eq!(2, reader.reads());
+    assert_eq!(Duration::ZERO, reader.total_time());
+    assert_eq!(None, reader.max_call_time());
+    assert_eq!(None, reader.mean_call_time());
+    assert!(reader.time_histogram(ms(1)).is_empty());
+}
+
+#[test]
+fn timed_reads_aggregate_exact_times() {
+    let mut reader =
+        ReadStats::timed_with_clock(&b"abcdef"[..], MockClock::per_call(&[3, 9, 6, 1]));
+    let mut buffer = [0_u8; 2];
+    while reader.read(&mut buffer).unwrap() > 0 {}
+    assert_eq!(4, reader.reads());
+    assert_eq!(
+        &[ms(3), ms(9), ms(6), ms(1)],
+        reader.read_times().durations()
+    );
+    assert_eq!(ms(19), reader.total_time());
+    assert_eq!(Some(ms(9)), reader.max_call_time());
+    assert_eq!(Some(Duration::from_micros(4750)), reader.mean_call_time());
+    assert_eq!(
+        vec![(ms(0)..ms(5), 2), (ms(5)..ms(10), 2)],
+        reader.time_histogram(ms(5))
+    );
+    assert_eq!(
+        vec![
+            (ms(0)..ms(3), 1),
+            (ms(3)..ms(6), 1),
+            (ms(6)..ms(9), 1),
+            (ms(9)..ms(12), 1)
+        ],
+        reader.time_histogram(ms(3))
+    );
+}
+
+#[test]
+fn timed_writes_and_flushes_are_kept_apart() {
+    let mut writer = WriteStats::timed_with_clock(Vec::new(), MockClock::per_call(&[2, 4, 10, 20]));
+    writer.write_all(b"ab").unwrap();
+    writer.write_all(b"cd").unwrap();
+    writer.flush().unwrap();
+    writer.flush().unwrap();
+    assert_eq!(2, writer.writes());
+    assert_eq!(ms(6), writer.total_time());
+    assert_eq!(Some(ms(4)), writer.max_call_time());
+    assert_eq!(Some(ms(3)), writer.mean_call_time());
+    assert_eq!(vec![(ms(0)..ms(100), 2)], writer.time_histogram(ms(100)));
+    assert_eq!(&[ms(10), ms(20)], writer.flush_times().durations());
+    assert_eq!(Some(ms(15)), writer.flush_times().mean());
+}
+
+#[test]
+fn flushes_are_counted() {
+    let mut writer = WriteStats::new(Vec::new());
+    assert_eq!(0, writer.flush_count());
+    assert_eq!(0, writer.last_flush_byte_offset());
+    writer.write_all(&[0; 10]).unwrap();
+    writer.flush().unwrap();
+    writer.write_all(&[0; 5]).unwrap();
+    assert_eq!(1, writer.flush_count());
+    assert_eq!(10, writer.last_flush_byte_offset());
+    assert_eq!(5, writer.bytes_through() - writer.last_flush_byte_offset());
+    writer.flush().unwrap();
+    assert_eq!(2, writer.flush_count());
+    assert_eq!(15, writer.last_flush_byte_offset());
+}
+
+#[test]
+fn failed_flushes_are_not_counted() {
+    struct Failing;
+    impl Write for Failing {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> Result<()> {
+            Err(std::io::Error::other("unflushable"))
+        }
+    }
+
+    let mut writer = WriteStats::new(Failing);
+    writer.write_all(b"abc").unwrap();
+    assert!(writer.flush().is_err());
+    assert_eq!(0, writer.flush_count());
+    assert_eq!(0, writer.last_flush_byte_offset());
+}
+
+#[test]
+fn failed_calls_are_not_timed() {
+    struct Failing;
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> Result<usize> {
+            Err(std::io::Error::other("unreadable"))
+        }
+    }
+
+    let mut reader = ReadStats::timed_with_clock(Failing, MockClock::per_call(&[5]));
+    assert!(reader.read(&mut [0_u8; 4]).is_err());
+    assert_eq!(0, reader.reads());
+    assert!(reader.read_times().durations().is_empty());
+}
+
+#[test]
+fn timed_uses_the_system_clock() {
+    let mut writer = WriteStats::timed(std::io::sink());
+    writer.write_all(b"xyz").unwrap();
+    assert_eq!(1, writer.write_times().durations().len());
+}
+
+#[test]
+fn into_inner_returns_the_wrapped_value() {
+    let mut writer = WriteStats::new(Vec::new());
+    writer.write_all(b"abc").unwrap();
+    assert_eq!(writer.into_inner(), b"abc");
+
+    let reader = ReadStats::new(&b"xyz"[..]);
+    assert_eq!(reader.into_inner(), b"xyz");
+}
+
+#[test]
+fn fmt_write_counts_chars_and_bytes() {
+    use std::fmt::Write;
+
+    let mut writer = FmtWriteStats::new(String::new());
+    let (word, number) = ("café", 42);
+    write!(writer, "{word} → {number}").unwrap();
+
+    assert_eq!(writer.get_ref(), "café → 42");
+    assert_eq!(writer.chars_through(), 9);
+    assert_eq!(writer.bytes_through(), "café → 42".len());
+    assert_eq!(writer.bytes_through(), 12);
+    assert_eq!(writer.char_writes(), 0);
+    assert!(writer.writes() > 0);
+}
+
+#[test]
+fn fmt_write_counts_char_writes_separately() {
+    use std::fmt::Write;
+
+    let mut writer = FmtWriteStats::new(String::new());
+    writer.write_str("한글").unwrap();
+    writer.write_char('🦀').unwrap();
+    writer.write_char('a').unwrap();
+
+    assert_eq!(writer.writes(), 1);
+    assert_eq!(writer.char_writes(), 2);
+    assert_eq!(writer.chars_through(), 4);
+    assert_eq!(writer.bytes_through(), 6 + 4 + 1);
+    assert_eq!(writer.into_inner(), "한글🦀a");
+}
+
+#[test]
+fn failed_fmt_writes_are_not_counted() {
+    use std::fmt::Write;
+
+    struct Failing;
+    impl Write for Failing {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut writer = FmtWriteStats::new(Failing);
+    assert!(writer.write_str("lost").is_err());
+    assert!(writer.write_char('x').is_err());
+    assert_eq!(writer.writes(), 0);
+    assert_eq!(writer.char_writes(), 0);
+    assert_eq!(writer.chars_through(), 0);
+    assert_eq!(writer.bytes_through(), 0);
+}
+
+#[test]
+fn throughput_over_a_scripted_schedule() {
+    let data = [0_u8; 1000];
+    let clock = MockClock::schedule(&[(100, 200), (100, 200), (100, 200), (100, 0)]);
+    let mut reader = ReadStats::timed_with_clock(&data[..], clock);
+    for size in [100, 200, 300, 400] {
+        assert_eq!(size, reader.read(&mut vec![0_u8; size]).unwrap());
+    }
+
+    // The reads end at 100, 400, 700 and 1000ms.
+    assert_eq!(Some(1000.0), reader.throughput_bytes_per_sec());
+    assert_eq!(Some(1600.0), reader.window_throughput(ms(250)));
+    assert_eq!(Some(1400.0), reader.window_throughput(ms(500)));
+    // The read that ended exactly 600ms before the last is left out.
+    assert_eq!(Some(700.0 / 0.6), reader.window_throughput(ms(600)));
+    assert_eq!(Some(1125.0), reader.window_throughput(ms(800)));
+    assert_eq!(Some(1000.0), reader.window_throughput(ms(1000)));
+    assert_eq!(Some(500.0), reader.window_throughput(ms(2000)));
+    assert_eq!(None, reader.window_throughput(Duration::ZERO));
+}
+
+#[test]
+fn throughput_of_no_samples_and_one_sample() {
+    let reader = ReadStats::timed_with_clock(&b"abc"[..], MockClock::per_call(&[]));
+    assert_eq!(None, reader.throughput_bytes_per_sec());
+    assert_eq!(None, reader.window_throughput(ms(100)));
+
+    let mut reader = ReadStats::timed_with_clock(&[0_u8; 100][..], MockClock::per_call(&[100]));
+    assert_eq!(100, reader.read(&mut [0_u8; 100]).unwrap());
+    assert_eq!(Some(1000.0), reader.throughput_bytes_per_sec());
+    assert_eq!(Some(100.0), reader.window_throughput(ms(1000)));
+
+    // A call that takes no time gives no overall rate, but still counts in a window.
+    let mut reader = ReadStats::timed_with_clock(&[0_u8; 100][..], MockClock::per_call(&[0]));
+    assert_eq!(100, reader.read(&mut [0_u8; 100]).unwrap());
+    assert_eq!(None, reader.throughput_bytes_per_sec());
+    assert_eq!(Some(200.0), reader.window_throughput(ms(500)));
+}
+
+#[test]
+fn untimed_stats_have_no_throughput() {
+    let mut reader = ReadStats::new(&b"abc"[..]);
+    reader.read_to_end(&mut Vec::new()).unwrap();
+    assert_eq!(None, reader.throughput_bytes_per_sec());
+    assert_eq!(None, reader.window_throughput(ms(100)));
+}
+
+#[test]
+fn sample_capacity_bounds_the_window() {
+    let data = [0_u8; 1000];
+    let clock = MockClock::schedule(&[(100, 200), (100, 200), (100, 200), (100, 0)]);
+    let mut reader = ReadStats::timed_with_clock(&data[..], clock).with_sample_capacity(2);
+    for size in [100, 200, 300, 400] {
+        assert_eq!(size, reader.read(&mut vec![0_u8; size]).unwrap());
+    }
+
+    assert_eq!(2, reader.read_throughput().capacity());
+    assert_eq!(Some(350.0), reader.window_throughput(ms(2000)));
+    assert_eq!(Some(1000.0), reader.throughput_bytes_per_sec());
+
+    let mut reader =
+        ReadStats::timed_with_clock(&data[..], MockClock::per_call(&[100])).with_sample_capacity(0);
+    assert_eq!(100, reader.read(&mut [0_u8; 100]).unwrap());
+    assert_eq!(None, reader.window_throughput(ms(2000)));
+    assert_eq!(Some(1000.0), reader.throughput_bytes_per_sec());
+}
+
+#[test]
+fn write_throughput_leaves_out_flushes() {
+    let clock = MockClock::schedule(&[(50, 50), (400, 0), (50, 0)]);
+    let mut writer = WriteStats::timed_with_clock(Vec::new(), clock);
+    writer.write_all(&[0; 10]).unwrap();
+    writer.flush().unwrap();
+    writer.write_all(&[0; 40]).unwrap();
+
+    // The writes run from 0 to 50ms and from 500 to 550ms.
+    assert_eq!(Some(50.0 / 0.55), writer.throughput_bytes_per_sec());
+    assert_eq!(Some(400.0), writer.window_throughput(ms(100)));
+    assert_eq!(
+        Some(50.0),
+        writer.write_throughput().window_bytes_per_sec(ms(1000))
+    );
+}
+
+#[test]
+fn histogram_skips_empty_ranges() {
+    let mut reader =
+        ReadStats::timed_with_clock(&b"abcdef"[..], MockClock::per_call(&[1, 3, 1_000_000]));
+    let mut buffer = [0_u8; 2];
+    for _ in 0..3 {
+        assert_eq!(2, reader.read(&mut buffer).unwrap());
+    }
+    assert_eq!(
+        vec![
+            (ms(0)..ms(2), 1),
+            (ms(2)..ms(4), 1),
+            (ms(1_000_000)..ms(1_000_002), 1)
+        ],
+        reader.time_histogram(ms(2))
+    );
+    assert_eq!(
+        vec![
+            (
+                Duration::from_nanos(1_000_000)..Duration::from_nanos(1_000_001),
+                1
+            ),
+            (
+                Duration::from_nanos(3_000_000)..Duration::from_nanos(3_000_001),
+                1
+            ),
+            (ms(1_000_000)..ms(1_000_000) + Duration::from_nanos(1), 1)
+        ],
+        reader.time_histogram(Duration::from_nanos(1))
+    );
+}
+
+#[test]
+fn mean_survives_totals_beyond_duration_max() {
+    let times = CallTimes {
+        durations: vec![Duration::from_secs(u64::MAX / 4); 5],
+    };
+    assert_eq!(Some(Duration::from_secs(u64::MAX / 4)), times.mean());
+    let times = CallTimes {
+        durations: vec![Duration::from_nanos(1), Duration::from_nanos(2)],
+    };
+    assert_eq!(Some(Duration::from_nanos(1)), times.mean());
+}
+
+#[test]
+fn stats_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ReadStats<std::io::Empty>>();
+    assert_send_sync::<WriteStats<std::io::Sink>>();
+    assert_send_sync::<FmtWriteStats<String>>();
+    assert_send_sync::<Box<dyn Clock>>();
+}
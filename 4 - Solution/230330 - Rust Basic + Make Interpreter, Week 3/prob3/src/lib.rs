@@ -1,7 +1,18 @@
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Write;
+use std::hash::Hash;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 /// `InputCellId` is a unique identifier for an input cell.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct InputCellId(usize);
+pub struct InputCellId {
+    reactor: u64,
+    index: usize,
+}
 /// `ComputeCellId` is a unique identifier for a compute cell.
 /// Values of type `InputCellId` and `ComputeCellId` should not be mutually assignable,
 /// demonstrated by the following tests:
@@ -17,16 +28,53 @@ pub struct InputCellId(usize);
 /// let compute: react::InputCellId = r.create_compute(&[react::CellId::Input(input)], |_| 222).unwrap();
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct ComputeCellId(usize);
+pub struct ComputeCellId {
+    reactor: u64,
+    index: usize,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CallbackId {
+    reactor: u64,
+    index: usize,
+}
+/// `ObserveAllId` identifies a callback registered with `Reactor::observe_all`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct CallbackId(usize);
+pub struct ObserveAllId {
+    reactor: u64,
+    index: usize,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CellId {
     Input(InputCellId),
     Compute(ComputeCellId),
 }
-struct InputCell<T> {
+// Every id also carries the tag of the reactor that issued it, so a reactor can reject ids from
+// another one even when their indices are the same.
+static NEXT_REACTOR_TAG: AtomicU64 = AtomicU64::new(0);
+// Cells are numbered in creation order, separately for inputs and computes, and displayed as
+// `input0`, `compute3` and so on. These are also the node names used by `export_dot`.
+impl fmt::Display for InputCellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input{}", self.index)
+    }
+}
+impl fmt::Display for ComputeCellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "compute{}", self.index)
+    }
+}
+impl fmt::Display for CellId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CellId::Input(id) => id.fmt(f),
+            CellId::Compute(id) => id.fmt(f),
+        }
+    }
+}
+struct InputCell<'a, T> {
     value: T,
+    callbacks: HashMap<usize, Box<dyn 'a + FnMut(T)>>,
+    next_callback_id: usize,
 }
 struct ComputeCell<'a, T> {
     value: T,
@@ -34,17 +82,79 @@ struct ComputeCell<'a, T> {
     compute_func: Box<dyn 'a + Fn(&[T]) -> T>,
     callbacks: HashMap<usize, Box<dyn 'a + FnMut(T)>>,
     next_callback_id: usize,
+    cache_stats: Option<Rc<Cell<CacheStats>>>,
+}
+#[derive(Clone, Copy, Default)]
+struct CacheStats {
+    hits: usize,
+    misses: usize,
+}
+// A least-recently-used cache from dependency values to computed values.
+// Eviction scans for the oldest entry, which is fine for the small capacities
+// compute cells are expected to use.
+struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, usize)>,
+    tick: usize,
+}
+impl<K: Clone + Eq + Hash, V: Copy> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(value, last_used)| {
+            *last_used = tick;
+            *value
+        })
+    }
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(key, (value, self.tick));
+    }
 }
 #[derive(Debug, PartialEq, Eq)]
 pub enum RemoveCallbackError {
     NonexistentCell,
     NonexistentCallback,
 }
-#[derive(Default)]
 pub struct Reactor<'a, T: Default> {
-    input_cells: Vec<InputCell<T>>,
+    tag: u64,
+    input_cells: Vec<InputCell<'a, T>>,
     compute_cells: Vec<ComputeCell<'a, T>>,
     dependencies: HashMap<CellId, HashSet<ComputeCellId>>,
+    observers: HashMap<usize, Box<dyn 'a + FnMut(ComputeCellId, T)>>,
+    next_observer_id: usize,
+}
+impl<'a, T: Default> Default for Reactor<'a, T> {
+    fn default() -> Self {
+        Self {
+            tag: NEXT_REACTOR_TAG.fetch_add(1, Ordering::Relaxed),
+            input_cells: Vec::new(),
+            compute_cells: Vec::new(),
+            dependencies: HashMap::new(),
+            observers: HashMap::new(),
+            next_observer_id: 0,
+        }
+    }
 }
 // You are guaranteed that Reactor will only be tested against types that are Copy + PartialEq.
 impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
@@ -54,8 +164,39 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
     // Creates an input cell with the specified initial value, returning its ID.
     pub fn create_input(&mut self, initial: T) -> InputCellId {
         let next_id = self.input_cells.len();
-        self.input_cells.push(InputCell { value: initial });
-        InputCellId(next_id)
+        self.input_cells.push(InputCell {
+            value: initial,
+            callbacks: HashMap::new(),
+            next_callback_id: 0,
+        });
+        self.input_id(next_id)
+    }
+    fn input_id(&self, index: usize) -> InputCellId {
+        InputCellId {
+            reactor: self.tag,
+            index,
+        }
+    }
+    fn compute_id(&self, index: usize) -> ComputeCellId {
+        ComputeCellId {
+            reactor: self.tag,
+            index,
+        }
+    }
+    // Retrieves a compute cell, or None if it doesn't exist or belongs to another reactor.
+    fn compute_cell(&self, id: ComputeCellId) -> Option<&ComputeCell<'a, T>> {
+        if id.reactor == self.tag {
+            self.compute_cells.get(id.index)
+        } else {
+            None
+        }
+    }
+    fn compute_cell_mut(&mut self, id: ComputeCellId) -> Option<&mut ComputeCell<'a, T>> {
+        if id.reactor == self.tag {
+            self.compute_cells.get_mut(id.index)
+        } else {
+            None
+        }
     }
     // Creates a compute cell with the specified dependencies and compute function.
     // The compute function is expected to take in its arguments in the same order as specified in
@@ -75,24 +216,50 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
         dependencies: &[CellId],
         compute_func: F,
     ) -> Result<ComputeCellId, CellId> {
-        let values = self.values(dependencies)?;
+        let values = self.values_of(dependencies)?;
+        let value = compute_func(&values);
+        Ok(self.push_compute(dependencies, value, compute_func))
+    }
+    // Creates a compute cell like `create_compute`, but whose value is `initial` until one of its
+    // dependencies changes or `recompute_now` is called, instead of `compute_func` applied to the
+    // current values. This is for dependencies that only hold placeholders at creation time.
+    //
+    // The first computed value replaces `initial` like any other update, so callbacks are called
+    // if and only if the two differ.
+    pub fn create_compute_with_initial<F: 'a + Fn(&[T]) -> T>(
+        &mut self,
+        dependencies: &[CellId],
+        initial: T,
+        compute_func: F,
+    ) -> Result<ComputeCellId, CellId> {
+        self.values_of(dependencies)?;
+        Ok(self.push_compute(dependencies, initial, compute_func))
+    }
+    // Adds a compute cell with the given value, whose dependencies are known to exist.
+    fn push_compute<F: 'a + Fn(&[T]) -> T>(
+        &mut self,
+        dependencies: &[CellId],
+        value: T,
+        compute_func: F,
+    ) -> ComputeCellId {
         let compute_cell = ComputeCell {
-            value: compute_func(&values),
+            value,
             dependencies: dependencies.to_vec(),
             compute_func: Box::new(compute_func),
             callbacks: HashMap::new(),
             next_callback_id: 0,
+            cache_stats: None,
         };
         let next_id = self.compute_cells.len();
         self.compute_cells.push(compute_cell);
-        let compute_cell_id = ComputeCellId(next_id);
+        let compute_cell_id = self.compute_id(next_id);
         for dependency in dependencies.iter() {
             self.dependencies
                 .entry(*dependency)
                 .or_insert_with(HashSet::new)
                 .insert(compute_cell_id);
         }
-        Ok(compute_cell_id)
+        compute_cell_id
     }
     // Retrieves the current value of the cell, or None if the cell does not exist.
     //
@@ -103,12 +270,15 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
     // We chose not to cover this here, since this exercise is probably enough work as-is.
     pub fn value(&self, id: CellId) -> Option<T> {
         match id {
-            CellId::Input(id) => self.input_cells.get(id.0).map(|cell| cell.value),
-            CellId::Compute(id) => self.compute_cells.get(id.0).map(|cell| cell.value),
+            CellId::Input(id) if id.reactor == self.tag => {
+                self.input_cells.get(id.index).map(|cell| cell.value)
+            }
+            CellId::Input(_) => None,
+            CellId::Compute(id) => self.compute_cell(id).map(|cell| cell.value),
         }
     }
     // Retrieves the values of the given cell IDs.
-    fn values(&self, cell_ids: &[CellId]) -> Result<Vec<T>, CellId> {
+    fn values_of(&self, cell_ids: &[CellId]) -> Result<Vec<T>, CellId> {
         cell_ids
             .iter()
             .map(|&id| self.value(id).ok_or(id))
@@ -123,24 +293,64 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
     //
     // As before, that turned out to add too much extra complexity.
     pub fn set_value(&mut self, id: InputCellId, new_value: T) -> bool {
-        match self.input_cells.get_mut(id.0) {
+        if id.reactor != self.tag {
+            return false;
+        }
+        match self.input_cells.get_mut(id.index) {
             Some(input_cell) => {
                 input_cell.value = new_value;
+                for callback in input_cell.callbacks.values_mut() {
+                    callback(new_value);
+                }
                 let mut updated = HashMap::new();
                 self.update_dependencies(&CellId::Input(id), &mut updated);
-                for (id, old_value) in updated {
-                    let compute_cell = self.compute_cells.get_mut(id.0).unwrap();
-                    if compute_cell.value != old_value {
-                        for callback in compute_cell.callbacks.values_mut() {
-                            callback(compute_cell.value);
-                        }
-                    }
-                }
+                self.notify(updated);
                 true
             }
             None => false,
         }
     }
+    // Evaluates the specified compute cell from the current values of its dependencies, and
+    // propagates the result exactly like `set_value` would if it changed.
+    //
+    // Returns false if the cell does not exist.
+    pub fn recompute_now(&mut self, id: ComputeCellId) -> bool {
+        let compute_cell = match self.compute_cell(id) {
+            Some(compute_cell) => compute_cell,
+            None => return false,
+        };
+        let values = self.values_of(&compute_cell.dependencies).unwrap();
+        let value = (compute_cell.compute_func)(&values);
+        let mut updated = HashMap::new();
+        if value != compute_cell.value {
+            updated.insert(id, compute_cell.value);
+            self.compute_cells[id.index].value = value;
+            self.update_dependencies(&CellId::Compute(id), &mut updated);
+        }
+        self.notify(updated);
+        true
+    }
+    // Calls the callbacks of every updated cell whose value differs from the old one, then the
+    // observers for each of those cells in creation order.
+    fn notify(&mut self, updated: HashMap<ComputeCellId, T>) {
+        let mut changed = Vec::new();
+        for (id, old_value) in updated {
+            let compute_cell = self.compute_cells.get_mut(id.index).unwrap();
+            if compute_cell.value != old_value {
+                for callback in compute_cell.callbacks.values_mut() {
+                    callback(compute_cell.value);
+                }
+                changed.push(id);
+            }
+        }
+        changed.sort_by_key(|id| id.index);
+        for id in changed {
+            let value = self.compute_cells[id.index].value;
+            for observer in self.observers.values_mut() {
+                observer(id, value);
+            }
+        }
+    }
     // Updates all the compute cells recursively which depend on the given cell, if the cell is changed
     // And adds the updated compute cells to the given hash map
     fn update_dependencies(
@@ -150,12 +360,12 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
     ) {
         if let Some(compute_cell_ids) = self.dependencies.get(input_cell_id) {
             for compute_cell_id in compute_cell_ids.to_owned() {
-                let compute_cell = &self.compute_cells[compute_cell_id.0];
-                let values = self.values(&compute_cell.dependencies).unwrap();
+                let compute_cell = &self.compute_cells[compute_cell_id.index];
+                let values = self.values_of(&compute_cell.dependencies).unwrap();
                 let value = (compute_cell.compute_func)(&values);
                 if value != compute_cell.value {
                     updated.entry(compute_cell_id).or_insert(compute_cell.value);
-                    self.compute_cells[compute_cell_id.0].value = value;
+                    self.compute_cells[compute_cell_id.index].value = value;
                     self.update_dependencies(&CellId::Compute(compute_cell_id), updated);
                 }
             }
@@ -178,12 +388,57 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
         id: ComputeCellId,
         callback: F,
     ) -> Option<CallbackId> {
-        let compute_cell = self.compute_cells.get_mut(id.0)?;
+        let reactor = self.tag;
+        let compute_cell = self.compute_cell_mut(id)?;
         compute_cell.next_callback_id += 1;
         compute_cell
             .callbacks
             .insert(compute_cell.next_callback_id, Box::new(callback));
-        Some(CallbackId(compute_cell.next_callback_id))
+        Some(CallbackId {
+            reactor,
+            index: compute_cell.next_callback_id,
+        })
+    }
+    // Adds a callback to the specified input cell, called with the new value on every set_value
+    // call on that cell, before any compute cell callbacks, even if the value stays the same.
+    //
+    // Returns the ID of the just-added callback, or None if the cell doesn't exist.
+    pub fn subscribe_to_input<F: 'a + FnMut(T)>(
+        &mut self,
+        id: InputCellId,
+        callback: F,
+    ) -> Option<CallbackId> {
+        if id.reactor != self.tag {
+            return None;
+        }
+        let input_cell = self.input_cells.get_mut(id.index)?;
+        input_cell.next_callback_id += 1;
+        input_cell
+            .callbacks
+            .insert(input_cell.next_callback_id, Box::new(callback));
+        Some(CallbackId {
+            reactor: self.tag,
+            index: input_cell.next_callback_id,
+        })
+    }
+    // Removes a callback added with subscribe_to_input, like remove_callback does for compute
+    // cells.
+    pub fn unsubscribe_from_input(
+        &mut self,
+        cell: InputCellId,
+        callback: CallbackId,
+    ) -> Result<(), RemoveCallbackError> {
+        let input_cell = match self.input_cells.get_mut(cell.index) {
+            Some(input_cell) if cell.reactor == self.tag => input_cell,
+            _ => return Err(RemoveCallbackError::NonexistentCell),
+        };
+        if callback.reactor != self.tag {
+            return Err(RemoveCallbackError::NonexistentCallback);
+        }
+        match input_cell.callbacks.remove(&callback.index) {
+            Some(_) => Ok(()),
+            None => Err(RemoveCallbackError::NonexistentCallback),
+        }
     }
     // Removes the specified callback, using an ID returned from add_callback.
     //
@@ -195,14 +450,335 @@ impl<'a, T: Copy + PartialEq + Default> Reactor<'a, T> {
         cell: ComputeCellId,
         callback: CallbackId,
     ) -> Result<(), RemoveCallbackError> {
-        match self.compute_cells.get_mut(cell.0) {
-            Some(compute_cell) => match compute_cell.callbacks.remove(&callback.0) {
+        let reactor = self.tag;
+        match self.compute_cell_mut(cell) {
+            Some(_) if callback.reactor != reactor => Err(RemoveCallbackError::NonexistentCallback),
+            Some(compute_cell) => match compute_cell.callbacks.remove(&callback.index) {
                 Some(_) => Ok(()),
                 None => Err(RemoveCallbackError::NonexistentCallback),
             },
             None => Err(RemoveCallbackError::NonexistentCell),
         }
     }
+    // Removes several callbacks at once, like calling remove_callback on each pair in order.
+    //
+    // Returns the result for each pair, in the same order.
+    pub fn batch_remove_callbacks(
+        &mut self,
+        pairs: &[(ComputeCellId, CallbackId)],
+    ) -> Vec<Result<(), RemoveCallbackError>> {
+        pairs
+            .iter()
+            .map(|&(cell, callback)| self.remove_callback(cell, callback))
+            .collect()
+    }
+    // Adds a callback that is called for every compute cell whose value changed, with the same
+    // semantics as `add_callback`.
+    //
+    // During a set_value call, these run after all per-cell callbacks, visiting the changed cells
+    // in creation order.
+    pub fn observe_all<F: 'a + FnMut(ComputeCellId, T)>(&mut self, f: F) -> ObserveAllId {
+        self.next_observer_id += 1;
+        self.observers.insert(self.next_observer_id, Box::new(f));
+        ObserveAllId {
+            reactor: self.tag,
+            index: self.next_observer_id,
+        }
+    }
+    // Removes a callback added with observe_all.
+    //
+    // Returns false if it was already removed.
+    pub fn remove_observe_all(&mut self, id: ObserveAllId) -> bool {
+        id.reactor == self.tag && self.observers.remove(&id.index).is_some()
+    }
+    // Retrieves the `(hits, misses)` counters of a memoized compute cell.
+    //
+    // Returns None if the cell doesn't exist or wasn't created with `create_compute_memoized`.
+    pub fn cache_stats(&self, id: ComputeCellId) -> Option<(usize, usize)> {
+        let stats = self.compute_cell(id)?.cache_stats.as_ref()?.get();
+        Some((stats.hits, stats.misses))
+    }
+    // Retrieves the dependencies a compute cell was created with, in the same order.
+    //
+    // Returns None if the cell doesn't exist.
+    pub fn depends_on(&self, cell: ComputeCellId) -> Option<Vec<CellId>> {
+        self.compute_cell(cell)
+            .map(|cell| cell.dependencies.clone())
+    }
+    // Retrieves the compute cells that list `cell` as a direct dependency, in creation order.
+    pub fn dependents_of(&self, cell: CellId) -> Vec<ComputeCellId> {
+        let mut dependents: Vec<ComputeCellId> = self
+            .dependencies
+            .get(&cell)
+            .map(|dependents| dependents.iter().copied().collect())
+            .unwrap_or_default();
+        dependents.sort_by_key(|id| id.index);
+        dependents
+    }
+    // Lists the ids of all input cells in creation order.
+    pub fn input_cell_ids(&self) -> Vec<InputCellId> {
+        (0..self.input_cells.len())
+            .map(|index| self.input_id(index))
+            .collect()
+    }
+    // Lists the ids of all compute cells in creation order.
+    pub fn compute_cell_ids(&self) -> Vec<ComputeCellId> {
+        (0..self.compute_cells.len())
+            .map(|index| self.compute_id(index))
+            .collect()
+    }
+    // Returns the number of input cells.
+    pub fn input_count(&self) -> usize {
+        self.input_cells.len()
+    }
+    // Returns the number of compute cells.
+    pub fn compute_count(&self) -> usize {
+        self.compute_cells.len()
+    }
+    // Returns the total number of cells.
+    pub fn cell_count(&self) -> usize {
+        self.input_count() + self.compute_count()
+    }
+    // Snapshots the value of every cell: input cells first, then compute cells, each in creation
+    // order.
+    pub fn values(&self) -> Vec<(CellId, T)> {
+        let inputs = self
+            .input_cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (CellId::Input(self.input_id(index)), cell.value));
+        let computes = self
+            .compute_cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (CellId::Compute(self.compute_id(index)), cell.value));
+        inputs.chain(computes).collect()
+    }
+    // Lists every `(dependency, dependent)` edge of the graph.
+    //
+    // Edges are ordered by dependent in creation order, then by the order of its dependencies.
+    pub fn export_edges(&self) -> Vec<(CellId, ComputeCellId)> {
+        self.compute_cells
+            .iter()
+            .enumerate()
+            .flat_map(|(index, cell)| {
+                cell.dependencies
+                    .iter()
+                    .map(move |&dependency| (dependency, self.compute_id(index)))
+            })
+            .collect()
+    }
+}
+impl<'a, T: Copy + PartialEq + Default + fmt::Display> Reactor<'a, T> {
+    // Renders the graph as a Graphviz DOT digraph.
+    //
+    // Input cells are boxes labeled with their value, compute cells are ellipses labeled with
+    // their id and value, and every edge points from a dependency to its dependent.
+    pub fn export_dot(&self) -> String {
+        let mut dot = String::from("digraph reactor {\n");
+        for (index, cell) in self.input_cells.iter().enumerate() {
+            let label = escape_dot(&cell.value.to_string());
+            writeln!(
+                dot,
+                "    {} [shape=box, label=\"{}\"];",
+                self.input_id(index),
+                label
+            )
+            .unwrap();
+        }
+        for (index, cell) in self.compute_cells.iter().enumerate() {
+            let id = self.compute_id(index);
+            let label = escape_dot(&format!("{} = {}", id, cell.value));
+            writeln!(dot, "    {} [shape=ellipse, label=\"{}\"];", id, label).unwrap();
+        }
+        for (dependency, dependent) in self.export_edges() {
+            writeln!(dot, "    {} -> {};", dependency, dependent).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+// Shows every cell in creation order: input cells with their value, compute cells with their
+// dependencies and value.
+impl<'a, T: Copy + PartialEq + Default + fmt::Debug> fmt::Debug for Reactor<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Inputs<'r, 'a, T>(&'r [InputCell<'a, T>], u64);
+        impl<T: fmt::Debug> fmt::Debug for Inputs<'_, '_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_map()
+                    .entries(self.0.iter().enumerate().map(|(index, cell)| {
+                        (
+                            Plain(InputCellId {
+                                reactor: self.1,
+                                index,
+                            }),
+                            &cell.value,
+                        )
+                    }))
+                    .finish()
+            }
+        }
+        struct Computes<'r, 'a, T>(&'r [ComputeCell<'a, T>], u64);
+        impl<T: fmt::Debug> fmt::Debug for Computes<'_, '_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut map = f.debug_map();
+                for (index, cell) in self.0.iter().enumerate() {
+                    let dependencies: Vec<_> = cell.dependencies.iter().map(Plain).collect();
+                    map.key(&Plain(ComputeCellId {
+                        reactor: self.1,
+                        index,
+                    }))
+                    .value(&format_args!("{:?} <- {:?}", cell.value, dependencies));
+                }
+                map.finish()
+            }
+        }
+        f.debug_struct("Reactor")
+            .field("inputs", &Inputs(&self.input_cells, self.tag))
+            .field("computes", &Computes(&self.compute_cells, self.tag))
+            .finish()
+    }
+}
+// Formats a value with `Display` wherever `Debug` is expected.
+struct Plain<D>(D);
+impl<D: fmt::Display> fmt::Debug for Plain<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+// Escapes a label so it can be written between double quotes in DOT.
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+impl<'a, T: Copy + Eq + Hash + Default + 'a> Reactor<'a, T> {
+    // Creates a compute cell like `create_compute`, but remembers the results of the last
+    // `cache_capacity` distinct dependency values so `compute_func` isn't called again for them.
+    //
+    // Change propagation is unaffected: a cached result is compared against the previous value
+    // exactly like a freshly computed one, so callbacks fire the same way.
+    pub fn create_compute_memoized<F: 'a + Fn(&[T]) -> T>(
+        &mut self,
+        dependencies: &[CellId],
+        cache_capacity: usize,
+        compute_func: F,
+    ) -> Result<ComputeCellId, CellId> {
+        let stats = Rc::new(Cell::new(CacheStats::default()));
+        let cache = RefCell::new(LruCache::new(cache_capacity));
+        let cell_stats = Rc::clone(&stats);
+        let id = self.create_compute(dependencies, move |values: &[T]| {
+            let mut current = cell_stats.get();
+            let cached = cache.borrow_mut().get(&values.to_vec());
+            let value = match cached {
+                Some(value) => {
+                    current.hits += 1;
+                    value
+                }
+                None => {
+                    current.misses += 1;
+                    let value = compute_func(values);
+                    cache.borrow_mut().insert(values.to_vec(), value);
+                    value
+                }
+            };
+            cell_stats.set(current);
+            value
+        })?;
+        self.compute_cells[id.index].cache_stats = Some(stats);
+        Ok(id)
+    }
+}
+type SharedCallback<T> = Arc<Mutex<Box<dyn FnMut(T) + Send>>>;
+// Each callback due to be called, with the value it should see.
+type Pending<T> = Arc<Mutex<Vec<(SharedCallback<T>, T)>>>;
+// The reactor behind a `SharedReactor`, along with the callbacks its last propagation triggered.
+struct SharedState<T: Default> {
+    reactor: Reactor<'static, T>,
+    pending: Pending<T>,
+}
+// SAFETY: `Reactor` isn't `Send` only because it boxes closures without requiring them to be
+// `Send`, and because memoized cells share their statistics through an `Rc`. `SharedReactor`
+// only ever hands it closures that are `Send`, and never creates memoized cells.
+unsafe impl<T: Default + Send> Send for SharedState<T> {}
+// A `Reactor` that several threads can use at once, behind a mutex.
+//
+// Callbacks added through `add_callback` are not called while the mutex is held. Propagation only
+// records which callbacks should see which value; they're called once the mutex is released, so a
+// callback may itself call any method here, for example to set another input. The one thing a
+// callback must not do is cause itself to be called again, which would wait on itself forever.
+//
+// When several threads set values at the same time, each callback still sees every change to its
+// cell exactly once, but not necessarily in the order the changes happened.
+pub struct SharedReactor<T: Default> {
+    state: Mutex<SharedState<T>>,
+}
+impl<T: Copy + PartialEq + Default + Send + 'static> Default for SharedReactor<T> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(SharedState {
+                reactor: Reactor::new(),
+                pending: Arc::new(Mutex::new(Vec::new())),
+            }),
+        }
+    }
+}
+impl<T: Copy + PartialEq + Default + Send + 'static> SharedReactor<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // Like `Reactor::create_input`.
+    pub fn create_input(&self, initial: T) -> InputCellId {
+        self.state.lock().unwrap().reactor.create_input(initial)
+    }
+    // Like `Reactor::create_compute`. The compute function runs with the mutex held, so it must
+    // not call back into this reactor.
+    pub fn create_compute<F: Fn(&[T]) -> T + Send + 'static>(
+        &self,
+        dependencies: &[CellId],
+        compute_func: F,
+    ) -> Result<ComputeCellId, CellId> {
+        let mut state = self.state.lock().unwrap();
+        state.reactor.create_compute(dependencies, compute_func)
+    }
+    // Like `Reactor::value`.
+    pub fn value(&self, id: CellId) -> Option<T> {
+        self.state.lock().unwrap().reactor.value(id)
+    }
+    // Like `Reactor::set_value`, except that the callbacks are called after the mutex is
+    // released.
+    pub fn set_value(&self, id: InputCellId, new_value: T) -> bool {
+        let (set, pending) = {
+            let mut state = self.state.lock().unwrap();
+            let set = state.reactor.set_value(id, new_value);
+            let pending = mem::take(&mut *state.pending.lock().unwrap());
+            (set, pending)
+        };
+        for (callback, value) in pending {
+            (callback.lock().unwrap())(value);
+        }
+        set
+    }
+    // Like `Reactor::add_callback`, with the callback called outside the mutex.
+    pub fn add_callback<F: FnMut(T) + Send + 'static>(
+        &self,
+        id: ComputeCellId,
+        callback: F,
+    ) -> Option<CallbackId> {
+        let callback: SharedCallback<T> = Arc::new(Mutex::new(Box::new(callback)));
+        let mut state = self.state.lock().unwrap();
+        let pending = Arc::clone(&state.pending);
+        state.reactor.add_callback(id, move |value| {
+            pending.lock().unwrap().push((Arc::clone(&callback), value));
+        })
+    }
+    // Like `Reactor::remove_callback`.
+    pub fn remove_callback(
+        &self,
+        cell: ComputeCellId,
+        callback: CallbackId,
+    ) -> Result<(), RemoveCallbackError> {
+        let mut state = self.state.lock().unwrap();
+        state.reactor.remove_callback(cell, callback)
+    }
 }
 
 #[test]
@@ -644,3 +1220,726 @@ fn test_adder_with_boolean_values() {
         );
     }
 }
+
+#[test]
+fn memoized_compute_cells_skip_recomputing_seen_values() {
+    let calls = std::cell::Cell::new(0);
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute_memoized(&[CellId::Input(input)], 4, |v| {
+            calls.set(calls.get() + 1);
+            v[0] * 10
+        })
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(20);
+    for _ in 0..5 {
+        assert!(reactor.set_value(input, 1));
+        cb.expect_to_have_been_called_with(10);
+        assert!(reactor.set_value(input, 2));
+        cb.expect_to_have_been_called_with(20);
+    }
+
+    assert_eq!(calls.get(), 2);
+    assert_eq!(reactor.cache_stats(output), Some((10, 2)));
+}
+
+#[test]
+fn memoized_compute_cells_evict_least_recently_used_values() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute_memoized(&[CellId::Input(input)], 2, |v| {
+            calls.set(calls.get() + 1);
+            v[0] + 1
+        })
+        .unwrap();
+
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 1));
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(calls.get(), 3);
+
+    // 2 was the least recently used value when 3 was inserted.
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(calls.get(), 4);
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(3));
+    assert_eq!(reactor.cache_stats(output), Some((1, 4)));
+}
+
+#[test]
+fn cache_stats_are_only_reported_for_memoized_cells() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    assert_eq!(reactor.cache_stats(output), None);
+    assert_eq!(Reactor::<i32>::new().cache_stats(output), None);
+}
+
+#[cfg(test)]
+fn adder_circuit() -> (Reactor<'static, bool>, [InputCellId; 3], [ComputeCellId; 5]) {
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(false);
+    let b = reactor.create_input(false);
+    let carry_in = reactor.create_input(false);
+
+    let a_xor_b = reactor
+        .create_compute(&[CellId::Input(a), CellId::Input(b)], |v| v[0] ^ v[1])
+        .unwrap();
+    let sum = reactor
+        .create_compute(&[CellId::Compute(a_xor_b), CellId::Input(carry_in)], |v| {
+            v[0] ^ v[1]
+        })
+        .unwrap();
+    let a_xor_b_and_cin = reactor
+        .create_compute(&[CellId::Compute(a_xor_b), CellId::Input(carry_in)], |v| {
+            v[0] && v[1]
+        })
+        .unwrap();
+    let a_and_b = reactor
+        .create_compute(&[CellId::Input(a), CellId::Input(b)], |v| v[0] && v[1])
+        .unwrap();
+    let carry_out = reactor
+        .create_compute(
+            &[CellId::Compute(a_xor_b_and_cin), CellId::Compute(a_and_b)],
+            |v| v[0] || v[1],
+        )
+        .unwrap();
+
+    (
+        reactor,
+        [a, b, carry_in],
+        [a_xor_b, sum, a_xor_b_and_cin, a_and_b, carry_out],
+    )
+}
+
+#[test]
+fn export_edges_lists_every_dependency() {
+    let (reactor, [a, b, carry_in], [a_xor_b, sum, a_xor_b_and_cin, a_and_b, carry_out]) =
+        adder_circuit();
+
+    let edges: HashSet<_> = reactor.export_edges().into_iter().collect();
+    let expected: HashSet<_> = [
+        (CellId::Input(a), a_xor_b),
+        (CellId::Input(b), a_xor_b),
+        (CellId::Compute(a_xor_b), sum),
+        (CellId::Input(carry_in), sum),
+        (CellId::Compute(a_xor_b), a_xor_b_and_cin),
+        (CellId::Input(carry_in), a_xor_b_and_cin),
+        (CellId::Input(a), a_and_b),
+        (CellId::Input(b), a_and_b),
+        (CellId::Compute(a_xor_b_and_cin), carry_out),
+        (CellId::Compute(a_and_b), carry_out),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(edges, expected);
+    assert_eq!(reactor.export_edges().len(), 10);
+}
+
+#[test]
+fn export_dot_declares_each_node_once() {
+    let (mut reactor, [a, _, _], computes) = adder_circuit();
+    assert!(reactor.set_value(a, true));
+
+    let dot = reactor.export_dot();
+    assert!(dot.starts_with("digraph reactor {\n"));
+    assert!(dot.ends_with("}\n"));
+
+    let declarations: Vec<&str> = dot.lines().filter(|line| line.contains('[')).collect();
+    assert_eq!(declarations.len(), 8);
+    for node in ["input0", "input1", "input2"] {
+        let prefix = format!("    {} [shape=box", node);
+        assert_eq!(
+            declarations
+                .iter()
+                .filter(|line| line.starts_with(&prefix))
+                .count(),
+            1
+        );
+    }
+    for id in computes {
+        let prefix = format!("    {} [shape=ellipse", id);
+        assert_eq!(
+            declarations
+                .iter()
+                .filter(|line| line.starts_with(&prefix))
+                .count(),
+            1
+        );
+    }
+
+    assert!(dot.contains("    input0 [shape=box, label=\"true\"];"));
+    assert!(dot.contains("    compute0 [shape=ellipse, label=\"compute0 = true\"];"));
+    assert!(dot.contains("    compute4 [shape=ellipse, label=\"compute4 = false\"];"));
+    assert!(dot.contains("    compute3 -> compute4;"));
+    assert_eq!(dot.matches(" -> ").count(), 10);
+}
+
+#[test]
+fn cell_ids_display_their_creation_index() {
+    let mut reactor = Reactor::new();
+    let first = reactor.create_input(1);
+    let second = reactor.create_input(2);
+    let compute = reactor
+        .create_compute(&[CellId::Input(first), CellId::Input(second)], |v| {
+            v[0] + v[1]
+        })
+        .unwrap();
+    assert_eq!(first.to_string(), "input0");
+    assert_eq!(CellId::Input(second).to_string(), "input1");
+    assert_eq!(CellId::Compute(compute).to_string(), "compute0");
+}
+
+#[test]
+fn export_dot_escapes_labels() {
+    assert_eq!(escape_dot(r#"say "hi" \ bye"#), r#"say \"hi\" \\ bye"#);
+}
+
+#[test]
+fn depends_on_lists_direct_dependencies() {
+    let (reactor, [_, _, carry_in], [a_xor_b, _, a_xor_b_and_cin, a_and_b, carry_out]) =
+        adder_circuit();
+    assert_eq!(
+        reactor.depends_on(carry_out),
+        Some(vec![
+            CellId::Compute(a_xor_b_and_cin),
+            CellId::Compute(a_and_b)
+        ])
+    );
+    assert_eq!(
+        reactor.depends_on(a_xor_b_and_cin),
+        Some(vec![CellId::Compute(a_xor_b), CellId::Input(carry_in)])
+    );
+}
+
+#[test]
+fn depends_on_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let output = dummy_reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0])
+        .unwrap();
+    assert_eq!(Reactor::<i32>::new().depends_on(output), None);
+}
+
+#[test]
+fn dependents_of_lists_direct_dependents() {
+    let (reactor, [a, _, carry_in], [a_xor_b, sum, a_xor_b_and_cin, a_and_b, carry_out]) =
+        adder_circuit();
+    assert_eq!(
+        reactor.dependents_of(CellId::Input(a)),
+        vec![a_xor_b, a_and_b]
+    );
+    assert_eq!(
+        reactor.dependents_of(CellId::Input(carry_in)),
+        vec![sum, a_xor_b_and_cin]
+    );
+    assert_eq!(
+        reactor.dependents_of(CellId::Compute(a_xor_b)),
+        vec![sum, a_xor_b_and_cin]
+    );
+    assert!(reactor.dependents_of(CellId::Compute(carry_out)).is_empty());
+}
+
+#[test]
+fn cell_ids_are_listed_in_creation_order() {
+    let (reactor, inputs, computes) = adder_circuit();
+    assert_eq!(reactor.input_cell_ids(), inputs.to_vec());
+    assert_eq!(reactor.compute_cell_ids(), computes.to_vec());
+    assert!(Reactor::<i32>::new().input_cell_ids().is_empty());
+}
+
+#[test]
+fn debug_shows_every_cell_value() {
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(5);
+    let b = reactor.create_input(3);
+    let sum = reactor
+        .create_compute(&[CellId::Input(a), CellId::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", reactor),
+        "Reactor { inputs: {input0: 5, input1: 3}, computes: {compute0: 8 <- [input0, input1]} }"
+    );
+    assert!(reactor.set_value(b, 10));
+    assert_eq!(reactor.value(CellId::Compute(sum)), Some(15));
+    assert_eq!(
+        format!("{:?}", reactor),
+        "Reactor { inputs: {input0: 5, input1: 10}, computes: {compute0: 15 <- [input0, input1]} }"
+    );
+}
+
+#[test]
+fn debug_shows_each_cell_once() {
+    let (mut reactor, [a, _, carry_in], _) = adder_circuit();
+    assert!(reactor.set_value(a, true));
+    assert!(reactor.set_value(carry_in, true));
+    let debug = format!("{:?}", reactor);
+    for (id, value) in reactor.values() {
+        let entry = format!("{}: {}", id, value);
+        assert_eq!(debug.matches(&entry).count(), 1, "{entry} in {debug}");
+    }
+    assert!(debug.contains("compute1: false <- [compute0, input2]"));
+    assert!(debug.contains("compute4: true <- [compute2, compute3]"));
+}
+
+#[test]
+fn values_match_value() {
+    let (mut reactor, [a, b, _], _) = adder_circuit();
+    assert!(reactor.set_value(a, true));
+    assert!(reactor.set_value(b, true));
+    let values = reactor.values();
+    assert_eq!(values.len(), reactor.cell_count());
+    for (id, value) in values {
+        assert_eq!(reactor.value(id), Some(value));
+    }
+    assert_eq!(reactor.input_count(), 3);
+    assert_eq!(reactor.compute_count(), 5);
+    assert_eq!(reactor.cell_count(), 8);
+    assert!(Reactor::<i32>::new().values().is_empty());
+}
+
+#[test]
+fn observe_all_sees_every_changed_cell() {
+    let seen = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let plus_one = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let times_two = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    reactor
+        .create_compute(&[CellId::Input(input)], |_| 0)
+        .unwrap();
+    let id = reactor.observe_all(|cell, value| seen.borrow_mut().push((cell, value)));
+    assert!(reactor.set_value(input, 5));
+    assert!(reactor.remove_observe_all(id));
+    assert!(!reactor.remove_observe_all(id));
+    assert!(reactor.set_value(input, 7));
+    drop(reactor);
+    assert_eq!(seen.into_inner(), vec![(plus_one, 6), (times_two, 10)]);
+}
+
+#[test]
+fn observe_all_runs_after_cell_callbacks() {
+    let order = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    reactor.observe_all(|_, _| order.borrow_mut().push("observer"));
+    reactor
+        .add_callback(output, |_| order.borrow_mut().push("callback"))
+        .unwrap();
+    assert!(reactor.set_value(input, 2));
+    drop(reactor);
+    assert_eq!(order.into_inner(), vec!["callback", "observer"]);
+}
+
+#[test]
+fn ids_from_another_reactor_are_rejected() {
+    let mut first = Reactor::new();
+    let mut second = Reactor::new();
+    let mut inputs = Vec::new();
+    let mut computes = Vec::new();
+    for value in 0..10 {
+        for reactor in [&mut first, &mut second] {
+            let input = reactor.create_input(value);
+            let compute = reactor
+                .create_compute(&[CellId::Input(input)], |v| v[0] * 10)
+                .unwrap();
+            let callback = reactor.add_callback(compute, |_| ()).unwrap();
+            inputs.push(input);
+            computes.push((compute, callback));
+        }
+    }
+    for (i, (&input, &(compute, callback))) in inputs.iter().zip(&computes).enumerate() {
+        let (owner, other) = if i % 2 == 0 {
+            (&mut first, &mut second)
+        } else {
+            (&mut second, &mut first)
+        };
+        // The same indices exist in both reactors, but only the owner accepts them.
+        assert_eq!(owner.value(CellId::Input(input)), Some(i as i32 / 2));
+        assert_eq!(other.value(CellId::Input(input)), None);
+        assert_eq!(other.value(CellId::Compute(compute)), None);
+        assert!(!other.set_value(input, 100));
+        assert_eq!(
+            other.create_compute(&[CellId::Compute(compute)], |v| v[0]),
+            Err(CellId::Compute(compute))
+        );
+        assert_eq!(other.add_callback(compute, |_| ()), None);
+        assert_eq!(
+            other.remove_callback(compute, callback),
+            Err(RemoveCallbackError::NonexistentCell)
+        );
+        let own_compute = other.compute_cell_ids()[i / 2];
+        assert_eq!(
+            other.remove_callback(own_compute, callback),
+            Err(RemoveCallbackError::NonexistentCallback)
+        );
+        assert_eq!(other.depends_on(compute), None);
+        assert!(other.dependents_of(CellId::Input(input)).is_empty());
+        assert_eq!(owner.remove_callback(compute, callback), Ok(()));
+    }
+    assert_eq!(first.compute_count(), 10);
+    assert_eq!(second.compute_count(), 10);
+}
+
+#[test]
+fn observe_all_ids_from_another_reactor_are_rejected() {
+    let mut first = Reactor::<i32>::new();
+    let mut second = Reactor::<i32>::new();
+    let id = first.observe_all(|_, _| ());
+    second.observe_all(|_, _| ());
+    assert!(!second.remove_observe_all(id));
+    assert!(first.remove_observe_all(id));
+}
+
+#[test]
+fn batch_remove_callbacks_removes_each_pair() {
+    let calls = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let cells: Vec<ComputeCellId> = (1..=3)
+        .map(|n| {
+            reactor
+                .create_compute(&[CellId::Input(input)], move |v| v[0] * n)
+                .unwrap()
+        })
+        .collect();
+    let calls = &calls;
+    let callbacks: Vec<(ComputeCellId, CallbackId)> = [0, 0, 1, 1, 2]
+        .iter()
+        .enumerate()
+        .map(|(name, &cell)| {
+            let id = reactor
+                .add_callback(cells[cell], move |_| calls.borrow_mut().push(name))
+                .unwrap();
+            (cells[cell], id)
+        })
+        .collect();
+    let removed = [callbacks[0], callbacks[2], callbacks[4], callbacks[0]];
+    assert_eq!(
+        reactor.batch_remove_callbacks(&removed),
+        vec![
+            Ok(()),
+            Ok(()),
+            Ok(()),
+            Err(RemoveCallbackError::NonexistentCallback)
+        ]
+    );
+    assert!(reactor.set_value(input, 2));
+    drop(reactor);
+    let mut calls = calls.take();
+    calls.sort();
+    assert_eq!(calls, vec![1, 3]);
+}
+
+#[test]
+fn compute_cell_with_initial_starts_at_initial_without_computing() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    // Dividing by the placeholder would panic.
+    let output = reactor
+        .create_compute_with_initial(&[CellId::Input(input)], -1, |v| 100 / v[0])
+        .unwrap();
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(-1));
+    let doubled = reactor
+        .create_compute(&[CellId::Compute(output)], |v| v[0] * 2)
+        .unwrap();
+    assert_eq!(reactor.value(CellId::Compute(doubled)), Some(-2));
+}
+
+#[test]
+fn compute_cell_with_initial_rejects_nonexistent_dependencies() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let mut reactor = Reactor::new();
+    assert_eq!(
+        reactor.create_compute_with_initial(&[CellId::Input(input)], 0, |_| 0),
+        Err(CellId::Input(input))
+    );
+}
+
+#[test]
+fn compute_cell_with_initial_is_computed_on_first_propagation() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute_with_initial(&[CellId::Input(input)], -1, |v| 100 / v[0])
+        .unwrap();
+    let doubled = reactor
+        .create_compute(&[CellId::Compute(output)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+    assert!(reactor.set_value(input, 4));
+    cb.expect_to_have_been_called_with(25);
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(25));
+    assert_eq!(reactor.value(CellId::Compute(doubled)), Some(50));
+    assert!(reactor.set_value(input, 5));
+    cb.expect_to_have_been_called_with(20);
+}
+
+#[test]
+fn first_computed_value_equal_to_initial_is_not_a_change() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute_with_initial(&[CellId::Input(input)], 10, |v| v[0] * 10)
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+    assert!(reactor.set_value(input, 1));
+    cb.expect_not_to_have_been_called();
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(20);
+}
+
+#[test]
+fn compute_cell_with_initial_waits_for_a_change_upstream() {
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(1);
+    let constant = reactor.create_compute(&[CellId::Input(a)], |_| 7).unwrap();
+    let output = reactor
+        .create_compute_with_initial(&[CellId::Compute(constant), CellId::Input(b)], 0, |v| {
+            v[0] + v[1]
+        })
+        .unwrap();
+    // `constant` doesn't change, so nothing reaches `output`.
+    assert!(reactor.set_value(a, 2));
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(0));
+    assert!(reactor.set_value(b, 3));
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(10));
+}
+
+#[test]
+fn recompute_now_evaluates_without_an_input_change() {
+    let cb = CallbackRecorder::new();
+    let observed = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(3);
+    let output = reactor
+        .create_compute_with_initial(&[CellId::Input(input)], 0, |v| v[0] + 1)
+        .unwrap();
+    let doubled = reactor
+        .create_compute(&[CellId::Compute(output)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+    reactor.observe_all(|id, value| observed.borrow_mut().push((id, value)));
+    assert!(reactor.recompute_now(output));
+    cb.expect_to_have_been_called_with(4);
+    assert_eq!(reactor.value(CellId::Compute(doubled)), Some(8));
+    // Nothing to do the second time round.
+    assert!(reactor.recompute_now(output));
+    cb.expect_not_to_have_been_called();
+    drop(reactor);
+    assert_eq!(observed.take(), vec![(output, 4), (doubled, 8)]);
+}
+
+#[test]
+fn recompute_now_on_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let output = dummy_reactor
+        .create_compute(&[CellId::Input(input)], |_| 0)
+        .unwrap();
+    let mut reactor = Reactor::<i32>::new();
+    assert!(!reactor.recompute_now(output));
+}
+
+#[test]
+fn input_callbacks_fire_on_every_set_value() {
+    let values = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert!(reactor
+        .subscribe_to_input(input, |v| values.borrow_mut().push(v))
+        .is_some());
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 5));
+    drop(reactor);
+    assert_eq!(values.take(), vec![2, 2, 5]);
+}
+
+#[test]
+fn input_callbacks_fire_before_compute_callbacks() {
+    let order = RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] * 10)
+        .unwrap();
+    let other = reactor.create_input(0);
+    assert!(reactor
+        .add_callback(output, |v| order.borrow_mut().push(("output", v)))
+        .is_some());
+    assert!(reactor
+        .subscribe_to_input(input, |v| order.borrow_mut().push(("input", v)))
+        .is_some());
+    assert!(reactor
+        .subscribe_to_input(other, |v| order.borrow_mut().push(("other", v)))
+        .is_some());
+    assert!(reactor.set_value(input, 3));
+    drop(reactor);
+    assert_eq!(order.take(), vec![("input", 3), ("output", 30)]);
+}
+
+#[test]
+fn input_callbacks_can_be_removed() {
+    let cb1 = CallbackRecorder::new();
+    let cb2 = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let callback = reactor
+        .subscribe_to_input(input, |v| cb1.callback_called(v))
+        .unwrap();
+    assert!(reactor
+        .subscribe_to_input(input, |v| cb2.callback_called(v))
+        .is_some());
+    assert!(reactor.set_value(input, 2));
+    cb1.expect_to_have_been_called_with(2);
+    cb2.expect_to_have_been_called_with(2);
+    assert_eq!(reactor.unsubscribe_from_input(input, callback), Ok(()));
+    assert_eq!(
+        reactor.unsubscribe_from_input(input, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert!(reactor.set_value(input, 3));
+    cb1.expect_not_to_have_been_called();
+    cb2.expect_to_have_been_called_with(3);
+}
+
+#[test]
+fn subscribing_to_a_nonexistent_input() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let callback = dummy_reactor.subscribe_to_input(input, |_| {}).unwrap();
+    let mut reactor = Reactor::<i32>::new();
+    assert_eq!(reactor.subscribe_to_input(input, |_| {}), None);
+    assert_eq!(
+        reactor.unsubscribe_from_input(input, callback),
+        Err(RemoveCallbackError::NonexistentCell)
+    );
+    let own = reactor.create_input(1);
+    assert_eq!(
+        reactor.unsubscribe_from_input(own, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+}
+
+#[test]
+fn shared_reactor_is_send_and_sync() {
+    fn assert_send_sync<S: Send + Sync>() {}
+    assert_send_sync::<SharedReactor<i32>>();
+}
+
+#[test]
+fn shared_reactor_mirrors_reactor() {
+    let reactor = SharedReactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellId::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&calls);
+    let callback = reactor
+        .add_callback(output, move |value| recorded.lock().unwrap().push(value))
+        .unwrap();
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(2));
+    assert!(reactor.set_value(input, 3));
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(reactor.value(CellId::Compute(output)), Some(4));
+    assert_eq!(*calls.lock().unwrap(), [4]);
+    assert_eq!(reactor.remove_callback(output, callback), Ok(()));
+    assert!(reactor.set_value(input, 5));
+    assert_eq!(*calls.lock().unwrap(), [4]);
+    assert_eq!(
+        reactor.remove_callback(output, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    let other = Reactor::<i32>::new().create_input(0);
+    assert!(!reactor.set_value(other, 1));
+}
+
+#[test]
+fn shared_reactor_from_two_threads() {
+    const UPDATES: i32 = 2_000;
+    let reactor = SharedReactor::new();
+    let a = reactor.create_input(0);
+    let b = reactor.create_input(0);
+    let sum = reactor
+        .create_compute(&[CellId::Input(a), CellId::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    let calls = Arc::new(Mutex::new(0));
+    let counted = Arc::clone(&calls);
+    reactor
+        .add_callback(sum, move |_| *counted.lock().unwrap() += 1)
+        .unwrap();
+    std::thread::scope(|scope| {
+        for (input, sign) in [(a, 1), (b, -10)] {
+            let reactor = &reactor;
+            scope.spawn(move || {
+                for value in 1..=UPDATES {
+                    assert!(reactor.set_value(input, sign * value));
+                }
+            });
+        }
+    });
+    assert_eq!(reactor.value(CellId::Input(a)), Some(UPDATES));
+    assert_eq!(reactor.value(CellId::Input(b)), Some(-10 * UPDATES));
+    assert_eq!(reactor.value(CellId::Compute(sum)), Some(-9 * UPDATES));
+    // Every update changes the sum, since the two inputs move by different steps.
+    assert_eq!(*calls.lock().unwrap(), 2 * UPDATES);
+}
+
+#[test]
+fn shared_reactor_callback_can_set_another_input() {
+    let reactor = Arc::new(SharedReactor::new());
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(0);
+    let doubled = reactor
+        .create_compute(&[CellId::Input(a)], |v| v[0] * 2)
+        .unwrap();
+    let scaled = reactor
+        .create_compute(&[CellId::Input(b)], |v| v[0] * 10)
+        .unwrap();
+    let inner = Arc::clone(&reactor);
+    reactor
+        .add_callback(doubled, move |value| {
+            assert!(inner.set_value(b, value + 1));
+            assert_eq!(inner.value(CellId::Compute(scaled)), Some((value + 1) * 10));
+        })
+        .unwrap();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&calls);
+    reactor
+        .add_callback(scaled, move |value| recorded.lock().unwrap().push(value))
+        .unwrap();
+    assert!(reactor.set_value(a, 5));
+    assert_eq!(reactor.value(CellId::Input(b)), Some(11));
+    assert_eq!(reactor.value(CellId::Compute(scaled)), Some(110));
+    assert_eq!(*calls.lock().unwrap(), [110]);
+}
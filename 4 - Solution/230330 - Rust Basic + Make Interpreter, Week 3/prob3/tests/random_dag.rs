@@ -0,0 +1,215 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use prob3::{CellId, Reactor};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use proptest::test_runner::RngSeed;
+
+/// How a compute cell combines its dependencies. The reactor and the oracle
+/// both call `apply`, so they can only disagree about propagation.
+#[derive(Debug, Clone, Copy)]
+enum Formula {
+    Sum(i32),
+    Product,
+    Max,
+    Difference,
+    // Changes far less often than its inputs, so some updates stop here.
+    Threshold(i32),
+}
+
+impl Formula {
+    fn apply(self, values: &[i32]) -> i32 {
+        match self {
+            Formula::Sum(offset) => values.iter().fold(offset, |acc, v| acc.wrapping_add(*v)),
+            Formula::Product => values.iter().fold(1, |acc: i32, v| acc.wrapping_mul(*v)),
+            Formula::Max => *values.iter().max().unwrap(),
+            Formula::Difference => values[1..]
+                .iter()
+                .fold(values[0], |acc, v| acc.wrapping_sub(*v)),
+            Formula::Threshold(limit) => {
+                i32::from(values.iter().fold(0, |acc: i32, v| acc.wrapping_add(*v)) > limit)
+            }
+        }
+    }
+}
+
+/// A compute cell as the oracle sees it: indices into the list of all
+/// cells, inputs first, and its formula.
+struct Node {
+    dependencies: Vec<usize>,
+    formula: Formula,
+}
+
+/// Everything a run does, generated up front. Each `Index` picks among the
+/// cells that exist at that point, so dependencies always come earlier.
+#[derive(Debug)]
+struct Plan {
+    inputs: Vec<i32>,
+    // The dependencies and formula of each compute cell, and whether it
+    // gets a recording callback.
+    computes: Vec<(Vec<Index>, Formula, bool)>,
+    // Which input to set, and to what.
+    updates: Vec<(Index, i32)>,
+}
+
+fn value() -> impl Strategy<Value = i32> {
+    -10..=10
+}
+
+fn formula() -> impl Strategy<Value = Formula> {
+    prop_oneof![
+        value().prop_map(Formula::Sum),
+        Just(Formula::Product),
+        Just(Formula::Max),
+        Just(Formula::Difference),
+        value().prop_map(Formula::Threshold),
+    ]
+}
+
+fn plan(updates: usize) -> impl Strategy<Value = Plan> {
+    (
+        prop::collection::vec(value(), 1..=5),
+        prop::collection::vec(
+            (
+                prop::collection::vec(any::<Index>(), 1..=4),
+                formula(),
+                any::<bool>(),
+            ),
+            1..=30,
+        ),
+        prop::collection::vec((any::<Index>(), value()), updates),
+    )
+        .prop_map(|(inputs, computes, updates)| Plan {
+            inputs,
+            computes,
+            updates,
+        })
+}
+
+/// Recomputes every cell from scratch in creation order, which is a
+/// topological order since cells can only depend on earlier ones.
+fn oracle(inputs: &[i32], nodes: &[Node]) -> Vec<i32> {
+    let mut values = inputs.to_vec();
+
+    for node in nodes {
+        let dependencies: Vec<i32> = node.dependencies.iter().map(|&i| values[i]).collect();
+        values.push(node.formula.apply(&dependencies));
+    }
+
+    values
+}
+
+fn run_random_dag(plan: &Plan) -> Result<(), TestCaseError> {
+    let mut reactor = Reactor::new();
+    let mut inputs = plan.inputs.clone();
+
+    let mut input_ids = Vec::new();
+    let mut cells = Vec::new();
+    for &value in &inputs {
+        let id = reactor.create_input(value);
+        input_ids.push(id);
+        cells.push(CellId::Input(id));
+    }
+
+    let mut nodes = Vec::new();
+    let mut compute_ids = Vec::new();
+    for (picks, formula, _) in &plan.computes {
+        let formula = *formula;
+        let dependencies: Vec<usize> = picks.iter().map(|pick| pick.index(cells.len())).collect();
+
+        let ids: Vec<CellId> = dependencies.iter().map(|&i| cells[i]).collect();
+        let id = reactor
+            .create_compute(&ids, move |values| formula.apply(values))
+            .unwrap();
+
+        nodes.push(Node {
+            dependencies,
+            formula,
+        });
+        compute_ids.push(id);
+        cells.push(CellId::Compute(id));
+    }
+
+    let mut recorders = Vec::new();
+    for (i, (&id, (_, _, recorded))) in compute_ids.iter().zip(&plan.computes).enumerate() {
+        if *recorded {
+            let calls = Rc::new(RefCell::new(Vec::new()));
+            let recorded = Rc::clone(&calls);
+            reactor
+                .add_callback(id, move |value| recorded.borrow_mut().push(value))
+                .unwrap();
+            recorders.push((inputs.len() + i, calls));
+        }
+    }
+
+    let mut expected = oracle(&inputs, &nodes);
+    for (i, &cell) in cells.iter().enumerate() {
+        prop_assert_eq!(reactor.value(cell), Some(expected[i]), "cell {}", i);
+    }
+
+    for (update, (pick, value)) in plan.updates.iter().enumerate() {
+        let input = pick.index(inputs.len());
+
+        prop_assert!(reactor.set_value(input_ids[input], *value));
+        inputs[input] = *value;
+
+        let previous = expected;
+        expected = oracle(&inputs, &nodes);
+
+        for (i, &cell) in cells.iter().enumerate() {
+            prop_assert_eq!(
+                reactor.value(cell),
+                Some(expected[i]),
+                "update {}, cell {}",
+                update,
+                i
+            );
+        }
+
+        for (i, calls) in &recorders {
+            let want = if previous[*i] != expected[*i] {
+                vec![expected[*i]]
+            } else {
+                Vec::new()
+            };
+
+            prop_assert_eq!(
+                calls.replace(Vec::new()),
+                want,
+                "update {}, callback on cell {}",
+                update,
+                i
+            );
+        }
+    }
+
+    Ok(())
+}
+
+proptest! {
+    // A fixed seed, so every run checks the same graphs.
+    #![proptest_config(ProptestConfig {
+        cases: 50,
+        rng_seed: RngSeed::Fixed(635),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn random_dags_match_full_recompute(plan in plan(300)) {
+        run_random_dag(&plan)?;
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 2_000,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    #[ignore]
+    fn random_dags_match_full_recompute_stress(plan in plan(1_000)) {
+        run_random_dag(&plan)?;
+    }
+}